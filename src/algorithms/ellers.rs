@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+
+use crate::prelude::*;
+
+// CarvedRow : The passages of one row of a maze carved by Eller's algorithm, those leading
+// east out of each cell and those leading south into the row below.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CarvedRow {
+    pub y: usize,
+    pub east: Vec<bool>,
+    pub south: Vec<bool>,
+}
+
+// Ellers : Eller's algorithm, carving a `width` by `height` maze a row at a time. Only the set
+// each cell of the latest row belongs to is remembered, so a maze of any height is carved in
+// the memory of a single row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ellers {
+    width: usize,
+    height: usize,
+    y: usize,
+    // the set of each cell of the next row, the cells in the same set being joined through
+    // the rows already carved.
+    sets: Vec<usize>,
+}
+
+impl Ellers {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            y: 0,
+            sets: (0..width).collect(),
+        }
+    }
+
+    // the set `set` was merged into, shortening the way there as it goes.
+    fn find(parents: &mut [usize], set: usize) -> usize {
+        let mut root = set;
+
+        while parents[root] != root {
+            root = parents[root];
+        }
+
+        let mut set = set;
+
+        while parents[set] != root {
+            let next = parents[set];
+            parents[set] = root;
+            set = next;
+        }
+
+        return root;
+    }
+}
+
+impl Iterator for Ellers {
+    type Item = CarvedRow;
+
+    fn next(&mut self) -> Option<CarvedRow> {
+        if self.y >= self.height {
+            return None;
+        }
+
+        let mut random = random::rng();
+        let last = self.y + 1 == self.height;
+        // sets are numbered below the width, so a row's merges fit in a list that size.
+        let mut parents = (0..self.width).collect::<Vec<usize>>();
+        let mut east = vec![false; self.width];
+
+        // neighbors in different sets are joined at random, or always on the last row so
+        // every set ends up as one.
+        for x in 1..self.width {
+            let (a, b) = (
+                Ellers::find(&mut parents, self.sets[x - 1]),
+                Ellers::find(&mut parents, self.sets[x]),
+            );
+
+            if a != b && (last || random.gen_bool(0.5)) {
+                parents[b] = a;
+                east[x - 1] = true;
+            }
+        }
+
+        let sets = self
+            .sets
+            .iter()
+            .map(|&set| Ellers::find(&mut parents, set))
+            .collect::<Vec<usize>>();
+        let mut south = vec![false; self.width];
+
+        // every set carries on into the next row through at least one passage south.
+        if !last {
+            let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+
+            for (x, &set) in sets.iter().enumerate() {
+                members.entry(set).or_default().push(x);
+            }
+
+            // visited in the order they first appear, so the same seed carves the same maze.
+            let mut firsts = members
+                .values()
+                .map(|cells| cells[0])
+                .collect::<Vec<usize>>();
+            firsts.sort();
+
+            for first in firsts {
+                let cells = &members[&sets[first]];
+                let mut down = false;
+
+                for &x in cells {
+                    if random.gen_bool(0.5) {
+                        south[x] = true;
+                        down = true;
+                    }
+                }
+
+                if !down {
+                    south[*cells.choose(&mut random).unwrap()] = true;
+                }
+            }
+        }
+
+        // the cells below a passage south keep its set, the rest start sets of their own,
+        // renumbered so every set is again below the width.
+        let mut numbers = HashMap::new();
+        let mut fresh = 0..self.width;
+
+        self.sets = (0..self.width)
+            .map(|x| {
+                let set = if south[x] { sets[x] } else { self.width + x };
+                *numbers.entry(set).or_insert_with(|| fresh.next().unwrap())
+            })
+            .collect();
+
+        let row = CarvedRow {
+            y: self.y,
+            east,
+            south,
+        };
+        self.y += 1;
+
+        return Some(row);
+    }
+}
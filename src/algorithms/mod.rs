@@ -1,225 +1,509 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, seq::SliceRandom};
+
 use crate::prelude::*;
 
+mod ellers;
+mod weights;
+
+pub use ellers::*;
+pub use weights::*;
+
+/// Algorithm : A way of carving a maze into any grid, each leaving its own texture behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Algorithm {
     BinaryTree,
     Sidewinder,
     AldousBroder,
     Wilsons,
     HuntAndKill,
-    RecursiveBacktracker,
+    RecursiveBacktracker(DirectionWeights),
     None,
 }
 
 impl Algorithm {
+    // the names algorithms are chosen by, in the order they're listed.
+    pub const NAMES: [&'static str; 7] = [
+        "binarytree",
+        "sidewinder",
+        "aldousbroder",
+        "wilsons",
+        "huntandkill",
+        "recursivebacktracker",
+        "none",
+    ];
+
+    // the algorithm called `name`, in any case, with the recursive backtracker leaning by
+    // `weights`.
+    pub fn from_name(name: &str, weights: DirectionWeights) -> Result<Algorithm, MazeError> {
+        let algorithm = match name.to_lowercase().as_str() {
+            "binarytree" => Algorithm::BinaryTree,
+            "sidewinder" => Algorithm::Sidewinder,
+            "aldousbroder" => Algorithm::AldousBroder,
+            "wilsons" => Algorithm::Wilsons,
+            "huntandkill" => Algorithm::HuntAndKill,
+            "recursivebacktracker" => Algorithm::RecursiveBacktracker(weights),
+            "none" => Algorithm::None,
+            _ => return Err(MazeError::UnknownAlgorithm(name.to_string())),
+        };
+
+        return Ok(algorithm);
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::BinaryTree => "binarytree",
+            Algorithm::Sidewinder => "sidewinder",
+            Algorithm::AldousBroder => "aldousbroder",
+            Algorithm::Wilsons => "wilsons",
+            Algorithm::HuntAndKill => "huntandkill",
+            Algorithm::RecursiveBacktracker(_) => "recursivebacktracker",
+            Algorithm::None => "none",
+        }
+    }
+
+    // the texture the algorithm leaves behind, in a line.
+    pub fn texture(&self) -> &'static str {
+        match self {
+            Algorithm::BinaryTree => {
+                "Unbroken corridors along the north and east edges, and a diagonal bias."
+            }
+            Algorithm::Sidewinder => {
+                "An unbroken corridor along the north edge, with runs leading up to it."
+            }
+            Algorithm::AldousBroder => "Unbiased, every maze equally likely, but slow to finish.",
+            Algorithm::Wilsons => "Unbiased like Aldous-Broder, slow to start but quick to finish.",
+            Algorithm::HuntAndKill => "Long winding passages with few dead ends.",
+            Algorithm::RecursiveBacktracker(_) => {
+                "Long twisting passages with few dead ends, leaning by --weights."
+            }
+            Algorithm::None => "Carves nothing, leaving every wall standing.",
+        }
+    }
+
     pub fn on(&mut self, grid: &mut dyn Grid) {
+        log::debug!(
+            "carving {} cells with {}",
+            grid.cells().iter().flatten().count(),
+            self.name()
+        );
+
         match self {
             Algorithm::BinaryTree => self.binary_tree(grid),
             Algorithm::Sidewinder => self.sidewinder(grid),
             Algorithm::AldousBroder => self.aldous_broder(grid),
             Algorithm::Wilsons => self.wilsons(grid),
             Algorithm::HuntAndKill => self.hunt_and_kill(grid),
-            Algorithm::RecursiveBacktracker => self.recursive_backtracker(grid),
+            Algorithm::RecursiveBacktracker(weights) => {
+                let weights = *weights;
+                self.recursive_backtracker(grid, weights)
+            }
             Algorithm::None => {}
         }
+
+        log::debug!(
+            "carved {} passages with {}",
+            grid.cells()
+                .iter()
+                .flatten()
+                .map(|cell| grid.links(cell.point).len())
+                .sum::<usize>()
+                / 2,
+            self.name()
+        );
     }
 
     fn binary_tree(&mut self, grid: &mut dyn Grid) {
-        let mut actions = Vec::new();
-        for cell in grid.cells().iter() {
-            let mut neighbors = Vec::new();
-            if let Some(cell) = cell {
-                if let Some(north) = grid.get(cell.north.point.clone()) {
-                    neighbors.push(north);
-                }
-
-                if let Some(east) = grid.get(cell.east.point.clone()) {
-                    neighbors.push(east);
-                }
-
-                if !neighbors.is_empty() {
-                    let index = rand::thread_rng().gen_range(0..neighbors.len());
-                    let neighbor_point = neighbors[index].point.clone();
-                    let cell_point = cell.point.clone();
+        let points = grid
+            .cells()
+            .iter()
+            .flatten()
+            .map(|cell| cell.point)
+            .collect::<Vec<Point>>();
+
+        for point in points {
+            let neighbors = grid.neighbors(point);
+            let candidates = [point.north(), point.east()]
+                .into_iter()
+                .filter(|p| neighbors.contains(p))
+                .collect::<Vec<Point>>();
 
-                    actions.push((cell_point, neighbor_point));
-                }
+            if !candidates.is_empty() {
+                let index = random::rng().gen_range(0..candidates.len());
+                grid.link(point, candidates[index], true);
             }
         }
-
-        for (cell_point, neighbor_point) in actions.iter() {
-            grid.link(*cell_point, *neighbor_point, true);
-        }
     }
 
     fn sidewinder(&mut self, grid: &mut dyn Grid) {
-        let mut random = rand::thread_rng();
-        let mut actions = Vec::new();
+        let mut random = random::rng();
+        let rows = grid
+            .iter_rows()
+            .map(|row| row.iter().flatten().map(|cell| cell.point).collect())
+            .collect::<Vec<Vec<Point>>>();
 
-        for row in grid.iter_rows() {
+        for row in rows {
             let mut run = Vec::new();
 
-            for cell in row.iter() {
-                if let Some(cell) = cell {
-                    run.push(cell);
+            for point in row {
+                run.push(point);
 
-                    let at_eastern_boundary = cell.east.point.x == (grid.width() as i32);
-                    let at_northern_boundary = cell.north.point.y <= 0;
+                let at_eastern_boundary = point.east().x == (grid.width() as i32);
+                let at_northern_boundary = point.north().y < 0;
 
-                    let should_close_out =
-                        at_eastern_boundary || (!at_northern_boundary && random.gen_bool(0.5));
+                let should_close_out =
+                    at_eastern_boundary || (!at_northern_boundary && random.gen_bool(0.5));
 
-                    if should_close_out {
-                        let index = random.gen_range(0..run.len());
-                        let member = run.get(index).unwrap();
-                        let north = member.north.point;
+                if should_close_out {
+                    let member = run[random.gen_range(0..run.len())];
 
-                        actions.push((member.point.clone(), north.clone()));
-                        run.clear();
-                    } else {
-                        actions.push((cell.point.clone(), cell.east.point.clone()));
-                    }
+                    grid.link(member, member.north(), true);
+                    run.clear();
+                } else {
+                    grid.link(point, point.east(), true);
                 }
             }
         }
-
-        for (cell_point, neighbor_point) in actions.iter() {
-            grid.link(*cell_point, *neighbor_point, true);
-        }
     }
 
     fn aldous_broder(&mut self, grid: &mut dyn Grid) {
-        let mut random = rand::thread_rng();
-
-        let mut cell = *grid.random_cell().unwrap();
-        let mut unvisited = grid.width() * grid.height() - 1;
-
-        while unvisited > 0 {
-            let neighbors = cell.neighbors(grid);
-            let random_index = random.gen_range(0..neighbors.len());
-            let neighbor = neighbors.get(random_index).unwrap();
+        let mut random = random::rng();
+        let mut visited = Visited::new(grid);
+        let total = grid.cells().iter().flatten().count();
+        let mut carved = 0;
+        let mut steps = 0;
+        let mut start = grid.random_cell().map(|cell| cell.point);
+
+        // each region of the mask is walked on its own, as a walk never leaves the region it
+        // starts in.
+        while let Some(mut point) = start {
+            visited.insert(grid, point);
+
+            let mut unvisited = grid.reachable_from(point).len() - 1;
+            carved += 1;
+
+            while unvisited > 0 {
+                steps += 1;
+                let neighbors = grid.neighbors(point);
+                let neighbor = neighbors[random.gen_range(0..neighbors.len())];
+
+                if visited.insert(grid, neighbor) {
+                    grid.link(point, neighbor, true);
+                    unvisited -= 1;
+                    carved += 1;
+                    progress::report(carved, total);
+                }
 
-            if neighbor.links().len() == 0 {
-                grid.link(cell.point, neighbor.point, true);
-                unvisited -= 1;
+                point = neighbor;
             }
 
-            cell = neighbor.clone();
+            start = Algorithm::unvisited_cell(grid, &visited, &mut random);
         }
+
+        log::debug!(
+            "aldousbroder walked {} steps to visit {} cells",
+            steps,
+            total
+        );
     }
 
     fn wilsons(&mut self, grid: &mut dyn Grid) {
-        let mut unvisited = grid
+        let mut random = random::rng();
+        let mut order = grid
             .cells()
             .iter()
-            .filter(|c| c.is_some())
-            .map(|c| c.unwrap())
-            .collect::<Vec<Cell>>()
-            .clone();
+            .flatten()
+            .map(|c| c.point)
+            .collect::<Vec<Point>>();
+        let mut visited = Visited::new(grid);
+        let mut remaining = order.len();
+
+        // every disconnected region of a mask needs its own starting cell, otherwise walks
+        // that begin there would never reach the maze.
+        order.shuffle(&mut random);
+
+        let mut regions = HashSet::new();
+
+        for point in order.iter() {
+            if !regions.contains(point) {
+                regions.extend(grid.reachable_from(*point));
+                visited.insert(grid, *point);
+                remaining -= 1;
+            }
+        }
 
-        let mut random = rand::thread_rng();
-        let index = random.gen_range(0..unvisited.len());
+        log::debug!(
+            "wilsons started {} regions, {} cells left to walk to them",
+            order.len() - remaining,
+            remaining
+        );
 
-        unvisited.remove(index);
+        // walking from the cells in shuffled order picks each new walk's start uniformly
+        // among the cells that are still visited.
+        order.shuffle(&mut random);
 
-        while !unvisited.is_empty() {
-            let index = random.gen_range(0..unvisited.len());
-            let mut cell = *unvisited.get(index).unwrap();
-            let mut path = vec![cell.clone()];
+        for &start in order.iter() {
+            if visited.contains(grid, start) {
+                continue;
+            }
 
-            while unvisited.contains(&cell) {
-                let index = random.gen_range(0..cell.neighbors(grid).len());
-                cell = *cell.neighbors(grid).get(index).unwrap();
+            // remembering only the last exit taken from each cell erases loops implicitly,
+            // so the walk never has to search or truncate its path.
+            let mut exits = HashMap::new();
+            let mut cell = start;
 
-                let position = path.iter().position(|c| c == &cell);
+            while !visited.contains(grid, cell) {
+                let neighbors = grid.neighbors(cell);
+                let next = neighbors[random.gen_range(0..neighbors.len())];
 
-                if let Some(position) = position {
-                    path.truncate(position + 1);
-                } else {
-                    path.push(cell.clone());
-                }
+                exits.insert(cell, next);
+                cell = next;
             }
 
-            for i in 0..path.len() - 1 {
-                grid.link(path[i].point, path[i + 1].point, true);
-                unvisited.retain(|c| c != &path[i]);
+            let mut cell = start;
+
+            while visited.insert(grid, cell) {
+                let next = exits[&cell];
+
+                grid.link(cell, next, true);
+                cell = next;
+                remaining -= 1;
             }
+
+            progress::report(order.len() - remaining, order.len());
         }
     }
 
     fn hunt_and_kill(&mut self, grid: &mut dyn Grid) {
-        let mut random = rand::thread_rng();
-        let mut current = Some(*grid.random_cell().unwrap());
+        let mut random = random::rng();
+        let mut current = grid.random_cell().map(|cell| cell.point);
+        let mut visited = Visited::new(grid);
 
-        while current.is_some() {
-            let neighbors = current.unwrap().neighbors(grid);
-            let mut unvisited_neighbors = Vec::new();
+        if let Some(start) = current {
+            visited.insert(grid, start);
+        }
 
-            for neighbor in neighbors {
-                if neighbor.links().is_empty() {
-                    unvisited_neighbors.push(neighbor);
-                }
-            }
+        // every cell before this index is known to be visited or masked out, so each hunt
+        // resumes where the last one left off instead of rescanning the whole grid.
+        let mut hunt_from = 0;
+        let mut hunts = 0;
+
+        while let Some(point) = current {
+            let unvisited_neighbors = grid
+                .neighbors(point)
+                .into_iter()
+                .filter(|&n| !visited.contains(grid, n))
+                .collect::<Vec<Point>>();
 
             if !unvisited_neighbors.is_empty() {
                 let index = random.gen_range(0..unvisited_neighbors.len());
-                let neighbor = *unvisited_neighbors.get(index).unwrap();
-                grid.link(current.unwrap().point, neighbor.point, true);
+                let neighbor = unvisited_neighbors[index];
+                grid.link(point, neighbor, true);
+                visited.insert(grid, neighbor);
                 current = Some(neighbor);
             } else {
-                let cells = grid
-                    .cells()
-                    .iter()
-                    .filter(|x| x.is_some())
-                    .map(|x| x.unwrap())
-                    .collect::<Vec<Cell>>();
-
                 current = None;
+                hunts += 1;
+
+                let mut settled = true;
+                let start = hunt_from;
+
+                for index in start..grid.cells().len() {
+                    let point = match &grid.cells()[index] {
+                        Some(cell) if !visited.contains(grid, cell.point) => cell.point,
+                        _ => {
+                            if settled {
+                                hunt_from = index + 1;
+                            }
+                            continue;
+                        }
+                    };
 
-                for cell in cells.iter() {
-                    let mut visited_neighors = Vec::new();
+                    settled = false;
 
-                    for neighbor in cell.neighbors(grid) {
-                        if !neighbor.links().is_empty() {
-                            visited_neighors.push(neighbor);
-                        }
-                    }
+                    let visited_neighbors = grid
+                        .neighbors(point)
+                        .into_iter()
+                        .filter(|&n| visited.contains(grid, n))
+                        .collect::<Vec<Point>>();
 
-                    if cell.links().is_empty() && !visited_neighors.is_empty() {
-                        let index = random.gen_range(0..visited_neighors.len());
-                        let neighbor = *visited_neighors.get(index).unwrap();
-                        grid.link(cell.point, neighbor.point, true);
-                        current = Some(*cell);
+                    if !visited_neighbors.is_empty() {
+                        let index = random.gen_range(0..visited_neighbors.len());
+                        grid.link(point, visited_neighbors[index], true);
+                        visited.insert(grid, point);
+                        current = Some(point);
                         break;
                     }
                 }
+
+                // the cells left over have no way into the carved region, so they're part of
+                // another region of the mask and carving starts over in one of them.
+                if current.is_none() && !settled {
+                    current = Algorithm::unvisited_cell(grid, &visited, &mut random);
+
+                    if let Some(start) = current {
+                        visited.insert(grid, start);
+                    }
+                }
             }
         }
+
+        log::debug!("huntandkill hunted {} times", hunts);
     }
 
-    fn recursive_backtracker(&mut self, grid: &mut dyn Grid) {
-        let mut random = rand::thread_rng();
-        let mut stack: Vec<Point> = Vec::new();
-        let random_cell = grid.random_cell().unwrap().clone();
-        stack.push(random_cell.point);
+    fn recursive_backtracker(&mut self, grid: &mut dyn Grid, weights: DirectionWeights) {
+        let mut random = random::rng();
+        let mut stack = grid
+            .random_cell()
+            .map(|cell| vec![cell.point])
+            .unwrap_or_default();
+        let mut deepest = 0;
+
+        // the cells each region was started from, which may have been left without a
+        // passage when they have no neighbors.
+        let mut started = Visited::new(grid);
+
+        if let Some(&start) = stack.first() {
+            started.insert(grid, start);
+        }
 
         while !stack.is_empty() {
+            deepest = deepest.max(stack.len());
             let current = stack.last();
             let neighbors = grid
                 .neighbors(*current.unwrap())
-                .iter()
-                .map(|&p| grid.get(p).unwrap())
-                .filter(|&n| n.links().is_empty())
-                .map(|&n| n.point)
+                .into_iter()
+                .filter(|&n| grid.links(n).is_empty())
                 .collect::<Vec<Point>>();
 
             if neighbors.is_empty() {
                 stack.pop();
             } else {
-                let index = random.gen_range(0..neighbors.len());
+                let neighbor_weights = neighbors
+                    .iter()
+                    .map(|&n| weights.weight_between(*current.unwrap(), n));
+
+                // fall back to a uniform choice when every available direction has no weight.
+                let index = match WeightedIndex::new(neighbor_weights) {
+                    Ok(distribution) => distribution.sample(&mut random),
+                    Err(_) => random.gen_range(0..neighbors.len()),
+                };
                 let neighbor = *neighbors.get(index).unwrap();
 
                 grid.link(*current.unwrap(), neighbor, true);
-                stack.push(grid.get(neighbor).unwrap().point);
+                stack.push(neighbor);
+            }
+
+            // once a region is finished, the next starts from any cell still uncarved.
+            if stack.is_empty() {
+                let uncarved = grid
+                    .cells()
+                    .iter()
+                    .flatten()
+                    .map(|cell| cell.point)
+                    .filter(|&point| grid.links(point).is_empty() && !started.contains(grid, point))
+                    .collect::<Vec<Point>>();
+
+                if let Some(&start) = uncarved.choose(&mut random) {
+                    started.insert(grid, start);
+                    stack.push(start);
+                }
+            }
+        }
+
+        log::debug!(
+            "recursivebacktracker backtracked from {} cells deep",
+            deepest
+        );
+    }
+
+    // a cell none of the carving so far has reached, picked at random to start the next
+    // region of a mask from, or None once every cell has been reached.
+    fn unvisited_cell(grid: &dyn Grid, visited: &Visited, random: &mut impl Rng) -> Option<Point> {
+        let unvisited = grid
+            .cells()
+            .iter()
+            .flatten()
+            .map(|cell| cell.point)
+            .filter(|&point| !visited.contains(grid, point))
+            .collect::<Vec<Point>>();
+
+        return unvisited.choose(random).copied();
+    }
+}
+
+// Visited : Which cells of a grid have been reached, kept by each cell's index in the grid's
+// list of cells rather than by its point.
+struct Visited {
+    cells: Vec<bool>,
+}
+
+impl Visited {
+    fn new(grid: &dyn Grid) -> Self {
+        Self {
+            cells: vec![false; grid.cells().len()],
+        }
+    }
+
+    fn contains(&self, grid: &dyn Grid, point: Point) -> bool {
+        return grid
+            .point_to_index(point)
+            .is_some_and(|index| self.cells[index]);
+    }
+
+    // marks `point` as reached, returning whether it hadn't been already.
+    fn insert(&mut self, grid: &dyn Grid, point: Point) -> bool {
+        match grid.point_to_index(point) {
+            Some(index) => return !std::mem::replace(&mut self.cells[index], true),
+            None => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a mask in three regions, cut in two by a column left out down the middle with a
+    // single cell cut off in the bottom right corner.
+    fn split() -> Mask {
+        let mut mask = Mask::new(7, 4);
+
+        for y in 0..4 {
+            mask.set(Point::new(3, y), false);
+        }
+
+        mask.set(Point::new(5, 3), false);
+        mask.set(Point::new(6, 2), false);
+
+        return mask;
+    }
+
+    #[test]
+    fn carves_every_region_of_a_split_mask() {
+        let algorithms = [
+            Algorithm::AldousBroder,
+            Algorithm::Wilsons,
+            Algorithm::HuntAndKill,
+            Algorithm::RecursiveBacktracker(DirectionWeights::default()),
+        ];
+
+        for mut algorithm in algorithms {
+            for seed in 0..10 {
+                random::seed(seed);
+
+                let mut grid = RectangularGrid::from_mask(&split());
+                algorithm.on(&mut grid);
+
+                assert_eq!(grid.regions(), 3);
+                assert_eq!(
+                    verify(&grid),
+                    Vec::new(),
+                    "{} seed {}",
+                    algorithm.name(),
+                    seed
+                );
             }
         }
     }
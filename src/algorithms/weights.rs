@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+// Weights : How strongly a carver prefers moving in each direction. Heavier north and
+// south weights produce long vertical corridors, heavier east and west weights produce
+// long horizontal ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectionWeights {
+    pub north: u32,
+    pub east: u32,
+    pub south: u32,
+    pub west: u32,
+}
+
+impl DirectionWeights {
+    pub fn new(north: u32, east: u32, south: u32, west: u32) -> Self {
+        Self {
+            north,
+            east,
+            south,
+            west,
+        }
+    }
+
+    pub fn weight(&self, direction: Direction) -> u32 {
+        match direction {
+            Direction::North => self.north,
+            Direction::East => self.east,
+            Direction::South => self.south,
+            Direction::West => self.west,
+        }
+    }
+
+    // neighbors that aren't a single orthogonal step away have no direction, so they
+    // keep the neutral weight of 1.
+    pub fn weight_between(&self, from: Point, to: Point) -> u32 {
+        match from.direction_to(to) {
+            Some(direction) => self.weight(direction),
+            None => 1,
+        }
+    }
+}
+
+impl Default for DirectionWeights {
+    fn default() -> Self {
+        Self::new(1, 1, 1, 1)
+    }
+}
+
+impl FromStr for DirectionWeights {
+    type Err = String;
+
+    // parses a list such as "n=3,e=1,s=3,w=1", any direction left out keeps a weight of 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = DirectionWeights::default();
+
+        for pair in s.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or(format!(
+                "Invalid weight '{}', expected <direction>=<weight>",
+                pair
+            ))?;
+
+            let value = value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid weight value '{}'", value))?;
+
+            match key.trim().to_lowercase().as_str() {
+                "n" | "north" => weights.north = value,
+                "e" | "east" => weights.east = value,
+                "s" | "south" => weights.south = value,
+                "w" | "west" => weights.west = value,
+                _ => return Err(format!("Invalid direction '{}'", key)),
+            }
+        }
+
+        return Ok(weights);
+    }
+}
@@ -0,0 +1,874 @@
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    time::Instant,
+};
+
+use crate::prelude::*;
+
+// Stats : A summary of a carved maze, used to compare the textures different algorithms
+// leave behind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub cells: usize,
+    pub dead_ends: usize,
+    pub passages: usize,
+    // how many independent loops the passages make, none in a perfect maze.
+    pub cycles: usize,
+}
+
+impl Stats {
+    pub fn of(grid: &dyn Grid) -> Self {
+        let cells = grid.cells().iter().flatten().count();
+
+        // every passage is stored on both of the cells it joins.
+        let links = grid
+            .cells()
+            .iter()
+            .flatten()
+            .map(|cell| grid.links(cell.point).len())
+            .sum::<usize>();
+
+        Self {
+            cells,
+            dead_ends: grid.dead_ends().len(),
+            passages: links / 2,
+            cycles: verify(grid)
+                .iter()
+                .filter(|violation| matches!(violation, Violation::Cycle(..)))
+                .count(),
+        }
+    }
+
+    pub fn dead_end_ratio(&self) -> f64 {
+        if self.cells == 0 {
+            return 0.0;
+        }
+
+        return self.dead_ends as f64 / self.cells as f64;
+    }
+
+    // roughly the share of a perfect maze's dead ends that braiding removed, taking every
+    // loop to have been made by opening up one dead end.
+    pub fn braid_factor(&self) -> f64 {
+        if self.cycles + self.dead_ends == 0 {
+            return 0.0;
+        }
+
+        return self.cycles as f64 / (self.cycles + self.dead_ends) as f64;
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Cells: {}", self.cells)?;
+        writeln!(f, "Passages: {}", self.passages)?;
+        writeln!(f, "Dead ends: {}", self.dead_ends)?;
+        writeln!(f, "Dead end ratio: {:.1}%", self.dead_end_ratio() * 100.0)?;
+        writeln!(f, "Cycles: {}", self.cycles)?;
+        write!(f, "Braid factor: {:.2}", self.braid_factor())
+    }
+}
+
+// Sample : Statistics averaged over many mazes carved the same way, for comparing
+// algorithms. Solutions run from the first cell of each maze to its last.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Sample {
+    pub dead_ends: Vec<f64>,
+    pub solution_lengths: Vec<f64>,
+    // how long carving each maze took, in milliseconds.
+    pub times: Vec<f64>,
+}
+
+impl Sample {
+    // carves `runs` mazes with `algorithm`, each on a fresh grid from `make`.
+    pub fn run<T: Grid>(runs: usize, algorithm: &mut Algorithm, make: impl Fn() -> T) -> Self {
+        let mut sample = Sample::default();
+
+        for _ in 0..runs {
+            let mut grid = make();
+            let started = Instant::now();
+            algorithm.on(&mut grid);
+            sample.times.push(started.elapsed().as_secs_f64() * 1000.0);
+
+            sample.dead_ends.push(grid.dead_ends().len() as f64);
+
+            let first = grid.first_cell();
+            let last = grid.cells().iter().flatten().last().map(|cell| cell.point);
+
+            if let (Some(first), Some(last)) = (first, last) {
+                let mut distances = Distances::new(first);
+                distances.compute(&grid);
+
+                if let Some(length) = distances.distance(last) {
+                    sample.solution_lengths.push(length as f64);
+                }
+            }
+        }
+
+        return sample;
+    }
+
+    // the mean of `values` and their standard deviation.
+    pub fn spread(values: &[f64]) -> (f64, f64) {
+        if values.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+
+        return (mean, variance.sqrt());
+    }
+}
+
+impl Display for Sample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (dead_ends, dead_ends_deviation) = Sample::spread(&self.dead_ends);
+        let (length, length_deviation) = Sample::spread(&self.solution_lengths);
+        let (time, time_deviation) = Sample::spread(&self.times);
+
+        writeln!(f, "Runs: {}", self.times.len())?;
+        writeln!(
+            f,
+            "Dead ends: {:.2} ± {:.2}",
+            dead_ends, dead_ends_deviation
+        )?;
+        writeln!(
+            f,
+            "Solution length: {:.2} ± {:.2}",
+            length, length_deviation
+        )?;
+        write!(
+            f,
+            "Generation time: {:.3}ms ± {:.3}ms",
+            time, time_deviation
+        )
+    }
+}
+
+// Analysis : The texture of a carved maze. Cells are sorted by the shape of the passages
+// through them, and corridors are the runs of cells between dead ends and junctions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub dead_ends: usize,
+    pub straights: usize,
+    pub elbows: usize,
+    pub junctions: usize,
+    // how many corridors there are of each length, in steps, shortest first.
+    pub corridors: BTreeMap<usize, usize>,
+    pub horizontal: usize,
+    pub vertical: usize,
+}
+
+impl Analysis {
+    pub fn of(grid: &dyn Grid) -> Self {
+        let mut analysis = Self {
+            dead_ends: 0,
+            straights: 0,
+            elbows: 0,
+            junctions: 0,
+            corridors: BTreeMap::new(),
+            horizontal: 0,
+            vertical: 0,
+        };
+
+        for cell in grid.cells().iter().flatten() {
+            let point = cell.point;
+            let links = grid.links(point);
+
+            match links.as_slice() {
+                [_] => analysis.dead_ends += 1,
+                // a straight passage leaves through the side opposite the one it came in by.
+                [a, b] if *a - point == point - *b => analysis.straights += 1,
+                [_, _] => analysis.elbows += 1,
+                [] => {}
+                _ => analysis.junctions += 1,
+            }
+
+            for &link in links.iter() {
+                match point.direction_to(link) {
+                    Some(Direction::East) => analysis.horizontal += 1,
+                    Some(Direction::South) => analysis.vertical += 1,
+                    _ => {}
+                }
+            }
+
+            // corridors are followed from the end with the lower point, so each is counted
+            // once. Loops made only of corridor cells have no ends and aren't counted.
+            if links.len() == 2 {
+                continue;
+            }
+
+            for &link in links.iter() {
+                let (length, end) = Analysis::follow(grid, point, link);
+
+                if point <= end {
+                    *analysis.corridors.entry(length).or_default() += 1;
+                }
+            }
+        }
+
+        return analysis;
+    }
+
+    // walks from `from` through `next` until reaching a cell that isn't part of a corridor,
+    // returning the number of steps taken and where the walk stopped.
+    fn follow(grid: &dyn Grid, from: Point, next: Point) -> (usize, Point) {
+        let (mut previous, mut current) = (from, next);
+        let mut length = 1;
+
+        loop {
+            let links = grid.links(current);
+
+            if links.len() != 2 || current == from {
+                return (length, current);
+            }
+
+            let onward = if links[0] == previous {
+                links[1]
+            } else {
+                links[0]
+            };
+            (previous, current) = (current, onward);
+            length += 1;
+        }
+    }
+
+    // the passages running east to west for every one running north to south, above 1 when
+    // the maze leans towards horizontal corridors.
+    pub fn bias(&self) -> f64 {
+        if self.vertical == 0 {
+            return self.horizontal as f64;
+        }
+
+        return self.horizontal as f64 / self.vertical as f64;
+    }
+
+    pub fn to_json(&self) -> String {
+        let corridors = self
+            .corridors
+            .iter()
+            .map(|(length, count)| format!("\"{}\": {}", length, count))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        return format!(
+            "{{\"dead_ends\": {}, \"straights\": {}, \"elbows\": {}, \"junctions\": {}, \
+             \"corridors\": {{{}}}, \"horizontal\": {}, \"vertical\": {}, \"bias\": {:.3}}}",
+            self.dead_ends,
+            self.straights,
+            self.elbows,
+            self.junctions,
+            corridors,
+            self.horizontal,
+            self.vertical,
+            self.bias()
+        );
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Dead ends: {}", self.dead_ends)?;
+        writeln!(f, "Straights: {}", self.straights)?;
+        writeln!(f, "Elbows: {}", self.elbows)?;
+        writeln!(f, "Junctions: {}", self.junctions)?;
+        writeln!(f, "Corridor lengths:")?;
+
+        for (length, count) in self.corridors.iter() {
+            writeln!(f, "  {:>3}: {}", length, count)?;
+        }
+
+        let lean = match self.bias() {
+            bias if bias > 1.1 => "horizontal",
+            bias if bias < 0.9 => "vertical",
+            _ => "none",
+        };
+
+        write!(
+            f,
+            "Bias: {} ({} horizontal, {} vertical passages)",
+            lean, self.horizontal, self.vertical
+        )
+    }
+}
+
+// Branching : How many cells of a maze have each number of passages leading out of them,
+// from dead ends through corridors to junctions. Works for any shape of cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branching {
+    pub counts: BTreeMap<usize, usize>,
+}
+
+impl Branching {
+    pub fn of(grid: &dyn Grid) -> Self {
+        let mut counts = BTreeMap::new();
+
+        for cell in grid.cells().iter().flatten() {
+            *counts.entry(grid.links(cell.point).len()).or_default() += 1;
+        }
+
+        Self { counts }
+    }
+
+    pub fn name(links: usize) -> String {
+        return match links {
+            0 => String::from("isolated"),
+            1 => String::from("dead end"),
+            2 => String::from("corridor"),
+            3 => String::from("T-junction"),
+            4 => String::from("crossroads"),
+            n => format!("{}-way junction", n),
+        };
+    }
+
+    // the average number of new ways on offered at a junction, counting the way back as
+    // one a solver has already taken.
+    pub fn branching_factor(&self) -> f64 {
+        let (choices, junctions) =
+            self.counts
+                .range(3..)
+                .fold((0, 0), |(choices, junctions), (links, count)| {
+                    (choices + (links - 1) * count, junctions + count)
+                });
+
+        if junctions == 0 {
+            return 0.0;
+        }
+
+        return choices as f64 / junctions as f64;
+    }
+
+    pub fn to_json(&self) -> String {
+        let counts = self
+            .counts
+            .iter()
+            .map(|(links, count)| format!("\"{}\": {}", links, count))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        return format!(
+            "{{\"counts\": {{{}}}, \"branching_factor\": {:.3}}}",
+            counts,
+            self.branching_factor()
+        );
+    }
+}
+
+impl Display for Branching {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // bars are scaled so the most common kind of cell fills the width.
+        const BAR_WIDTH: usize = 40;
+        let most = self.counts.values().copied().max().unwrap_or(0).max(1);
+
+        for (&links, &count) in self.counts.iter() {
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(most));
+            writeln!(
+                f,
+                "{:>16} ({}): {:>5} {}",
+                Branching::name(links),
+                links,
+                count,
+                bar
+            )?;
+        }
+
+        write!(f, "Branching factor: {:.2}", self.branching_factor())
+    }
+}
+
+// how many steps from the solution a dead end can be and still count as near it.
+const NEAR_PATH: usize = 3;
+
+// Rating : How hard a maze is to solve, from the score of its `Difficulty`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rating {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Rating {
+    // the scores a maze of this rating has, from the first up to but not including the
+    // second.
+    pub fn scores(&self) -> (f64, f64) {
+        match self {
+            Rating::Easy => (0.0, 0.33),
+            Rating::Medium => (0.33, 0.4),
+            Rating::Hard => (0.4, f64::INFINITY),
+        }
+    }
+
+    pub fn of(score: f64) -> Self {
+        return [Rating::Easy, Rating::Medium]
+            .into_iter()
+            .find(|rating| score < rating.scores().1)
+            .unwrap_or(Rating::Hard);
+    }
+
+    // how far `score` is from the scores of this rating, 0 when it's one of them.
+    pub fn distance(&self, score: f64) -> f64 {
+        let (low, high) = self.scores();
+
+        return (low - score).max(score - high).max(0.0);
+    }
+}
+
+impl Display for Rating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rating::Easy => write!(f, "easy"),
+            Rating::Medium => write!(f, "medium"),
+            Rating::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+// Difficulty : How hard a rectangular maze is to solve by hand, measured along its
+// solution. Long solutions with many choices to make on the way and many dead ends close
+// by to be led into are harder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    pub cells: usize,
+    // the cells of the solution.
+    pub length: usize,
+    // the wrong ways on offered along the solution, one for each passage leading on from a
+    // cell of the solution beyond the one it takes.
+    pub decisions: usize,
+    // the share of the cells near the solution that are dead ends.
+    pub dead_end_density: f64,
+}
+
+impl Difficulty {
+    pub fn of(grid: &RectangularGrid) -> Self {
+        let mut solved = grid.clone();
+        let path = solved.solve().clone();
+        let cells = grid.cells.iter().flatten().count();
+
+        let mut decisions = 0;
+
+        for (i, &point) in path.iter().enumerate().take(path.len().saturating_sub(1)) {
+            let ways = grid
+                .links(point)
+                .into_iter()
+                .filter(|&link| i == 0 || link != path[i - 1])
+                .count();
+            decisions += ways.saturating_sub(1);
+        }
+
+        // every cell within NEAR_PATH steps of the solution, and how far it is.
+        let mut near = path
+            .iter()
+            .map(|&point| (point, 0))
+            .collect::<HashMap<_, _>>();
+        let mut frontier = path.clone();
+
+        for steps in 1..=NEAR_PATH {
+            let mut next = Vec::new();
+
+            for point in frontier {
+                for link in grid.links(point) {
+                    if let Entry::Vacant(entry) = near.entry(link) {
+                        entry.insert(steps);
+                        next.push(link);
+                    }
+                }
+            }
+
+            frontier = next;
+        }
+
+        let beside = near.iter().filter(|(_, &steps)| steps > 0).count();
+        let dead_ends = near
+            .iter()
+            .filter(|(&point, &steps)| steps > 0 && grid.links(point).len() == 1)
+            .count();
+
+        Self {
+            cells,
+            length: path.len(),
+            decisions,
+            dead_end_density: if beside == 0 {
+                0.0
+            } else {
+                dead_ends as f64 / beside as f64
+            },
+        }
+    }
+
+    // from 0 for a maze with nothing to solve towards 1, weighing how much of the maze the
+    // solution winds through, how often it offers a wrong way and how many dead ends wait
+    // beside it.
+    pub fn score(&self) -> f64 {
+        if self.cells == 0 || self.length == 0 {
+            return 0.0;
+        }
+
+        let coverage = (self.length as f64 / self.cells as f64).sqrt();
+        let choices = (self.decisions as f64 / self.length as f64).min(1.0);
+
+        return 0.4 * coverage + 0.3 * choices + 0.3 * self.dead_end_density;
+    }
+
+    pub fn rating(&self) -> Rating {
+        return Rating::of(self.score());
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Solution length: {}", self.length)?;
+        writeln!(f, "Decisions: {}", self.decisions)?;
+        writeln!(
+            f,
+            "Dead ends near the solution: {:.1}%",
+            self.dead_end_density * 100.0
+        )?;
+        write!(f, "Difficulty: {} ({:.2})", self.rating(), self.score())
+    }
+}
+
+// Violation : A way in which a carved maze fails to be a perfect maze, a spanning tree
+// over every cell that isn't masked out, or one for each region of a mask in pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    // a cell that can't be reached from the first cell of its region.
+    Unreachable(Point),
+    // a passage that closes a loop.
+    Cycle(Point, Point),
+    // a passage stored on only one of the two cells it joins.
+    OneWay(Point, Point),
+    // a passage leading to a cell that doesn't exist.
+    MissingCell(Point, Point),
+    // a maze of n cells in r regions needs exactly n - r passages.
+    PassageCount { expected: usize, found: usize },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let at = |p: &Point| format!("({}, {}, {})", p.x, p.y, p.z);
+
+        match self {
+            Violation::Unreachable(p) => write!(f, "{} is unreachable", at(p)),
+            Violation::Cycle(a, b) => write!(f, "{} to {} closes a loop", at(a), at(b)),
+            Violation::OneWay(a, b) => write!(f, "{} to {} is one way", at(a), at(b)),
+            Violation::MissingCell(a, b) => {
+                write!(f, "{} links to {}, which isn't a cell", at(a), at(b))
+            }
+            Violation::PassageCount { expected, found } => {
+                write!(f, "expected {} passages, found {}", expected, found)
+            }
+        }
+    }
+}
+
+// checks that the carved grid is a perfect maze, returning everything wrong with it. Cells
+// that grids add while carving, like the tunnels of a weave grid, count as cells too.
+pub fn verify(grid: &dyn Grid) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut nodes = grid
+        .cells()
+        .iter()
+        .flatten()
+        .map(|cell| cell.point)
+        .collect::<Vec<Point>>();
+    let mut seen = nodes.iter().copied().collect::<HashSet<Point>>();
+    let mut edges = Vec::new();
+    let mut index = 0;
+
+    while index < nodes.len() {
+        let point = nodes[index];
+        index += 1;
+
+        for link in grid.links(point) {
+            if grid.get(link).is_none() {
+                violations.push(Violation::MissingCell(point, link));
+                continue;
+            }
+
+            if !grid.linked(link, point) {
+                violations.push(Violation::OneWay(point, link));
+            }
+
+            if seen.insert(link) {
+                nodes.push(link);
+            }
+
+            if point < link || !grid.linked(link, point) {
+                edges.push((point, link));
+            }
+        }
+    }
+
+    // joining the two ends of every passage, any passage whose ends are already joined
+    // closes a loop.
+    let mut parents = HashMap::new();
+
+    fn root(parents: &mut HashMap<Point, Point>, point: Point) -> Point {
+        let parent = *parents.get(&point).unwrap_or(&point);
+
+        if parent == point {
+            return point;
+        }
+
+        let found = root(parents, parent);
+        parents.insert(point, found);
+
+        return found;
+    }
+
+    for &(a, b) in edges.iter() {
+        let (root_a, root_b) = (root(&mut parents, a), root(&mut parents, b));
+
+        if root_a == root_b {
+            violations.push(Violation::Cycle(a, b));
+        } else {
+            parents.insert(root_a, root_b);
+        }
+    }
+
+    // every cell should be joined to the first cell of its region, the cells that stepping
+    // between neighbors reaches. Only a mask cut into pieces gives a grid more than one, and
+    // each piece is a maze of its own.
+    let mut firsts = HashMap::new();
+    let mut regions = 0;
+
+    for cell in grid.cells().iter().flatten() {
+        if !firsts.contains_key(&cell.point) {
+            firsts.extend(
+                grid.reachable_from(cell.point)
+                    .into_iter()
+                    .map(|point| (point, cell.point)),
+            );
+            regions += 1;
+        }
+    }
+
+    for &point in nodes.iter() {
+        if let Some(&first) = firsts.get(&point) {
+            if root(&mut parents, point) != root(&mut parents, first) {
+                violations.push(Violation::Unreachable(point));
+            }
+        }
+    }
+
+    let expected = nodes.len().saturating_sub(regions);
+
+    if edges.len() != expected {
+        violations.push(Violation::PassageCount {
+            expected,
+            found: edges.len(),
+        });
+    }
+
+    return violations;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a width by height grid with a passage carved for each x, y to x, y in `passages`.
+    fn carved(width: usize, height: usize, passages: &[[i32; 4]]) -> RectangularGrid {
+        let mut grid = RectangularGrid::from_mask(&Mask::new(width, height));
+
+        for &[ax, ay, bx, by] in passages {
+            grid.link(Point::new(ax, ay), Point::new(bx, by), true);
+        }
+
+        return grid;
+    }
+
+    // every cell of a 2 by 2 grid joined to its neighbors, one loop and no dead ends.
+    fn ring() -> RectangularGrid {
+        return carved(
+            2,
+            2,
+            &[[0, 0, 1, 0], [1, 0, 1, 1], [1, 1, 0, 1], [0, 1, 0, 0]],
+        );
+    }
+
+    // a 3 by 3 grid winding back and forth from the top left to the bottom right.
+    fn snake() -> RectangularGrid {
+        return carved(
+            3,
+            3,
+            &[
+                [0, 0, 1, 0],
+                [1, 0, 2, 0],
+                [2, 0, 2, 1],
+                [2, 1, 1, 1],
+                [1, 1, 0, 1],
+                [0, 1, 0, 2],
+                [0, 2, 1, 2],
+                [1, 2, 2, 2],
+            ],
+        );
+    }
+
+    // a 3 by 3 grid whose solution runs along the top and down the right, passing two
+    // dead-end corridors on the way.
+    fn comb() -> RectangularGrid {
+        return carved(
+            3,
+            3,
+            &[
+                [0, 0, 1, 0],
+                [1, 0, 2, 0],
+                [2, 0, 2, 1],
+                [2, 1, 2, 2],
+                [0, 0, 0, 1],
+                [0, 1, 0, 2],
+                [1, 0, 1, 1],
+                [1, 1, 1, 2],
+            ],
+        );
+    }
+
+    #[test]
+    fn verify_passes_a_perfect_maze() {
+        assert_eq!(verify(&snake()), Vec::new());
+        assert_eq!(verify(&comb()), Vec::new());
+    }
+
+    #[test]
+    fn verify_finds_a_loop() {
+        let violations = verify(&ring());
+
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(violations[0], Violation::Cycle(..)));
+        assert_eq!(
+            violations[1],
+            Violation::PassageCount {
+                expected: 3,
+                found: 4
+            }
+        );
+    }
+
+    #[test]
+    fn verify_finds_one_way_passages_and_unreachable_cells() {
+        let mut grid = carved(2, 2, &[[0, 0, 1, 0], [1, 0, 1, 1]]);
+        grid.link(Point::new(0, 0), Point::new(0, 1), false);
+
+        let violations = verify(&grid);
+
+        assert!(violations.contains(&Violation::OneWay(Point::new(0, 0), Point::new(0, 1))));
+        assert!(!violations
+            .iter()
+            .any(|v| matches!(v, Violation::Unreachable(..))));
+
+        let violations = verify(&carved(2, 2, &[[0, 0, 1, 0]]));
+
+        assert!(violations.contains(&Violation::Unreachable(Point::new(0, 1))));
+        assert!(violations.contains(&Violation::Unreachable(Point::new(1, 1))));
+    }
+
+    // the second region of a mask is only a maze once it's carved too.
+    #[test]
+    fn verify_passes_a_maze_in_each_region() {
+        let mut mask = Mask::new(3, 2);
+        mask.set(Point::new(1, 0), false);
+        mask.set(Point::new(1, 1), false);
+
+        let mut grid = RectangularGrid::from_mask(&mask);
+        grid.link(Point::new(0, 0), Point::new(0, 1), true);
+
+        assert_eq!(
+            verify(&grid),
+            vec![
+                Violation::Unreachable(Point::new(2, 1)),
+                Violation::PassageCount {
+                    expected: 2,
+                    found: 1
+                }
+            ]
+        );
+
+        grid.link(Point::new(2, 0), Point::new(2, 1), true);
+
+        assert_eq!(verify(&grid), Vec::new());
+    }
+
+    #[test]
+    fn stats_count_loops_and_dead_ends() {
+        let stats = Stats::of(&ring());
+
+        assert_eq!(
+            stats,
+            Stats {
+                cells: 4,
+                dead_ends: 0,
+                passages: 4,
+                cycles: 1
+            }
+        );
+        assert_eq!(stats.braid_factor(), 1.0);
+
+        let stats = Stats::of(&comb());
+
+        assert_eq!(
+            stats,
+            Stats {
+                cells: 9,
+                dead_ends: 3,
+                passages: 8,
+                cycles: 0
+            }
+        );
+        assert_eq!(stats.dead_end_ratio(), 3.0 / 9.0);
+    }
+
+    #[test]
+    fn analysis_sorts_cells_and_corridors() {
+        let analysis = Analysis::of(&snake());
+
+        assert_eq!(analysis.dead_ends, 2);
+        assert_eq!(analysis.straights, 3);
+        assert_eq!(analysis.elbows, 4);
+        assert_eq!(analysis.junctions, 0);
+        assert_eq!(analysis.corridors, BTreeMap::from([(8, 1)]));
+        assert_eq!((analysis.horizontal, analysis.vertical), (6, 2));
+
+        // the ring is one loop of elbows with no ends to follow its corridor from.
+        let analysis = Analysis::of(&ring());
+
+        assert_eq!(analysis.elbows, 4);
+        assert!(analysis.corridors.is_empty());
+    }
+
+    #[test]
+    fn branching_counts_cells_by_their_passages() {
+        let branching = Branching::of(&comb());
+
+        assert_eq!(branching.counts, BTreeMap::from([(1, 3), (2, 5), (3, 1)]));
+        assert_eq!(branching.branching_factor(), 2.0);
+        assert_eq!(
+            branching.to_json(),
+            "{\"counts\": {\"1\": 3, \"2\": 5, \"3\": 1}, \"branching_factor\": 2.000}"
+        );
+
+        // the ring has no junctions to choose at.
+        assert_eq!(Branching::of(&ring()).branching_factor(), 0.0);
+    }
+
+    #[test]
+    fn difficulty_follows_the_solution() {
+        let difficulty = Difficulty::of(&snake());
+
+        assert_eq!((difficulty.length, difficulty.decisions), (9, 0));
+        assert_eq!(difficulty.dead_end_density, 0.0);
+        assert_eq!(difficulty.score(), 0.4);
+
+        let difficulty = Difficulty::of(&comb());
+
+        assert_eq!(
+            (difficulty.cells, difficulty.length, difficulty.decisions),
+            (9, 5, 2)
+        );
+        assert_eq!(difficulty.dead_end_density, 0.5);
+        assert_eq!(difficulty.rating(), Rating::Hard);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fs::File, io::Write};
+
+use image::codecs::gif::{GifEncoder, Repeat};
+
+use crate::prelude::*;
+
+// the most frames spent on each half of the animation, longer searches skip distances.
+const MAX_FRAMES: usize = 60;
+const FRAME_DELAY_MS: u32 = 80;
+// how many times the finished solution is repeated, so it stays up before looping.
+const HOLD_FRAMES: usize = 15;
+
+// the frames of a solver working through `grid`. The search spreads out from the start one
+// distance at a time, colored as the renderer colors distances, and then the path is traced
+// back from the goal to the start.
+pub fn solver_frames(grid: &RectangularGrid, style: &Style) -> Vec<RgbImage> {
+    let mut grid = grid.clone();
+    let path = grid.solve().clone();
+
+    let start = match path.first() {
+        Some(&start) => start,
+        None => return vec![grid.to_grid_image(style)],
+    };
+
+    let mut distances = Distances::new(start);
+    distances.compute(&grid);
+    let (max_distance, _) = distances.max(&grid);
+
+    let points = grid
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| cell.point)
+        .collect::<Vec<Point>>();
+    let mut frames = Vec::new();
+    grid.path = Vec::new();
+
+    for reached in thresholds(max_distance) {
+        let mut frontier = Distances::new(start);
+
+        for &point in points.iter() {
+            if let Some(distance) = distances.distance(point).filter(|&d| d <= reached) {
+                frontier.set(point, distance);
+            }
+        }
+
+        grid.distances = frontier;
+        frames.push(grid.to_grid_image(style));
+    }
+
+    grid.distances = distances;
+
+    // only the cells the path has reached since the frame before need drawing again.
+    let mut frame = grid.to_grid_image(style);
+    let mut drawn = path.len() - 1;
+
+    for traced in thresholds(path.len() - 1) {
+        let from = path.len() - 1 - traced;
+        grid.path = path[from..].to_vec();
+        grid.redraw(&mut frame, style, &path[from..=drawn]);
+        drawn = from;
+
+        frames.push(frame.clone());
+    }
+
+    if let Some(last) = frames.last().cloned() {
+        frames.extend(std::iter::repeat_n(last, HOLD_FRAMES));
+    }
+
+    return frames;
+}
+
+// counts from 0 up to `last`, in at most MAX_FRAMES even steps.
+fn thresholds(last: usize) -> Vec<usize> {
+    let step = last.div_ceil(MAX_FRAMES).max(1);
+    let mut steps = (0..last).step_by(step).collect::<Vec<usize>>();
+    steps.push(last);
+
+    return steps;
+}
+
+pub fn save_gif(frames: Vec<RgbImage>, path: &str) -> ImageResult<()> {
+    return write_gif(frames, File::create(path)?);
+}
+
+// the frames as a looping GIF, written to `writer`.
+pub fn write_gif<W: Write>(frames: Vec<RgbImage>, writer: W) -> ImageResult<()> {
+    let mut encoder = GifEncoder::new(writer);
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    let delay = Delay::from_numer_denom_ms(FRAME_DELAY_MS, 1);
+    let frames = frames.into_iter().map(|frame| {
+        return Frame::from_parts(DynamicImage::ImageRgb8(frame).into_rgba8(), 0, 0, delay);
+    });
+
+    return encoder.encode_frames(frames);
+}
@@ -0,0 +1,71 @@
+use ::bevy::{
+    app::{App, Plugin, Startup},
+    asset::Assets,
+    color::Color,
+    ecs::system::{Commands, Res, ResMut, Resource},
+    pbr::{PbrBundle, StandardMaterial},
+    render::{
+        mesh::{Indices, Mesh as BevyMesh, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+};
+
+use crate::prelude::*;
+
+impl MeshBuffers {
+    // the buffers as a Bevy mesh of triangles, ready to be added to its assets.
+    pub fn to_bevy_mesh(&self) -> BevyMesh {
+        let mut mesh = BevyMesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+
+        mesh.insert_attribute(BevyMesh::ATTRIBUTE_POSITION, self.positions.clone());
+        mesh.insert_attribute(BevyMesh::ATTRIBUTE_NORMAL, self.normals.clone());
+        mesh.insert_attribute(BevyMesh::ATTRIBUTE_UV_0, self.uvs.clone());
+        mesh.insert_indices(Indices::U32(self.indices.clone()));
+
+        return mesh;
+    }
+}
+
+// MazePlugin : A Bevy plugin spawning the model of a maze when the app starts, each of its
+// materials as a mesh of its own in the material's color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MazePlugin {
+    pub mesh: Mesh,
+}
+
+impl MazePlugin {
+    pub fn new(mesh: Mesh) -> Self {
+        Self { mesh }
+    }
+}
+
+impl Plugin for MazePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MazeBuffers(self.mesh.buffers()))
+            .add_systems(Startup, spawn_maze);
+    }
+}
+
+// MazeBuffers : The buffers of the maze the plugin spawns, kept until the app starts.
+#[derive(Resource)]
+struct MazeBuffers(Vec<MeshBuffers>);
+
+fn spawn_maze(
+    mut commands: Commands,
+    maze: Res<MazeBuffers>,
+    mut meshes: ResMut<Assets<BevyMesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for buffers in &maze.0 {
+        let [red, green, blue] = buffers.material.color();
+
+        commands.spawn(PbrBundle {
+            mesh: meshes.add(buffers.to_bevy_mesh()),
+            material: materials.add(StandardMaterial::from(Color::srgb(red, green, blue))),
+            ..Default::default()
+        });
+    }
+}
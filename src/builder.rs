@@ -0,0 +1,501 @@
+use crate::prelude::*;
+
+// how many seeds are tried in turn for a maze of the difficulty asked for.
+pub const DIFFICULTY_TRIES: u64 = 200;
+
+/// MazeBuilder : The options a maze is made with, gathered up one call at a time and carved
+/// all at once by `build` or `carve`. Every option but the grid, its mask and the algorithm
+/// only shapes rectangular mazes.
+///
+/// ```
+/// use rusty_mazes::{Algorithm, MazeBuilder};
+///
+/// let grid = MazeBuilder::new()
+///     .size(40, 30)
+///     .algorithm(Algorithm::Wilsons)
+///     .seed(7)
+///     .braid(0.2)
+///     .solve()
+///     .build();
+///
+/// assert!(!grid.path.is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MazeBuilder {
+    width: usize,
+    height: usize,
+    mask: Option<Mask>,
+    algorithm: Algorithm,
+    seed: Option<u64>,
+    grid: GridType,
+    depth: usize,
+    graph: Option<GraphGrid>,
+    symmetry: Option<Symmetry>,
+    stitch: Option<(usize, usize)>,
+    nest: Option<(usize, f64)>,
+    rooms: Vec<Rect>,
+    random_rooms: Option<(usize, usize, usize)>,
+    braid: Option<f64>,
+    loops: Option<usize>,
+    lava: Option<(usize, usize)>,
+    portals: Vec<(Point, Point)>,
+    random_portals: Option<usize>,
+    entrances: Option<EntranceType>,
+    one_way: Option<f64>,
+    locks: Option<usize>,
+    difficulty: Option<Rating>,
+    solve: bool,
+}
+
+/// Carved : A maze made by `MazeBuilder::carve`, along with the seed it was carved from and
+/// what was asked of it that couldn't be done, each as a sentence that can be shown as it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Carved {
+    pub grid: AnyGrid,
+    // the seed asked for, or the one a maze of the difficulty asked for was found with.
+    pub seed: Option<u64>,
+    pub problems: Vec<String>,
+}
+
+impl Default for MazeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeBuilder {
+    // an unmasked GRID_WIDTH by GRID_HEIGHT rectangular maze carved by the recursive
+    // backtracker.
+    pub fn new() -> Self {
+        Self {
+            width: GRID_WIDTH,
+            height: GRID_HEIGHT,
+            mask: None,
+            algorithm: Algorithm::RecursiveBacktracker(DirectionWeights::default()),
+            seed: None,
+            grid: GridType::Rectangular,
+            depth: GRID_DEPTH,
+            graph: None,
+            symmetry: None,
+            stitch: None,
+            nest: None,
+            rooms: Vec::new(),
+            random_rooms: None,
+            braid: None,
+            loops: None,
+            lava: None,
+            portals: Vec::new(),
+            random_portals: None,
+            entrances: None,
+            one_way: None,
+            locks: None,
+            difficulty: None,
+            solve: false,
+        }
+    }
+
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        return self;
+    }
+
+    // the cells the maze may use, which sizes the maze in place of `size`.
+    pub fn mask(mut self, mask: Mask) -> Self {
+        self.mask = Some(mask);
+        return self;
+    }
+
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        return self;
+    }
+
+    // reseeds the random number generator before carving, so the same options always make
+    // the same maze. Without a seed the generator carries on from wherever it was.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        return self;
+    }
+
+    // the kind of grid `carve` carves on, rectangular by default. `build` always carves a
+    // rectangular maze.
+    pub fn grid(mut self, grid: GridType) -> Self {
+        self.grid = grid;
+        return self;
+    }
+
+    // how many levels a 3d maze has.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        return self;
+    }
+
+    // the graph a graph maze is carved on, which has no cells until it's given one.
+    pub fn graph(mut self, graph: GraphGrid) -> Self {
+        self.graph = Some(graph);
+        return self;
+    }
+
+    // carves one half of the maze and mirrors it onto the other.
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = Some(symmetry);
+        return self;
+    }
+
+    // carves `count` mazes side by side and stitches each onto the last, joined by up to
+    // `connectors` passages.
+    pub fn stitch(mut self, count: usize, connectors: usize) -> Self {
+        self.stitch = Some((count, connectors));
+        return self;
+    }
+
+    // replaces blocks of `size` by `size` cells with mazes of their own, each with
+    // probability `chance`.
+    pub fn nest(mut self, size: usize, chance: f64) -> Self {
+        self.nest = Some((size, chance));
+        return self;
+    }
+
+    // opens up `rect` as a room with no walls inside it.
+    pub fn room(mut self, rect: Rect) -> Self {
+        self.rooms.push(rect);
+        return self;
+    }
+
+    // places up to `count` more rooms at random, each from `min` to `max` cells across.
+    pub fn random_rooms(mut self, count: usize, min: usize, max: usize) -> Self {
+        self.random_rooms = Some((count, min, max));
+        return self;
+    }
+
+    // removes dead ends once the maze is carved, each with probability `p`.
+    pub fn braid(mut self, p: f64) -> Self {
+        self.braid = Some(p);
+        return self;
+    }
+
+    // knocks down `count` walls between cells that are far apart along the maze.
+    pub fn loops(mut self, count: usize) -> Self {
+        self.loops = Some(count);
+        return self;
+    }
+
+    // gives `count` cells picked at random a cost of `cost` to step into.
+    pub fn lava(mut self, count: usize, cost: usize) -> Self {
+        self.lava = Some((count, cost));
+        return self;
+    }
+
+    // joins `a` and `b` with a portal.
+    pub fn portal(mut self, a: Point, b: Point) -> Self {
+        self.portals.push((a, b));
+        return self;
+    }
+
+    // joins up to `count` pairs of cells picked at random with portals.
+    pub fn random_portals(mut self, count: usize) -> Self {
+        self.random_portals = Some(count);
+        return self;
+    }
+
+    // opens two entrances in the boundary, and measures distances from the first.
+    pub fn entrances(mut self, entrances: EntranceType) -> Self {
+        self.entrances = Some(entrances);
+        return self;
+    }
+
+    // turns passages one-way, each with probability `p`, so long as the maze can still be
+    // solved.
+    pub fn one_way(mut self, p: f64) -> Self {
+        self.one_way = Some(p);
+        return self;
+    }
+
+    // locks up to `count` doors along the solution, each with its key behind the one before.
+    pub fn locks(mut self, count: usize) -> Self {
+        self.locks = Some(count);
+        return self;
+    }
+
+    // carves from the seed and those after it, up to DIFFICULTY_TRIES of them, until a maze
+    // of this rating is found, or keeps the closest.
+    pub fn difficulty(mut self, rating: Rating) -> Self {
+        self.difficulty = Some(rating);
+        return self;
+    }
+
+    // finds the path through the finished maze, kept in the grid's `path`.
+    pub fn solve(mut self) -> Self {
+        self.solve = true;
+        return self;
+    }
+
+    // a rectangular maze with every option, whatever grid was asked for.
+    pub fn build(mut self) -> RectangularGrid {
+        let mask = self.start();
+        let (grid, problems) = self.carve_rectangular(&mask);
+
+        for problem in problems {
+            log::debug!("{}", problem);
+        }
+
+        return grid;
+    }
+
+    // a maze on the grid asked for, along with the seed it was carved from and what
+    // couldn't be done.
+    pub fn carve(mut self) -> Carved {
+        let mask = self.start();
+
+        let mut grid = match self.grid {
+            GridType::Rectangular => {
+                let (grid, problems) = self.carve_rectangular(&mask);
+
+                return Carved {
+                    grid: AnyGrid::Rectangular(grid),
+                    seed: self.seed,
+                    problems,
+                };
+            }
+            GridType::Hex => AnyGrid::Hex(HexGrid::from_mask(&mask)),
+            GridType::Triangle => AnyGrid::Triangle(TriangleGrid::from_mask(&mask)),
+            GridType::Upsilon => AnyGrid::Upsilon(UpsilonGrid::from_mask(&mask)),
+            GridType::Weave => AnyGrid::Weave(WeaveGrid::from_mask(&mask)),
+            GridType::Cylinder => AnyGrid::Cylinder(CylinderGrid::from_mask(&mask)),
+            GridType::Sphere => AnyGrid::Sphere(SphereGrid::from_mask(&mask)),
+            GridType::Graph => AnyGrid::Graph(
+                self.graph
+                    .clone()
+                    .unwrap_or_else(|| GraphGrid::new(Vec::new(), &[])),
+            ),
+            GridType::Penrose => AnyGrid::Penrose(PenroseGrid::from_mask(&mask)),
+            GridType::ThreeD => AnyGrid::ThreeD(Grid3D::from_mask_3d(&mask, self.depth)),
+        };
+
+        self.algorithm.on(grid.grid_mut());
+
+        return Carved {
+            grid,
+            seed: self.seed,
+            problems: Vec::new(),
+        };
+    }
+
+    // carves `runs` mazes on the grid asked for, with none of the options that shape
+    // rectangular mazes, and gathers up how they turned out.
+    pub fn sample(mut self, runs: usize) -> Sample {
+        let mask = self.start();
+        let algorithm = &mut self.algorithm;
+
+        return match self.grid {
+            GridType::Rectangular => {
+                Sample::run(runs, algorithm, || RectangularGrid::from_mask(&mask))
+            }
+            GridType::Hex => Sample::run(runs, algorithm, || HexGrid::from_mask(&mask)),
+            GridType::Triangle => Sample::run(runs, algorithm, || TriangleGrid::from_mask(&mask)),
+            GridType::Upsilon => Sample::run(runs, algorithm, || UpsilonGrid::from_mask(&mask)),
+            GridType::Weave => Sample::run(runs, algorithm, || WeaveGrid::from_mask(&mask)),
+            GridType::Cylinder => Sample::run(runs, algorithm, || CylinderGrid::from_mask(&mask)),
+            GridType::Sphere => Sample::run(runs, algorithm, || SphereGrid::from_mask(&mask)),
+            GridType::Graph => {
+                let graph = self
+                    .graph
+                    .clone()
+                    .unwrap_or_else(|| GraphGrid::new(Vec::new(), &[]));
+
+                Sample::run(runs, algorithm, || graph.clone())
+            }
+            GridType::Penrose => Sample::run(runs, algorithm, || PenroseGrid::from_mask(&mask)),
+            GridType::ThreeD => {
+                Sample::run(runs, algorithm, || Grid3D::from_mask_3d(&mask, self.depth))
+            }
+        };
+    }
+
+    // opens up a carved rectangular maze with loops, lava, portals, entrances, one-way
+    // passages and locked doors as asked for, returning what couldn't be done. Mazes that
+    // are loaded rather than carved are shaped this way too.
+    pub fn shape(&self, grid: &mut RectangularGrid) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if let Some(p) = self.braid {
+            grid.braid(p);
+        }
+
+        if let Some(count) = self.loops {
+            let added = grid.add_loops(count);
+
+            if added < count {
+                problems.push(format!(
+                    "Only {} of {} loops could be added, the maze ran out of walls.",
+                    added, count
+                ));
+            }
+        }
+
+        if let Some((count, cost)) = self.lava {
+            grid.weigh_random_cells(count, cost);
+        }
+
+        for &(a, b) in self.portals.iter() {
+            if !grid.link_portal(a, b) {
+                problems.push(format!(
+                    "No portal joins ({}, {}) and ({}, {}), both must be cells of the maze.",
+                    a.x, a.y, b.x, b.y
+                ));
+            }
+        }
+
+        if let Some(count) = self.random_portals {
+            let added = grid.add_random_portals(count);
+            log::debug!("added {} portals", added.len());
+        }
+
+        if let Some(entrances) = self.entrances {
+            let (start, _) = match entrances {
+                EntranceType::Longest => grid.open_longest_entrances(),
+                EntranceType::Random => grid.open_random_entrances(),
+                EntranceType::Opposite => grid.open_opposite_entrances(),
+            };
+            grid.distances = Distances::new(start.cell);
+        }
+
+        if let Some(p) = self.one_way {
+            let turned = grid.make_one_way(p);
+            log::debug!("made {} passages one-way", turned);
+        }
+
+        if let Some(count) = self.locks {
+            let locked = grid.place_locks(count);
+
+            if locked < count {
+                problems.push(format!(
+                    "Only {} of {} doors could be locked, the solution has too few passages it can't go around.",
+                    locked, count
+                ));
+            }
+        }
+
+        return problems;
+    }
+
+    // reseeds the random number generator when there's a seed, and hands over the mask to
+    // carve.
+    fn start(&mut self) -> Mask {
+        if let Some(seed) = self.seed {
+            random::seed(seed);
+        }
+
+        return self
+            .mask
+            .take()
+            .unwrap_or_else(|| Mask::new(self.width, self.height));
+    }
+
+    // a rectangular maze with every option. With a difficulty, mazes are carved from one
+    // seed after another until one is rated as asked, or the closest is kept, and the seed
+    // is moved on to the one it was carved from.
+    fn carve_rectangular(&mut self, mask: &Mask) -> (RectangularGrid, Vec<String>) {
+        let target = match self.difficulty {
+            Some(target) => target,
+            None => {
+                let (mut grid, problems) = self.carve_rectangular_once(mask);
+
+                if self.solve {
+                    grid.solve();
+                }
+
+                return (grid, problems);
+            }
+        };
+
+        // the seeds tried follow on from the first, so one is needed to start from.
+        let first = *self.seed.get_or_insert_with(|| {
+            let seed = random::random_seed();
+            random::seed(seed);
+            seed
+        });
+
+        let (mut grid, mut problems) = self.carve_rectangular_once(mask);
+        let mut closest = target.distance(Difficulty::of(&grid).score());
+
+        for tries in 1..DIFFICULTY_TRIES {
+            if closest == 0.0 {
+                break;
+            }
+
+            let seed = first.wrapping_add(tries);
+            random::seed(seed);
+
+            let (next, next_problems) = self.carve_rectangular_once(mask);
+            let distance = target.distance(Difficulty::of(&next).score());
+
+            if distance < closest {
+                (grid, problems, closest) = (next, next_problems, distance);
+                self.seed = Some(seed);
+            }
+        }
+
+        if closest > 0.0 {
+            problems.insert(
+                0,
+                format!(
+                    "No {} maze was found in {} tries, so the closest was kept. Another algorithm may suit it better.",
+                    target, DIFFICULTY_TRIES
+                ),
+            );
+        }
+
+        if self.solve {
+            grid.solve();
+        }
+
+        return (grid, problems);
+    }
+
+    // a rectangular maze carved once from the current seed, along with what couldn't be done.
+    fn carve_rectangular_once(&mut self, mask: &Mask) -> (RectangularGrid, Vec<String>) {
+        let mut grid = self.carve_part(mask);
+
+        if let Some((count, connectors)) = self.stitch {
+            for _ in 1..count {
+                let next = self.carve_part(mask);
+
+                let offset = Point::new(grid.width as i32, 0);
+                grid = grid.stitch(&next, offset, connectors);
+            }
+        }
+
+        if let Some((size, chance)) = self.nest {
+            grid = grid.nest(size, chance, &mut self.algorithm);
+        }
+
+        let mut rooms = self.rooms.clone();
+
+        if let Some((count, min, max)) = self.random_rooms {
+            rooms.extend(grid.place_rooms(&rooms, count, min, max));
+        }
+
+        if !rooms.is_empty() {
+            log::debug!("opening {} rooms", rooms.len());
+            grid.open_rooms(&rooms);
+        }
+
+        let problems = self.shape(&mut grid);
+
+        return (grid, problems);
+    }
+
+    // a single rectangular maze over `mask`, before any of it is shaped.
+    fn carve_part(&mut self, mask: &Mask) -> RectangularGrid {
+        return match self.symmetry {
+            Some(symmetry) => RectangularGrid::symmetric(mask, symmetry, &mut self.algorithm),
+            None => {
+                let mut grid = RectangularGrid::from_mask(mask);
+                self.algorithm.on(&mut grid);
+                grid
+            }
+        };
+    }
+}
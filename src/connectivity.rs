@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+// Connectivity : The carved maze as a graph, its cells the nodes and its passages the edges,
+// for studying a maze's structure with other tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connectivity {
+    pub cells: Vec<Point>,
+    // each passage once, as the indices into `cells` of the two cells it joins.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Connectivity {
+    pub fn of(grid: &dyn Grid) -> Self {
+        let cells = grid
+            .cells()
+            .iter()
+            .flatten()
+            .map(|cell| cell.point)
+            .collect::<Vec<Point>>();
+        let indices = cells
+            .iter()
+            .enumerate()
+            .map(|(index, &point)| (point, index))
+            .collect::<HashMap<Point, usize>>();
+
+        // every passage is stored on both of the cells it joins, so it's kept from the first.
+        let mut edges = Vec::new();
+
+        for (index, &point) in cells.iter().enumerate() {
+            for link in grid.links(point) {
+                match indices.get(&link) {
+                    Some(&other) if other > index => edges.push((index, other)),
+                    _ => {}
+                }
+            }
+        }
+
+        edges.sort();
+
+        Self { cells, edges }
+    }
+
+    // cells are given by x and y, and by z too when the maze has more than one level.
+    fn columns(&self, point: Point) -> Vec<String> {
+        let mut columns = vec![point.x.to_string(), point.y.to_string()];
+
+        if self.cells.iter().any(|cell| cell.z != 0) {
+            columns.push(point.z.to_string());
+        }
+
+        return columns;
+    }
+
+    fn label(&self, point: Point) -> String {
+        return self.columns(point).join(":");
+    }
+
+    // a CSV file with a row for each passage, the coordinates of one cell and then the other.
+    pub fn to_edges_csv(&self) -> String {
+        let header = if self.cells.iter().any(|cell| cell.z != 0) {
+            "x1,y1,z1,x2,y2,z2"
+        } else {
+            "x1,y1,x2,y2"
+        };
+
+        let rows = self.edges.iter().map(|&(a, b)| {
+            let mut columns = self.columns(self.cells[a]);
+            columns.extend(self.columns(self.cells[b]));
+            columns.join(",")
+        });
+
+        return std::iter::once(header.to_string())
+            .chain(rows)
+            .map(|row| row + "\n")
+            .collect();
+    }
+
+    // a CSV file of the adjacency matrix, with a 1 wherever the cells of a row and a column
+    // are joined by a passage. Cells are labelled by their coordinates, as in 3:4.
+    pub fn to_adjacency_csv(&self) -> String {
+        let mut matrix = vec![vec![0; self.cells.len()]; self.cells.len()];
+
+        for &(a, b) in self.edges.iter() {
+            matrix[a][b] = 1;
+            matrix[b][a] = 1;
+        }
+
+        let labels = self
+            .cells
+            .iter()
+            .map(|&point| self.label(point))
+            .collect::<Vec<String>>();
+
+        let mut output = format!(",{}\n", labels.join(","));
+
+        for (label, row) in labels.iter().zip(matrix) {
+            let row = row.iter().map(|n| n.to_string()).collect::<Vec<String>>();
+            output.push_str(&format!("{},{}\n", label, row.join(",")));
+        }
+
+        return output;
+    }
+
+    // a Graphviz graph of the maze, each cell pinned where it lies in the maze so neato draws
+    // it in place. Levels of a 3d maze are laid out side by side. The cells and passages of
+    // `path` are drawn in red.
+    pub fn to_dot(&self, path: &[Point]) -> String {
+        let level_width = self.cells.iter().map(|cell| cell.x).max().unwrap_or(0) + 2;
+        let on_path = |point: &Point| path.contains(point);
+
+        let mut output = String::from(
+            "graph maze {\n  layout=neato;\n  node [shape=circle, width=0.15, label=\"\"];\n",
+        );
+
+        for point in self.cells.iter() {
+            // Graphviz's y points up, the maze's down.
+            output.push_str(&format!(
+                "  \"{}\" [pos=\"{},{}!\"{}];\n",
+                self.label(*point),
+                point.x + point.z * level_width,
+                -point.y,
+                if on_path(point) {
+                    ", style=filled, color=red"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        for &(a, b) in self.edges.iter() {
+            let (a, b) = (self.cells[a], self.cells[b]);
+
+            // the passages of the path join two cells that follow each other on it.
+            let solution = path.windows(2).any(|pair| pair == [a, b] || pair == [b, a]);
+
+            output.push_str(&format!(
+                "  \"{}\" -- \"{}\"{};\n",
+                self.label(a),
+                self.label(b),
+                if solution {
+                    " [color=red, penwidth=3]"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        output.push_str("}\n");
+
+        return output;
+    }
+}
@@ -1,92 +1,255 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap},
+};
+
+use rayon::prelude::*;
 
 use crate::prelude::*;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+// how wide the frontier of a search grows before its cells are looked at side by side.
+const PARALLEL_FRONTIER: usize = 1024;
+
+/// Distances : How far each cell is from the nearest of the roots, usually a single
+/// starting cell but possibly many, such as every exit of a maze.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Distances {
-    pub root: Point,
+    pub roots: Vec<Point>,
+    #[serde(with = "crate::pairs")]
     cells: HashMap<Point, usize>,
 }
 
-#[allow(dead_code)]
 impl Distances {
     pub fn new(root: Point) -> Self {
+        return Distances::new_multi(vec![root]);
+    }
+
+    pub fn new_multi(roots: Vec<Point>) -> Self {
         let cells = HashMap::new();
-        Self { root, cells }
+        Self { roots, cells }
+    }
+
+    // the first of the roots, which single rooted distances are measured from.
+    pub fn root(&self) -> Point {
+        return self.roots.first().copied().unwrap_or(Point::zero());
     }
 
     pub fn distance(&self, point: Point) -> Option<usize> {
         return self.cells.get(&point).copied();
     }
 
-    pub fn compute<T: Grid>(&mut self, grid: T) -> &mut Self {
-        self.cells.insert(self.root, 0);
-        let mut frontier = vec![self.root];
+    pub fn set(&mut self, point: Point, distance: usize) {
+        self.cells.insert(point, distance);
+    }
 
-        while !frontier.is_empty() {
-            let mut new_frontier = Vec::new();
+    // the distances from the same roots measured across `grid`, so a grid's own distances
+    // can be measured over it without copying it, as in
+    // `grid.distances = grid.distances.measure(&grid)`.
+    pub fn measure(&self, grid: &dyn Grid) -> Self {
+        let mut distances = Distances::new_multi(self.roots.clone());
+        distances.compute(grid);
 
-            for point in frontier {
-                let cell = grid.get(point);
+        return distances;
+    }
 
-                if cell.is_none() {
-                    continue;
-                }
+    // measures distances breadth first, one step further from the roots each round. Wide
+    // rounds look up the passages of their cells in parallel, then take them in order so
+    // the distances, and the order cells are reached in, match a search on one thread.
+    pub fn compute(&mut self, grid: &dyn Grid) -> &mut Self {
+        // plain breadth first search only works while every step costs the same.
+        if grid.cells().iter().flatten().any(|cell| cell.weight != 1) {
+            log::debug!(
+                "measuring weighted distances from {} roots",
+                self.roots.len()
+            );
+            return self.compute_weighted(grid);
+        }
+
+        log::debug!("measuring distances from {} roots", self.roots.len());
+
+        for &root in self.roots.iter() {
+            self.cells.insert(root, 0);
+        }
+
+        let mut frontier = self.roots.clone();
+        let mut distance = 0;
 
-                for link in cell.unwrap().links() {
-                    if self.cells.contains_key(&link) {
-                        continue;
+        while !frontier.is_empty() {
+            let links = |point: &Point| match grid.get(*point) {
+                Some(_) => grid.links(*point),
+                None => Vec::new(),
+            };
+
+            let reached = if frontier.len() < PARALLEL_FRONTIER {
+                frontier.iter().flat_map(links).collect::<Vec<Point>>()
+            } else {
+                frontier.par_iter().flat_map_iter(links).collect()
+            };
+
+            distance += 1;
+            frontier = reached
+                .into_iter()
+                .filter(|&link| match self.cells.entry(link) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(distance);
+                        true
                     }
+                    Entry::Occupied(_) => false,
+                })
+                .collect();
+        }
 
-                    self.cells.insert(link, self.distance(point).unwrap() + 1);
-                    new_frontier.push(link);
-                }
+        log::debug!("measured distances to {} cells", self.cells.len());
+
+        return self;
+    }
+
+    // Dijkstra's algorithm, where moving into a cell costs that cell's weight.
+    fn compute_weighted(&mut self, grid: &dyn Grid) -> &mut Self {
+        let mut queue = BinaryHeap::new();
+
+        for &root in self.roots.iter() {
+            self.cells.insert(root, 0);
+            queue.push(Reverse((0, root)));
+        }
+
+        while let Some(Reverse((distance, point))) = queue.pop() {
+            if self.distance(point).is_some_and(|d| d < distance) || grid.get(point).is_none() {
+                continue;
             }
 
-            frontier = new_frontier;
+            for link in grid.links(point) {
+                let weight = grid.get(link).map_or(1, |cell| cell.weight);
+                let next = distance + weight;
+
+                if self.distance(link).is_none_or(|d| next < d) {
+                    self.cells.insert(link, next);
+                    queue.push(Reverse((next, link)));
+                }
+            }
         }
 
+        log::debug!("measured distances to {} cells", self.cells.len());
+
         return self;
     }
 
+    // the cells of the shortest path from the nearest root to `goal`, each with its distance.
+    // When `goal` was never reached the path has no cells.
     pub fn shortest_path_to<T: Grid>(&self, grid: &T, goal: Point) -> Self {
         let mut current = goal;
-        let mut breadcrumbs = Distances::new(self.root);
-        breadcrumbs
-            .cells
-            .insert(current, self.distance(current).unwrap());
-
-        while current != self.root {
-            for neighbor in grid.get(current).unwrap().links() {
-                if self.distance(neighbor) < self.distance(current) {
-                    breadcrumbs
-                        .cells
-                        .insert(neighbor, self.distance(neighbor).unwrap());
+        let mut breadcrumbs = Distances::new(goal);
+
+        let mut distance = match self.distance(goal) {
+            Some(distance) => distance,
+            None => return breadcrumbs,
+        };
+        breadcrumbs.cells.insert(current, distance);
+
+        // the path ends at whichever root is nearest to the goal.
+        while !self.roots.contains(&current) {
+            // the previous step is the neighbor exactly this cell's weight closer to the root,
+            // with a passage leading from it into this cell.
+            let weight = grid.get(current).map_or(1, |cell| cell.weight);
+            let previous = match distance.checked_sub(weight) {
+                Some(previous) => previous,
+                None => break,
+            };
+
+            let step = grid
+                .links_into(current)
+                .into_iter()
+                .find(|&neighbor| self.distance(neighbor) == Some(previous));
+
+            match step {
+                Some(neighbor) => {
+                    breadcrumbs.cells.insert(neighbor, previous);
                     current = neighbor;
-                    break;
+                    distance = previous;
                 }
+                None => break,
             }
         }
 
+        breadcrumbs.roots = vec![current];
+
         return breadcrumbs;
     }
 
+    // the cells of the shortest path from the nearest root to `goal`, in order.
+    pub fn path_to<T: Grid>(&self, grid: &T, goal: Point) -> Vec<Point> {
+        let breadcrumbs = self.shortest_path_to(grid, goal);
+        let mut path = breadcrumbs.cells.keys().copied().collect::<Vec<Point>>();
+        path.sort_by_key(|&point| breadcrumbs.distance(point));
+
+        return path;
+    }
+
+    // the path between the two cells furthest apart in the maze, found by walking to the
+    // furthest cell from anywhere and then to the furthest cell from there. The path's root
+    // is one end and its `max` the other.
+    pub fn longest_path<T: Grid>(grid: &T) -> Self {
+        return Distances::longest_path_between(grid, |_| true);
+    }
+
+    // like `longest_path`, but only cells `ends` accepts may be either end of the path.
+    pub fn longest_path_between<T: Grid>(grid: &T, ends: impl Fn(Point) -> bool) -> Self {
+        let start = grid
+            .cells()
+            .iter()
+            .flatten()
+            .map(|cell| cell.point)
+            .find(|&point| ends(point));
+
+        let start = match start {
+            Some(start) => start,
+            None => return Distances::new(Point::zero()),
+        };
+
+        let mut distances = Distances::new(start);
+        distances.compute(grid);
+        let far = distances.furthest(grid, &ends);
+
+        let mut distances = Distances::new(far);
+        distances.compute(grid);
+        let goal = distances.furthest(grid, &ends);
+
+        return distances.shortest_path_to(grid, goal);
+    }
+
+    // the cell `ends` accepts that is furthest from the roots, the first of them in the grid's
+    // order when several are as far, so the same maze always gives the same cell.
+    fn furthest<T: Grid>(&self, grid: &T, ends: &impl Fn(Point) -> bool) -> Point {
+        let mut furthest = None;
+
+        for cell in grid.cells().iter().flatten() {
+            let distance = match self.distance(cell.point) {
+                Some(distance) if ends(cell.point) => distance,
+                _ => continue,
+            };
+
+            if furthest.is_none_or(|(max, _)| distance > max) {
+                furthest = Some((distance, cell.point));
+            }
+        }
+
+        return furthest.map_or(self.root(), |(_, point)| point);
+    }
+
     pub fn max(&self, grid: &dyn Grid) -> (usize, Point) {
         let mut max_distance = 0;
-        let mut max_point = self.root;
-
-        for cell in grid.cells().iter() {
-            if let Some(cell) = cell {
-                let distance = if let Some(distance) = self.distance(cell.point) {
-                    distance
-                } else {
-                    continue;
-                };
-
-                if distance > max_distance {
-                    max_distance = distance;
-                    max_point = cell.point;
-                }
+        let mut max_point = self.root();
+
+        for cell in grid.cells().iter().flatten() {
+            let distance = if let Some(distance) = self.distance(cell.point) {
+                distance
+            } else {
+                continue;
+            };
+
+            if distance > max_distance {
+                max_distance = distance;
+                max_point = cell.point;
             }
         }
 
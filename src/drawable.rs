@@ -1,47 +1,267 @@
 use crate::prelude::*;
 
+// the size in pixels of cells drawn as Braille, where each character is 2 by 4 pixels.
+const BRAILLE_CELL: usize = 4;
+
+/// Style : How a maze is drawn, the size in pixels of each of its cells and its walls, the
+/// colors of its walls and background, and the color the distance gradient fades into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub cell_width: usize,
+    pub cell_height: usize,
+    pub wall_thickness: usize,
+    pub wall_color: Rgb<u8>,
+    pub background: Rgb<u8>,
+    pub gradient: Rgb<u8>,
+}
+
+impl Style {
+    pub fn new(cell_width: usize, cell_height: usize) -> Self {
+        Self {
+            cell_width,
+            cell_height,
+            wall_thickness: 1,
+            wall_color: WHITE,
+            background: BLACK,
+            gradient: GRADIENT,
+        }
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        (self.wall_color, self.background) = theme.colors();
+        return self;
+    }
+}
+
+impl Theme {
+    // the wall and background colors of the theme.
+    pub fn colors(&self) -> (Rgb<u8>, Rgb<u8>) {
+        match self {
+            Theme::Dark => (WHITE, BLACK),
+            Theme::Light => (BLACK, WHITE),
+            Theme::Blueprint => (Rgb([220, 235, 255]), Rgb([20, 70, 140])),
+        }
+    }
+}
+
+// reads a color given by name or as a #rrggbb hex code.
+pub fn parse_color(value: &str) -> Result<Rgb<u8>, String> {
+    let color = match value.to_lowercase().as_str() {
+        "white" => WHITE,
+        "black" => BLACK,
+        "red" => Rgb([200, 0, 0]),
+        "green" => GRADIENT,
+        "blue" => Rgb([0, 60, 200]),
+        "purple" => Rgb([110, 0, 160]),
+        "orange" => Rgb([230, 110, 0]),
+        hex => {
+            let digits = hex.strip_prefix('#').unwrap_or(hex);
+            let channel = |i: usize| {
+                digits
+                    .get(i..i + 2)
+                    .and_then(|c| u8::from_str_radix(c, 16).ok())
+            };
+
+            match (digits.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Rgb([r, g, b]),
+                _ => return Err(format!("'{}' is not a color name or #rrggbb code", value)),
+            }
+        }
+    };
+
+    return Ok(color);
+}
+
+// the pixels of `image` in `ink` as Braille dots, each character covering 2 by 4 pixels.
+pub fn braille(image: &RgbImage, ink: Rgb<u8>) -> String {
+    // the bit of each dot in a character, by its column and row.
+    const DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+    let (width, height) = image.dimensions();
+    let mut output = String::new();
+
+    for top in (0..height).step_by(4) {
+        for left in (0..width).step_by(2) {
+            let mut bits = 0;
+
+            for (column, dots) in DOTS.iter().enumerate() {
+                for (row, dot) in dots.iter().enumerate() {
+                    let (x, y) = (left + column as u32, top + row as u32);
+
+                    if x < width && y < height && *image.get_pixel(x, y) == ink {
+                        bits |= dot;
+                    }
+                }
+            }
+
+            output.push(char::from_u32(0x2800 + bits).unwrap());
+        }
+
+        output.push('\n');
+    }
+
+    return output;
+}
+
+pub fn svg_color(color: Rgb<u8>) -> String {
+    let Rgb([r, g, b]) = color;
+
+    return format!("#{:02x}{:02x}{:02x}", r, g, b);
+}
+
+// the closest color in the 6x6x6 cube of a 256 color terminal.
+pub fn ansi_256(color: Rgb<u8>) -> u8 {
+    let level = |channel: u8| (channel as u16 * 5 + 127) / 255;
+    let Rgb([r, g, b]) = color;
+
+    return (16 + 36 * level(r) + 6 * level(g) + level(b)) as u8;
+}
+
+// a stroked outline following the path data `d`, `width` pixels wide, or nothing when
+// there's nothing to draw.
+pub fn svg_path(d: &str, color: Rgb<u8>, width: usize) -> String {
+    if d.is_empty() {
+        return String::new();
+    }
+
+    return format!(
+        "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" \
+         stroke-linecap=\"square\"/>\n",
+        d,
+        svg_color(color),
+        width
+    );
+}
+
+// wraps `body` in an SVG document filled with `background`, with room for the walls along
+// the right and bottom edges.
+pub fn svg_document(width: usize, height: usize, body: &str, background: Rgb<u8>) -> String {
+    let (width, height) = (width + 1, height + 1);
+
+    return format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"-0.5 -0.5 {w} {h}\">\n<rect x=\"-0.5\" y=\"-0.5\" width=\"{w}\" \
+         height=\"{h}\" fill=\"{}\"/>\n{}</svg>\n",
+        svg_color(background),
+        body,
+        w = width,
+        h = height
+    );
+}
+
+/// Drawable : A grid that can be rendered as an image, and as SVG or Braille, in a `Style`.
 pub trait Drawable {
-    fn to_grid_image(&self, size: usize) -> ImageBuffer<image::Rgb<u8>, Vec<u8>>;
+    // renders the grid with each cell `style.cell_width` by `style.cell_height` pixels, grids
+    // whose cells aren't squares stretch their shapes by the same proportions.
+    fn to_grid_image(&self, style: &Style) -> ImageBuffer<image::Rgb<u8>, Vec<u8>>;
+
+    // cells fade from white at the root to the style's gradient color at `max_distance`,
+    // and cells that can't be reached are left the background color.
+    fn background_color_for(
+        &self,
+        cell: &Cell,
+        distances: &Distances,
+        max_distance: usize,
+        style: &Style,
+    ) -> Rgb<u8> {
+        // heavy cells stand out so the detours taken around them can be seen.
+        if cell.weight > 1 {
+            return HEAVY;
+        }
 
-    fn background_color_for(&self, cell: &Cell, distances: &Distances) -> Rgb<u8> {
         let distance = distances.distance(cell.point);
 
-        if distance.is_none() {
-            return BLACK;
+        if distance.is_none() || max_distance == 0 {
+            return style.background;
         }
 
-        //let (max_distance, _) = distances.max(self);
-        let max_distance = 0;
-        if max_distance == 0 {
-            return BLACK;
-        }
+        let intensity = max_distance.saturating_sub(distance.unwrap()) as f64 / max_distance as f64;
+        let fade = |channel: u8| channel + ((255 - channel) as f64 * intensity) as u8;
+        let Rgb([r, g, b]) = style.gradient;
+
+        return Rgb([fade(r), fade(g), fade(b)]);
+    }
+
+    // the grid drawn in Braille characters, from an image with `BRAILLE_CELL` pixel cells so
+    // every cell is a couple of characters wide.
+    fn to_braille(&self) -> String {
+        let style = Style::new(BRAILLE_CELL, BRAILLE_CELL);
+
+        return braille(&self.to_grid_image(&style), style.wall_color);
+    }
+
+    // the size in pixels of the image `to_grid_image` draws.
+    fn image_size(&self, style: &Style) -> (u32, u32) {
+        return self.to_grid_image(style).dimensions();
+    }
+
+    // the `width` by `height` pixels at `x`, `y` of the image `to_grid_image` draws. Grids
+    // that can draw just the part in view override this to skip drawing the rest.
+    fn to_window_image(&self, style: &Style, x: u32, y: u32, width: u32, height: u32) -> RgbImage {
+        return imageops::crop_imm(&self.to_grid_image(style), x, y, width, height).to_image();
+    }
+
+    // renders the grid like `to_grid_image`, but with its background and masked out cells
+    // transparent. The grid is drawn on two backgrounds with nothing in common, and the
+    // pixels that change between them are the ones the background shows through.
+    fn to_transparent_image(&self, style: &Style) -> RgbaImage {
+        let Rgb([r, g, b]) = style.background;
+        let inverted = Style {
+            background: Rgb([255 - r, 255 - g, 255 - b]),
+            ..*style
+        };
 
-        let intensity = (max_distance - distance.unwrap()) as f64 / max_distance as f64;
-        let dark = (255.0 * intensity) as u8;
-        let bright = 128 + (127.0 * intensity) as u8;
-        let color = image::Rgb([dark, bright, dark]);
+        let image = self.to_grid_image(style);
+        let other = self.to_grid_image(&inverted);
 
-        return color;
+        return RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+            let Rgb([r, g, b]) = *image.get_pixel(x, y);
+            let alpha = if *other.get_pixel(x, y) == Rgb([r, g, b]) {
+                255
+            } else {
+                0
+            };
+
+            return Rgba([r, g, b, alpha]);
+        });
+    }
+
+    // renders the grid as an SVG document measured in the same pixels as `to_grid_image`,
+    // or None for grids that can only be drawn as images.
+    fn to_svg(&self, _style: &Style) -> Option<String> {
+        return None;
     }
 
+    // draws a line `thickness` pixels wide by stamping a square brush, centered on the
+    // line, at each of its points. The brush is kept inside the image, so walls along its
+    // edges are as thick as the rest.
     fn draw_line(
         buff: &mut ImageBuffer<image::Rgb<u8>, Vec<u8>>,
         mut x0: i32,
         mut y0: i32,
         x1: i32,
         y1: i32,
+        thickness: usize,
         color: Rgb<u8>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         let dx = i32::abs(x1 - x0);
         let sx = if x0 < x1 { 1 } else { -1 };
         let dy = -i32::abs(y1 - y0);
         let sy = if y0 < y1 { 1 } else { -1 };
         let mut err = dx + dy; // error value e_xy
+        let thickness = thickness.max(1) as i32;
+        let (width, height) = (buff.width() as i32, buff.height() as i32);
 
         loop {
-            if x0 >= 0 && y0 >= 0 {
-                let pixel = buff.get_pixel_mut(x0 as u32, y0 as u32);
-                *pixel = color;
+            let left = (x0 - (thickness - 1) / 2).min(width - thickness).max(0);
+            let top = (y0 - (thickness - 1) / 2).min(height - thickness).max(0);
+
+            for y in top..(top + thickness).min(height) {
+                for x in left..(left + thickness).min(width) {
+                    buff.put_pixel(x as u32, y as u32, color);
+                }
             }
 
             if x0 == x1 && y0 == y1 {
@@ -65,7 +285,9 @@ pub trait Drawable {
         center_y: u32,
         size: usize,
         color: image::Rgb<u8>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         let diameter = size;
         let mut x = diameter as i32;
         let mut y = 0;
@@ -89,4 +311,51 @@ pub trait Drawable {
             }
         }
     }
+
+    fn fill_polygon(
+        buff: &mut ImageBuffer<image::Rgb<u8>, Vec<u8>>,
+        points: &[(i32, i32)],
+        color: Rgb<u8>,
+    ) where
+        Self: Sized,
+    {
+        if points.len() < 3 {
+            return;
+        }
+
+        let min_y = points.iter().map(|p| p.1).min().unwrap().max(0);
+        let max_y = points
+            .iter()
+            .map(|p| p.1)
+            .max()
+            .unwrap()
+            .min(buff.height() as i32 - 1);
+
+        // scanline fill, sampling each row through the middle of its pixels.
+        for y in min_y..=max_y {
+            let scan = y as f32 + 0.5;
+            let mut crossings = Vec::new();
+
+            for i in 0..points.len() {
+                let (x0, y0) = points[i];
+                let (x1, y1) = points[(i + 1) % points.len()];
+
+                if (y0 as f32 <= scan) != (y1 as f32 <= scan) {
+                    let t = (scan - y0 as f32) / (y1 - y0) as f32;
+                    crossings.push(x0 as f32 + t * (x1 - x0) as f32);
+                }
+            }
+
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            for pair in crossings.chunks_exact(2) {
+                let start = pair[0].round().max(0.0) as i32;
+                let end = (pair[1].round() as i32).min(buff.width() as i32 - 1);
+
+                for x in start..=end {
+                    buff.put_pixel(x as u32, y as u32, color);
+                }
+            }
+        }
+    }
 }
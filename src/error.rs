@@ -0,0 +1,92 @@
+use std::{error::Error, fmt, io};
+
+use image::ImageError;
+
+/// MazeError : What went wrong reading the files and names a maze is made from, each with
+/// a message that can be shown as it is.
+#[derive(Debug)]
+pub enum MazeError {
+    // a file couldn't be read.
+    Io(io::Error),
+    // an image couldn't be decoded.
+    Image(ImageError),
+    // a text mask that isn't laid out as one, with the line it went wrong on.
+    InvalidMask { line: usize, message: String },
+    // a graph file that isn't laid out as one, with the line it went wrong on.
+    InvalidGraph { line: usize, message: String },
+    // an SVG drawing with no shapes that could be read.
+    InvalidSvg(String),
+    // a saved maze that couldn't be rebuilt.
+    InvalidSave(String),
+    // a name that isn't one of `Algorithm::NAMES`.
+    UnknownAlgorithm(String),
+    // a mask split into this many regions that no one maze could join.
+    DisconnectedMask { regions: usize },
+    // an output that couldn't be made from the maze, and why.
+    InvalidOutput(String),
+    // an option given with --huge that a streamed maze couldn't honor.
+    Unstreamable(String),
+    // an algorithm that can't carve a maze on the chosen kind of grid.
+    UnsupportedGrid { algorithm: String, grid: String },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::Io(e) => write!(f, "{}", e),
+            MazeError::Image(e) => write!(f, "{}", e),
+            MazeError::InvalidMask { line, message } => {
+                write!(f, "invalid mask, line {}: {}", line, message)
+            }
+            MazeError::InvalidGraph { line, message } => {
+                write!(f, "invalid graph, line {}: {}", line, message)
+            }
+            MazeError::InvalidSvg(message) => write!(f, "invalid SVG mask: {}", message),
+            MazeError::InvalidSave(message) => write!(f, "invalid saved maze: {}", message),
+            MazeError::UnknownAlgorithm(name) => write!(
+                f,
+                "unknown algorithm '{}', expected one of {}",
+                name,
+                crate::prelude::Algorithm::NAMES.join(", ")
+            ),
+            MazeError::DisconnectedMask { regions } => write!(
+                f,
+                "the mask has {} disconnected regions, so no maze could join them all",
+                regions
+            ),
+            MazeError::InvalidOutput(message) => write!(f, "{}", message),
+            MazeError::Unstreamable(option) => write!(
+                f,
+                "{} can't be used with --huge, which carves with Eller's algorithm and only writes a --to-png image",
+                option
+            ),
+            MazeError::UnsupportedGrid { algorithm, grid } => write!(
+                f,
+                "{} can't carve a perfect maze on a {} grid, as it only links cells to the north and east; choose another --algorithm",
+                algorithm, grid
+            ),
+        }
+    }
+}
+
+impl Error for MazeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MazeError::Io(e) => Some(e),
+            MazeError::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MazeError {
+    fn from(e: io::Error) -> Self {
+        return MazeError::Io(e);
+    }
+}
+
+impl From<ImageError> for MazeError {
+    fn from(e: ImageError) -> Self {
+        return MazeError::Image(e);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::prelude::*;
+
+// GridType : The kinds of grid a maze can be carved on.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridType {
+    Rectangular,
+    Hex,
+    Triangle,
+    Upsilon,
+    Weave,
+    Cylinder,
+    Sphere,
+    Graph,
+    Penrose,
+    #[value(name = "3d")]
+    ThreeD,
+}
+
+impl GridType {
+    // the shape of the grid's cells, in a line.
+    pub fn description(&self) -> &'static str {
+        match self {
+            GridType::Rectangular => "Square cells in rows and columns, with every output.",
+            GridType::Hex => "Hexagons, each touching up to six neighbors.",
+            GridType::Triangle => "Alternating upward and downward pointing triangles.",
+            GridType::Upsilon => "A checkerboard of octagons and squares.",
+            GridType::Weave => "Square cells whose passages may tunnel beneath each other.",
+            GridType::Cylinder => "Square cells rolled into a tube that wraps east to west.",
+            GridType::Sphere => "Six square faces folded into a cube, with no boundary.",
+            GridType::Graph => "Any graph of nodes and edges, read from --graph.",
+            GridType::Penrose => "The rhombuses of a Penrose tiling, which never repeats.",
+            GridType::ThreeD => "Rectangular levels stacked --depth high, joined by stairs.",
+        }
+    }
+}
+
+// AnyGrid : A maze on any of the kinds of grid, for when which one is only known once the
+// program runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyGrid {
+    Rectangular(RectangularGrid),
+    Hex(HexGrid),
+    Triangle(TriangleGrid),
+    Upsilon(UpsilonGrid),
+    Weave(WeaveGrid),
+    Cylinder(CylinderGrid),
+    Sphere(SphereGrid),
+    Graph(GraphGrid),
+    Penrose(PenroseGrid),
+    ThreeD(Grid3D),
+}
+
+impl AnyGrid {
+    pub fn grid(&self) -> &dyn Grid {
+        match self {
+            AnyGrid::Rectangular(grid) => grid,
+            AnyGrid::Hex(grid) => grid,
+            AnyGrid::Triangle(grid) => grid,
+            AnyGrid::Upsilon(grid) => grid,
+            AnyGrid::Weave(grid) => grid,
+            AnyGrid::Cylinder(grid) => grid,
+            AnyGrid::Sphere(grid) => grid,
+            AnyGrid::Graph(grid) => grid,
+            AnyGrid::Penrose(grid) => grid,
+            AnyGrid::ThreeD(grid) => grid,
+        }
+    }
+
+    pub fn grid_mut(&mut self) -> &mut dyn Grid {
+        match self {
+            AnyGrid::Rectangular(grid) => grid,
+            AnyGrid::Hex(grid) => grid,
+            AnyGrid::Triangle(grid) => grid,
+            AnyGrid::Upsilon(grid) => grid,
+            AnyGrid::Weave(grid) => grid,
+            AnyGrid::Cylinder(grid) => grid,
+            AnyGrid::Sphere(grid) => grid,
+            AnyGrid::Graph(grid) => grid,
+            AnyGrid::Penrose(grid) => grid,
+            AnyGrid::ThreeD(grid) => grid,
+        }
+    }
+
+    pub fn drawable(&self) -> &dyn Drawable {
+        match self {
+            AnyGrid::Rectangular(grid) => grid,
+            AnyGrid::Hex(grid) => grid,
+            AnyGrid::Triangle(grid) => grid,
+            AnyGrid::Upsilon(grid) => grid,
+            AnyGrid::Weave(grid) => grid,
+            AnyGrid::Cylinder(grid) => grid,
+            AnyGrid::Sphere(grid) => grid,
+            AnyGrid::Graph(grid) => grid,
+            AnyGrid::Penrose(grid) => grid,
+            AnyGrid::ThreeD(grid) => grid,
+        }
+    }
+
+    // measures how far every cell is from the roots of the grid's distances, so they can
+    // be drawn.
+    pub fn measure_distances(&mut self) {
+        match self {
+            AnyGrid::Rectangular(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Hex(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Triangle(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Upsilon(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Weave(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Cylinder(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Sphere(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Graph(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::Penrose(grid) => grid.distances = grid.distances.measure(grid),
+            AnyGrid::ThreeD(grid) => grid.distances = grid.distances.measure(grid),
+        }
+    }
+}
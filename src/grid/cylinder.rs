@@ -0,0 +1,251 @@
+use crate::prelude::*;
+
+// CylinderGrid : A rectangular grid rolled into a tube, the eastmost column neighbors the
+// westmost one so passages can wrap around horizontally.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CylinderGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl CylinderGrid {
+    fn new(width: usize, height: usize) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(Some(Cell::new(Point::new(x as i32, y as i32))));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    // the point with its x wrapped back onto the grid.
+    pub fn wrap(&self, point: Point) -> Point {
+        Point::new_3d(point.x.rem_euclid(self.width as i32), point.y, point.z)
+    }
+
+    fn draw_seam(
+        buff: &mut ImageBuffer<image::Rgb<u8>, Vec<u8>>,
+        x: i32,
+        y1: i32,
+        y2: i32,
+        color: Rgb<u8>,
+    ) {
+        for y in (y1..=y2).step_by(3) {
+            if y >= 0 && (y as u32) < buff.height() {
+                buff.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
+
+impl Grid for CylinderGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        let (a, b) = (self.wrap(a), self.wrap(b));
+
+        if self.get(a).is_none() || self.get(b).is_none() {
+            return;
+        }
+
+        self.links.link(a, b, bidi);
+    }
+
+    fn links(&self, point: Point) -> Vec<Point> {
+        return self.links.links(self.wrap(point));
+    }
+
+    fn linked(&self, a: Point, b: Point) -> bool {
+        return self.links.linked(self.wrap(a), self.wrap(b));
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        let mut neighbors = Vec::new();
+
+        for neighbor in [point.north(), point.south(), point.east(), point.west()] {
+            let neighbor = self.wrap(neighbor);
+
+            // a cylinder a single cell around would otherwise be its own neighbor.
+            if neighbor != point && self.get(neighbor).is_some() && !neighbors.contains(&neighbor) {
+                neighbors.push(neighbor);
+            }
+        }
+
+        return neighbors;
+    }
+
+    fn point_to_index(&self, point: Point) -> Option<usize> {
+        if point.y < 0 || point.y >= self.height as i32 || point.z != 0 || self.width == 0 {
+            return None;
+        }
+
+        let point = self.wrap(point);
+
+        return Some(point.y as usize * self.width + point.x as usize);
+    }
+}
+
+impl Drawable for CylinderGrid {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let img_width = self.width * cell_width + 1;
+        let img_height = self.height * cell_height + 1;
+
+        let mut imgbuf =
+            image::ImageBuffer::from_fn(img_width as u32, img_height as u32, |_, _| {
+                return style.background;
+            });
+
+        for mode in ["background", "walls"] {
+            for cell in self.cells.iter().flatten() {
+                let point = cell.point;
+                let (x1, x2, y1, y2) = (
+                    point.x * cell_width as i32,
+                    (point.x + 1) * cell_width as i32,
+                    point.y * cell_height as i32,
+                    (point.y + 1) * cell_height as i32,
+                );
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    CylinderGrid::fill_polygon(
+                        &mut imgbuf,
+                        &[(x1, y1), (x2, y1), (x2, y2), (x1, y2)],
+                        color,
+                    );
+                    continue;
+                }
+
+                if !self.linked(point, point.north()) {
+                    CylinderGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y1,
+                        x2,
+                        y1,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(point, point.south()) {
+                    CylinderGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y2,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                // passages crossing the seam are marked with a dotted line on both edges of
+                // the image, so it's clear where the maze continues on the other side.
+                let at_seam_west = point.x == 0;
+                let at_seam_east = point.x == self.width as i32 - 1;
+
+                if !self.linked(point, point.west()) {
+                    CylinderGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y1,
+                        x1,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                } else if at_seam_west {
+                    CylinderGrid::draw_seam(&mut imgbuf, x1, y1, y2, style.wall_color);
+                }
+
+                if !self.linked(point, point.east()) {
+                    CylinderGrid::draw_line(
+                        &mut imgbuf,
+                        x2,
+                        y1,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                } else if at_seam_east {
+                    CylinderGrid::draw_seam(&mut imgbuf, x2, y1, y2, style.wall_color);
+                }
+            }
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Maskable for CylinderGrid {
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = CylinderGrid::new(mask.width, mask.height);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        for (i, value) in mask.mask.iter().enumerate() {
+            if !value {
+                self.cells[i] = None;
+            }
+        }
+    }
+}
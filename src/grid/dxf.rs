@@ -0,0 +1,59 @@
+use crate::prelude::*;
+
+impl RectangularGrid {
+    // the walls joined into the longest straight runs they make, so each is cut in one pass.
+    fn wall_runs(&self) -> Vec<(Point, Point)> {
+        let horizontal = |(from, to): &(Point, Point)| from.y == to.y;
+
+        let mut walls = self.walls();
+        walls.sort_by_key(|wall| {
+            let (from, _) = *wall;
+
+            if horizontal(wall) {
+                (0, from.y, from.x)
+            } else {
+                (1, from.x, from.y)
+            }
+        });
+
+        let mut runs: Vec<(Point, Point)> = Vec::new();
+
+        for wall in walls {
+            match runs.last_mut() {
+                Some(run) if run.1 == wall.0 && horizontal(run) == horizontal(&wall) => {
+                    run.1 = wall.1;
+                }
+                _ => runs.push(wall),
+            }
+        }
+
+        return runs;
+    }
+
+    // the walls as the lines of a DXF drawing measured in millimeters, each cell `cell_size`
+    // across, for laser cutters and CAD programs. DXF's y points up, so rows are flipped to
+    // keep the drawing the right way round.
+    pub fn to_dxf(&self, cell_size: f32) -> String {
+        let height = self.height as f32 * cell_size;
+        let mut output = String::from(
+            "0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n4\n0\nENDSEC\n0\nSECTION\n2\nENTITIES\n",
+        );
+
+        for (from, to) in self.wall_runs() {
+            let (x0, y0) = (
+                from.x as f32 * cell_size,
+                height - from.y as f32 * cell_size,
+            );
+            let (x1, y1) = (to.x as f32 * cell_size, height - to.y as f32 * cell_size);
+
+            output.push_str(&format!(
+                "0\nLINE\n8\nwalls\n10\n{}\n20\n{}\n30\n0\n11\n{}\n21\n{}\n31\n0\n",
+                x0, y0, x1, y1
+            ));
+        }
+
+        output.push_str("0\nENDSEC\n0\nEOF\n");
+
+        return output;
+    }
+}
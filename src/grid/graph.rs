@@ -0,0 +1,247 @@
+use std::fs;
+
+use crate::prelude::*;
+
+// GraphGrid : A maze over any graph, such as a planar graph that isn't a lattice at all.
+// Each node is a cell with its own position, and edges list which cells neighbor each
+// other. Cells are identified by the order their nodes were declared, Point::new(id, 0).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GraphGrid {
+    pub cells: Vec<Option<Cell>>,
+    pub positions: Vec<(f32, f32)>,
+    pub adjacency: Vec<Vec<Point>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl GraphGrid {
+    pub fn new(positions: Vec<(f32, f32)>, edges: &[(usize, usize)]) -> Self {
+        let cells = (0..positions.len())
+            .map(|i| Some(Cell::new(Point::new(i as i32, 0))))
+            .collect();
+        let mut adjacency = vec![Vec::new(); positions.len()];
+
+        for &(a, b) in edges {
+            if a == b || adjacency[a].contains(&Point::new(b as i32, 0)) {
+                continue;
+            }
+
+            adjacency[a].push(Point::new(b as i32, 0));
+            adjacency[b].push(Point::new(a as i32, 0));
+        }
+
+        Self {
+            cells,
+            positions,
+            adjacency,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    // reads a graph made of `node <x> <y>` and `edge <a> <b>` lines, where edges refer to
+    // nodes by the order they were declared in. Blank lines and lines starting with # are
+    // ignored.
+    pub fn from_txt(file_path: &str) -> Result<GraphGrid, MazeError> {
+        let data = fs::read_to_string(file_path)
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", file_path, e)))?;
+        let invalid = |line: usize, message: &str| MazeError::InvalidGraph {
+            line: line + 1,
+            message: message.to_string(),
+        };
+
+        let mut positions = Vec::new();
+        let mut edges = Vec::new();
+        // the line each edge was declared on, for edges to nodes that never are.
+        let mut edge_lines = Vec::new();
+
+        for (i, line) in data.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let kind = parts.next().unwrap();
+            let values = parts.collect::<Vec<&str>>();
+
+            if values.len() != 2 {
+                return Err(invalid(i, "expected two values"));
+            }
+
+            match kind {
+                "node" => {
+                    let x = values[0].parse::<f32>();
+                    let y = values[1].parse::<f32>();
+
+                    match (x, y) {
+                        (Ok(x), Ok(y)) => positions.push((x, y)),
+                        _ => return Err(invalid(i, "invalid node position")),
+                    }
+                }
+                "edge" => {
+                    let a = values[0].parse::<usize>();
+                    let b = values[1].parse::<usize>();
+
+                    match (a, b) {
+                        (Ok(a), Ok(b)) => {
+                            edges.push((a, b));
+                            edge_lines.push(i);
+                        }
+                        _ => return Err(invalid(i, "invalid edge")),
+                    }
+                }
+                _ => return Err(invalid(i, "expected a node or an edge")),
+            }
+        }
+
+        if let Some(index) = edges
+            .iter()
+            .position(|&(a, b)| a >= positions.len() || b >= positions.len())
+        {
+            let (a, b) = edges[index];
+
+            return Err(invalid(
+                edge_lines[index],
+                &format!("edge {} {} refers to a node that doesn't exist", a, b),
+            ));
+        }
+
+        let mut grid = GraphGrid::new(positions, &edges);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return Ok(grid);
+    }
+}
+
+impl Grid for GraphGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn height(&self) -> usize {
+        1
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        if !self.neighbors(a).contains(&b) {
+            return;
+        }
+
+        self.links.link(a, b, bidi);
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        match self.point_to_index(point) {
+            Some(index) => self.adjacency[index]
+                .iter()
+                .copied()
+                .filter(|&p| self.get(p).is_some())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Drawable for GraphGrid {
+    // node positions are scaled by `cell_width` pixels per unit across and `cell_height`
+    // down, carved edges are drawn as passages between the nodes they join.
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let (scale_x, scale_y) = (cell_width as f32, cell_height as f32);
+        let min_x = self.positions.iter().map(|p| p.0).fold(f32::MAX, f32::min);
+        let min_y = self.positions.iter().map(|p| p.1).fold(f32::MAX, f32::min);
+        let max_x = self.positions.iter().map(|p| p.0).fold(f32::MIN, f32::max);
+        let max_y = self.positions.iter().map(|p| p.1).fold(f32::MIN, f32::max);
+
+        // leave half a unit around the outermost nodes.
+        let (margin_x, margin_y) = (scale_x / 2.0, scale_y / 2.0);
+        let img_width = ((max_x - min_x).max(0.0) * scale_x + 2.0 * margin_x).ceil() as u32 + 1;
+        let img_height = ((max_y - min_y).max(0.0) * scale_y + 2.0 * margin_y).ceil() as u32 + 1;
+
+        let mut imgbuf = image::ImageBuffer::from_fn(img_width, img_height, |_, _| {
+            return style.background;
+        });
+
+        let at = |point: Point| {
+            let (x, y) = self.positions[point.x as usize];
+            (
+                (margin_x + (x - min_x) * scale_x).round() as i32,
+                (margin_y + (y - min_y) * scale_y).round() as i32,
+            )
+        };
+
+        for cell in self.cells.iter().flatten() {
+            let (x1, y1) = at(cell.point);
+
+            for link in self.links(cell.point) {
+                if link.x > cell.point.x {
+                    let (x2, y2) = at(link);
+                    GraphGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+            }
+        }
+
+        let radius = (cell_width.min(cell_height) / 8).max(1) as i32;
+
+        for cell in self.cells.iter().flatten() {
+            let (x, y) = at(cell.point);
+            let color = match self.background_color_for(cell, &self.distances, max_distance, style)
+            {
+                color if color == style.background => style.wall_color,
+                color => color,
+            };
+            let corners = [
+                (x - radius, y - radius),
+                (x + radius, y - radius),
+                (x + radius, y + radius),
+                (x - radius, y + radius),
+            ];
+
+            GraphGrid::fill_polygon(&mut imgbuf, &corners, color);
+        }
+
+        return imgbuf;
+    }
+}
@@ -0,0 +1,377 @@
+use crate::prelude::*;
+
+// Grid3D : A stack of rectangular levels. Besides its north, east, south and west
+// neighbors every cell touches the cells directly above (z + 1) and below (z - 1) it, which
+// a maze reaches by taking the stairs.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Grid3D {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub distances: Distances,
+    pub links: Links,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl Grid3D {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        let mut cells = Vec::with_capacity(width * height * depth);
+
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    cells.push(Some(Cell::new(Point::new_3d(x as i32, y as i32, z as i32))));
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            depth,
+            cells,
+            distances: Distances::new(Point::new(0, 0)),
+            links: Links::new(),
+            live: LiveCells::new(),
+        }
+    }
+
+    pub fn from_mask_3d(mask: &Mask, depth: usize) -> Self {
+        let mut grid = Grid3D::new(mask.width, mask.height, depth);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+}
+
+impl Grid for Grid3D {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        return [
+            point.north(),
+            point.south(),
+            point.east(),
+            point.west(),
+            point + Point::new_3d(0, 0, 1),
+            point + Point::new_3d(0, 0, -1),
+        ]
+        .into_iter()
+        .filter(|&p| self.get(p).is_some())
+        .collect();
+    }
+
+    fn point_to_index(&self, point: Point) -> Option<usize> {
+        if point.x < 0
+            || point.y < 0
+            || point.z < 0
+            || point.x >= self.width as i32
+            || point.y >= self.height as i32
+            || point.z >= self.depth as i32
+        {
+            return None;
+        }
+
+        let level = self.width * self.height;
+
+        return Some(point.z as usize * level + point.y as usize * self.width + point.x as usize);
+    }
+}
+
+impl Drawable for Grid3D {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        // levels are laid out left to right, a cell's width apart.
+        let level_width = self.width * cell_width;
+        let img_width = self.depth * level_width + (self.depth - 1) * cell_width + 1;
+        let img_height = self.height * cell_height + 1;
+
+        let mut imgbuf =
+            image::ImageBuffer::from_fn(img_width as u32, img_height as u32, |_, _| {
+                return style.background;
+            });
+
+        for z in 0..self.depth {
+            let offset = z * (level_width + cell_width);
+            self.draw_level(&mut imgbuf, z, offset as i32, style, max_distance);
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Grid3D {
+    // one level of the maze drawn on its own, with its stairs marked as they are when all the
+    // levels are drawn side by side.
+    pub fn level_image(&self, z: usize, style: &Style) -> RgbImage {
+        let (max_distance, _) = self.distances.max(self);
+        let mut imgbuf = RgbImage::from_pixel(
+            (self.width * style.cell_width + 1) as u32,
+            (self.height * style.cell_height + 1) as u32,
+            style.background,
+        );
+
+        self.draw_level(&mut imgbuf, z, 0, style, max_distance);
+
+        return imgbuf;
+    }
+
+    // level `z` of the maze as text, drawn as a rectangular maze is with +, - and | or, when
+    // `unicode` is set, box drawing characters. Cells whose stairs lead up are marked U,
+    // those leading down D and those leading both ways X.
+    pub fn level_to_text(&self, z: usize, unicode: bool) -> String {
+        let mut level = RectangularGrid::new(self.width, self.height);
+
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let (flat, point) = (Point::new(x, y), Point::new_3d(x, y, z as i32));
+                let index = level.point_to_index(flat).unwrap();
+
+                if self.get(point).is_none() {
+                    level.cells[index] = None;
+                    continue;
+                }
+
+                for link in self
+                    .links(point)
+                    .into_iter()
+                    .filter(|link| link.z == point.z)
+                {
+                    level.link(flat, Point::new(link.x, link.y), false);
+                }
+
+                if let Some(distance) = self.distances.distance(point) {
+                    level.distances.set(flat, distance);
+                }
+            }
+        }
+
+        let text = if unicode {
+            level.to_unicode(None)
+        } else {
+            level.to_ascii(None)
+        };
+        let mut lines = text
+            .lines()
+            .map(|line| line.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+
+        for cell in self
+            .cells
+            .iter()
+            .flatten()
+            .filter(|c| c.point.z == z as i32)
+        {
+            let up = self.linked(cell.point, cell.point + Point::new_3d(0, 0, 1));
+            let down = self.linked(cell.point, cell.point + Point::new_3d(0, 0, -1));
+            let glyph = match (up, down) {
+                (true, true) => 'X',
+                (true, false) => 'U',
+                (false, true) => 'D',
+                (false, false) => continue,
+            };
+
+            // each row of cells is every other line, and each cell is four characters wide
+            // with its middle character free to mark.
+            let (x, y) = (cell.point.x as usize * 4 + 2, cell.point.y as usize * 2 + 1);
+
+            if let Some(spot) = lines.get_mut(y).and_then(|line| line.get_mut(x)) {
+                *spot = glyph;
+            }
+        }
+
+        return lines
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>() + "\n")
+            .collect();
+    }
+
+    fn draw_level(
+        &self,
+        imgbuf: &mut RgbImage,
+        z: usize,
+        offset: i32,
+        style: &Style,
+        max_distance: usize,
+    ) {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (inset_x, inset_y) = ((cell_width / 4) as i32, (cell_height / 4) as i32);
+
+        for mode in ["background", "walls"] {
+            for cell in self
+                .cells
+                .iter()
+                .flatten()
+                .filter(|c| c.point.z == z as i32)
+            {
+                let (x1, x2, y1, y2) = (
+                    offset + cell.point.x * cell_width as i32,
+                    offset + (cell.point.x + 1) * cell_width as i32,
+                    cell.point.y * cell_height as i32,
+                    (cell.point.y + 1) * cell_height as i32,
+                );
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    Grid3D::fill_polygon(imgbuf, &[(x1, y1), (x2, y1), (x2, y2), (x1, y2)], color);
+                    continue;
+                }
+
+                if !self.linked(cell.point, cell.point.north()) {
+                    Grid3D::draw_line(
+                        imgbuf,
+                        x1,
+                        y1,
+                        x2,
+                        y1,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(cell.point, cell.point.west()) {
+                    Grid3D::draw_line(
+                        imgbuf,
+                        x1,
+                        y1,
+                        x1,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(cell.point, cell.point.east()) {
+                    Grid3D::draw_line(
+                        imgbuf,
+                        x2,
+                        y1,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(cell.point, cell.point.south()) {
+                    Grid3D::draw_line(
+                        imgbuf,
+                        x1,
+                        y2,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                // stairs are marked with an arrow pointing the way they lead, up in the
+                // top half of the cell and down in the bottom half.
+                let mid_x = (x1 + x2) / 2;
+                let mid_y = (y1 + y2) / 2;
+
+                if self.linked(cell.point, cell.point + Point::new_3d(0, 0, 1)) {
+                    Grid3D::draw_line(
+                        imgbuf,
+                        x1 + inset_x,
+                        mid_y,
+                        mid_x,
+                        y1 + inset_y,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    Grid3D::draw_line(
+                        imgbuf,
+                        mid_x,
+                        y1 + inset_y,
+                        x2 - inset_x,
+                        mid_y,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if self.linked(cell.point, cell.point + Point::new_3d(0, 0, -1)) {
+                    Grid3D::draw_line(
+                        imgbuf,
+                        x1 + inset_x,
+                        mid_y,
+                        mid_x,
+                        y2 - inset_y,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    Grid3D::draw_line(
+                        imgbuf,
+                        mid_x,
+                        y2 - inset_y,
+                        x2 - inset_x,
+                        mid_y,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Maskable for Grid3D {
+    fn from_mask(mask: &Mask) -> Self {
+        return Grid3D::from_mask_3d(mask, GRID_DEPTH);
+    }
+
+    // the same mask is applied to every level.
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        let level = self.width * self.height;
+
+        for z in 0..self.depth {
+            for (i, value) in mask.mask.iter().enumerate() {
+                if !value {
+                    self.cells[z * level + i] = None;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::prelude::*;
+
+// HexGrid : A sigma maze of flat-topped hexagons. Odd columns sit half a cell lower than
+// even columns, so each cell touches up to six neighbors: north, south and the four
+// diagonals on either side.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HexGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl HexGrid {
+    fn new(width: usize, height: usize) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(Some(Cell::new(Point::new(x as i32, y as i32))));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    // the rows of the diagonal neighbors above and below a cell, which depend on whether
+    // its column is shifted down.
+    fn diagonal_rows(point: Point) -> (i32, i32) {
+        if point.x % 2 == 0 {
+            (point.y - 1, point.y)
+        } else {
+            (point.y, point.y + 1)
+        }
+    }
+
+    pub fn northwest(point: Point) -> Point {
+        Point::new(point.x - 1, HexGrid::diagonal_rows(point).0)
+    }
+
+    pub fn northeast(point: Point) -> Point {
+        Point::new(point.x + 1, HexGrid::diagonal_rows(point).0)
+    }
+
+    pub fn southwest(point: Point) -> Point {
+        Point::new(point.x - 1, HexGrid::diagonal_rows(point).1)
+    }
+
+    pub fn southeast(point: Point) -> Point {
+        Point::new(point.x + 1, HexGrid::diagonal_rows(point).1)
+    }
+}
+
+impl Grid for HexGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        return [
+            point.north(),
+            HexGrid::northeast(point),
+            HexGrid::southeast(point),
+            point.south(),
+            HexGrid::southwest(point),
+            HexGrid::northwest(point),
+        ]
+        .into_iter()
+        .filter(|&p| self.get(p).is_some())
+        .collect();
+    }
+}
+
+impl Drawable for HexGrid {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let size = cell_width as f32;
+        let a_size = size / 2.0;
+        let b_size = cell_height as f32 * 3f32.sqrt() / 2.0;
+        let cell_height = b_size * 2.0;
+
+        let img_width = (3.0 * a_size * self.width as f32 + a_size + 0.5) as u32 + 1;
+        let img_height = (cell_height * self.height as f32 + b_size + 0.5) as u32 + 1;
+
+        let mut imgbuf = image::ImageBuffer::from_fn(img_width, img_height, |_, _| {
+            return style.background;
+        });
+
+        for mode in ["background", "walls"] {
+            for cell in self.cells.iter().flatten() {
+                let point = cell.point;
+                let cx = size + 3.0 * point.x as f32 * a_size;
+                let mut cy = b_size + point.y as f32 * cell_height;
+
+                if point.x % 2 == 1 {
+                    cy += b_size;
+                }
+
+                let x_fw = (cx - size).round() as i32;
+                let x_nw = (cx - a_size).round() as i32;
+                let x_ne = (cx + a_size).round() as i32;
+                let x_fe = (cx + size).round() as i32;
+                let y_n = (cy - b_size).round() as i32;
+                let y_m = cy.round() as i32;
+                let y_s = (cy + b_size).round() as i32;
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    let corners = [
+                        (x_fw, y_m),
+                        (x_nw, y_n),
+                        (x_ne, y_n),
+                        (x_fe, y_m),
+                        (x_ne, y_s),
+                        (x_nw, y_s),
+                    ];
+                    HexGrid::fill_polygon(&mut imgbuf, &corners, color);
+                    continue;
+                }
+
+                // walls shared with an existing neighbor are drawn once, by whichever of the
+                // two cells owns that side, while walls against the boundary or a masked
+                // out cell are always drawn.
+                if self.get(HexGrid::southwest(point)).is_none() {
+                    HexGrid::draw_line(
+                        &mut imgbuf,
+                        x_fw,
+                        y_m,
+                        x_nw,
+                        y_s,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if self.get(HexGrid::northwest(point)).is_none() {
+                    HexGrid::draw_line(
+                        &mut imgbuf,
+                        x_fw,
+                        y_m,
+                        x_nw,
+                        y_n,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if self.get(point.north()).is_none() {
+                    HexGrid::draw_line(
+                        &mut imgbuf,
+                        x_nw,
+                        y_n,
+                        x_ne,
+                        y_n,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(point, HexGrid::northeast(point)) {
+                    HexGrid::draw_line(
+                        &mut imgbuf,
+                        x_ne,
+                        y_n,
+                        x_fe,
+                        y_m,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(point, HexGrid::southeast(point)) {
+                    HexGrid::draw_line(
+                        &mut imgbuf,
+                        x_fe,
+                        y_m,
+                        x_ne,
+                        y_s,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(point, point.south()) {
+                    HexGrid::draw_line(
+                        &mut imgbuf,
+                        x_ne,
+                        y_s,
+                        x_nw,
+                        y_s,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+            }
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Maskable for HexGrid {
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = HexGrid::new(mask.width, mask.height);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        for (i, value) in mask.mask.iter().enumerate() {
+            if !value {
+                self.cells[i] = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+// Links : The passages carved between the cells of a grid, each cell's kept under its point
+// in the order they were carved. A one-way passage is only kept under the cell it leads out
+// of.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Links {
+    #[serde(with = "crate::pairs")]
+    links: HashMap<Point, Vec<Point>>,
+}
+
+impl Links {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        let links = self.links.entry(a).or_default();
+
+        if !links.contains(&b) {
+            links.push(b);
+        }
+
+        if bidi {
+            self.link(b, a, false);
+        }
+    }
+
+    pub fn unlink(&mut self, a: Point, b: Point, bidi: bool) {
+        if let Some(links) = self.links.get_mut(&a) {
+            links.retain(|&link| link != b);
+        }
+
+        if bidi {
+            self.unlink(b, a, false);
+        }
+    }
+
+    pub fn links(&self, point: Point) -> Vec<Point> {
+        return self.links.get(&point).cloned().unwrap_or_default();
+    }
+
+    pub fn linked(&self, a: Point, b: Point) -> bool {
+        return self
+            .links
+            .get(&a)
+            .map(|links| links.contains(&b))
+            .unwrap_or(false);
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::OnceLock;
+
+use crate::prelude::*;
+
+// LiveCells : The indices of the cells a grid has, those not masked out, gathered the first
+// time they're needed and kept until the grid's cells change.
+#[derive(Debug, Clone, Default)]
+pub struct LiveCells {
+    indices: OnceLock<Vec<usize>>,
+}
+
+impl LiveCells {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the indices of the cells in `cells` that aren't masked out, gathered from them unless
+    // they already have been.
+    pub fn indices(&self, cells: &[Option<Cell>]) -> &[usize] {
+        return self.indices.get_or_init(|| {
+            cells
+                .iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.is_some())
+                .map(|(index, _)| index)
+                .collect()
+        });
+    }
+
+    // forgets the indices, for when cells are masked out or added.
+    pub fn clear(&mut self) {
+        self.indices = OnceLock::new();
+    }
+}
+
+// the indices only follow from the cells, so two grids are no different for having gathered
+// them or not.
+impl PartialEq for LiveCells {
+    fn eq(&self, _: &Self) -> bool {
+        return true;
+    }
+}
+
+impl Eq for LiveCells {}
@@ -0,0 +1,180 @@
+use std::collections::{HashSet, VecDeque};
+
+use rand::seq::SliceRandom;
+
+use crate::prelude::*;
+
+// the four corners of a door or key as drawn, in pixels.
+type Corners = [(i32, i32); 4];
+
+// Lock : A locked door across the passage between two cells, and the cell holding the key
+// that opens it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Lock {
+    pub door: (Point, Point),
+    pub key: Point,
+}
+
+impl Lock {
+    pub fn new(door: (Point, Point), key: Point) -> Self {
+        Self { door, key }
+    }
+
+    pub fn locks(&self, a: Point, b: Point) -> bool {
+        return self.door == (a, b) || self.door == (b, a);
+    }
+}
+
+impl RectangularGrid {
+    // locks up to `count` doors spread along the solution, each across a passage the
+    // solution can't go around, in place of any locked before. Each door's key is left in a
+    // dead end reached only through the door before it, so the doors are opened in order.
+    // Returns how many doors were locked.
+    pub fn place_locks(&mut self, count: usize) -> usize {
+        self.locks.clear();
+
+        let (start, goal) = match self.ends() {
+            Some(ends) => ends,
+            None => return 0,
+        };
+        let path = self.clone().solve().clone();
+
+        // the passages of the solution that every way from the start to the goal takes.
+        let needed = path
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .filter(|&door| !self.reachable(start, &[door]).contains(&goal))
+            .collect::<Vec<(Point, Point)>>();
+
+        let mut doors = (1..=count)
+            .filter_map(|i| needed.get(i * needed.len() / (count + 1)).copied())
+            .collect::<Vec<(Point, Point)>>();
+        doors.dedup();
+
+        let mut random = random::rng();
+        let mut opened = HashSet::new();
+
+        for (i, &door) in doors.iter().enumerate() {
+            // the cells that can be reached once the doors before this one are open.
+            let reached = self.reachable(start, &doors[i..]);
+            let mut section = reached
+                .iter()
+                .copied()
+                .filter(|point| !opened.contains(point))
+                .collect::<Vec<Point>>();
+            // sorted, so the same seed always leaves the keys in the same places.
+            section.sort();
+
+            let dead_ends = section
+                .iter()
+                .copied()
+                .filter(|&point| self.links(point).len() == 1 && !path.contains(&point))
+                .collect::<Vec<Point>>();
+            let off_path = section
+                .iter()
+                .copied()
+                .filter(|point| !path.contains(point))
+                .collect::<Vec<Point>>();
+
+            let choices = [dead_ends, off_path, section]
+                .into_iter()
+                .find(|choices| !choices.is_empty())
+                .unwrap_or_default();
+
+            if let Some(&key) = choices.choose(&mut random) {
+                self.locks.push(Lock::new(door, key));
+            }
+
+            opened = reached;
+        }
+
+        return self.locks.len();
+    }
+
+    // the index of the lock whose door is across the passage between `a` and `b`.
+    pub(crate) fn lock_at(&self, a: Point, b: Point) -> Option<usize> {
+        return self.locks.iter().position(|lock| lock.locks(a, b));
+    }
+
+    // the index of the lock whose key is at `point`.
+    pub(crate) fn key_at(&self, point: Point) -> Option<usize> {
+        return self.locks.iter().position(|lock| lock.key == point);
+    }
+
+    // every cell that can be walked to from `from` without passing through `closed`.
+    pub(crate) fn reachable(&self, from: Point, closed: &[(Point, Point)]) -> HashSet<Point> {
+        let mut reached = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(point) = queue.pop_front() {
+            for link in self.links(point) {
+                let shut = closed
+                    .iter()
+                    .any(|&door| door == (point, link) || door == (link, point));
+
+                if !shut && reached.insert(link) {
+                    queue.push_back(link);
+                }
+            }
+        }
+
+        return reached;
+    }
+
+    // draws each door as a bar across its passage and each key as a diamond in its cell,
+    // with every door in the same color as its key.
+    pub(crate) fn draw_locks(&self, image: &mut RgbImage, style: &Style, left: i32, top: i32) {
+        for (shape, color) in self.lock_shapes(style) {
+            let shape = shape.map(|(x, y)| (x - left, y - top));
+            RectangularGrid::fill_polygon(image, &shape, color);
+        }
+    }
+
+    // the outline of every door and key, in pixels, along with the color it's filled in.
+    pub(crate) fn lock_shapes(&self, style: &Style) -> Vec<(Corners, Rgb<u8>)> {
+        let (cell_width, cell_height) = (style.cell_width as i32, style.cell_height as i32);
+        let inset = cell_width.min(cell_height) / 4;
+        let bar = (cell_width.min(cell_height) / 8).max(style.wall_thickness as i32);
+        let mut shapes = Vec::new();
+
+        for (i, lock) in self.locks.iter().enumerate() {
+            let color = lock_color(i);
+            let (a, b) = lock.door;
+            let (x, y) = (a.x.max(b.x), a.y.max(b.y));
+
+            // the bar lies along the side the two cells share.
+            let door = if a.y == b.y {
+                let x = x * cell_width;
+                let (y1, y2) = (y * cell_height + inset, (y + 1) * cell_height - inset);
+                [(x - bar, y1), (x + bar, y1), (x + bar, y2), (x - bar, y2)]
+            } else {
+                let y = y * cell_height;
+                let (x1, x2) = (x * cell_width + inset, (x + 1) * cell_width - inset);
+                [(x1, y - bar), (x2, y - bar), (x2, y + bar), (x1, y + bar)]
+            };
+
+            let (center_x, center_y) = (
+                lock.key.x * cell_width + cell_width / 2,
+                lock.key.y * cell_height + cell_height / 2,
+            );
+            let (reach_x, reach_y) = (cell_width / 2 - inset, cell_height / 2 - inset);
+            let key = [
+                (center_x, center_y - reach_y),
+                (center_x + reach_x, center_y),
+                (center_x, center_y + reach_y),
+                (center_x - reach_x, center_y),
+            ];
+
+            shapes.push((door, color));
+            shapes.push((key, color));
+        }
+
+        return shapes;
+    }
+}
+
+// the color each door and its key are drawn in, taken from the portal colors three along so
+// the first lock and the first portal differ.
+pub(crate) fn lock_color(index: usize) -> Rgb<u8> {
+    return portal_color(index + 3);
+}
@@ -0,0 +1,352 @@
+use std::io::{Read, Write};
+
+use crate::prelude::*;
+
+// The .maze format, a compact binary save of a rectangular maze. Every number is a
+// little-endian u32 unless said otherwise, and cells are numbered row by row.
+//
+//   "MAZE", the version as a byte and a byte of flags, bit 0 set when distances are saved
+//   the width and the height
+//   a bit for each cell, set when it isn't masked out, eight cells to a byte
+//   four bits for each cell, the sides it has passages out through, two cells to a byte
+//   how many cells weigh other than 1, then the cell and weight of each
+//   how many other passages, such as portals, then the cells each leads from and to
+//   how many entrances, then the x and y of each one's cell and outside, as i32s
+//   how many locks, then the two cells of each one's door and the cell of its key
+//   with distances, how many roots and each root, then each cell's distance, u32::MAX when
+//   it wasn't reached
+const MAGIC: &[u8; 4] = b"MAZE";
+const VERSION: u8 = 1;
+const HAS_DISTANCES: u8 = 1;
+const UNREACHED: u32 = u32::MAX;
+
+fn invalid(message: impl Into<String>) -> MazeError {
+    return MazeError::InvalidSave(message.into());
+}
+
+// Bytes : The bytes of a .maze file not yet read.
+struct Bytes<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Bytes<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], MazeError> {
+        if count > self.bytes.len() {
+            return Err(invalid("the file ends early"));
+        }
+
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+
+        return Ok(taken);
+    }
+
+    fn u8(&mut self) -> Result<u8, MazeError> {
+        return Ok(self.take(1)?[0]);
+    }
+
+    fn u32(&mut self) -> Result<u32, MazeError> {
+        return Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()));
+    }
+
+    fn i32(&mut self) -> Result<i32, MazeError> {
+        return Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()));
+    }
+
+    fn usize(&mut self) -> Result<usize, MazeError> {
+        return Ok(self.u32()? as usize);
+    }
+}
+
+impl RectangularGrid {
+    // writes the maze to `writer` in the .maze format, with its distances when `distances`
+    // is set. Like `to_json`, it keeps the cells, their weights and passages, the entrances
+    // and the locked doors, but far more compactly.
+    pub fn save(&self, mut writer: impl Write, distances: bool) -> Result<(), MazeError> {
+        let count = self.cells.len();
+        let mut bytes = Vec::with_capacity(16 + count.div_ceil(8) + count.div_ceil(2));
+        let u32 = |bytes: &mut Vec<u8>, value: usize| {
+            bytes.extend_from_slice(&(value as u32).to_le_bytes());
+        };
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(if distances { HAS_DISTANCES } else { 0 });
+        u32(&mut bytes, self.width);
+        u32(&mut bytes, self.height);
+
+        let mut present = vec![0u8; count.div_ceil(8)];
+        let mut sides = vec![0u8; count.div_ceil(2)];
+
+        for (index, cell) in self.cells.iter().enumerate() {
+            let point = Point::new((index % self.width) as i32, (index / self.width) as i32);
+
+            if cell.is_some() {
+                present[index / 8] |= 1 << (index % 8);
+            }
+
+            sides[index / 2] |= self.sides.bits(point) << (4 * (index % 2));
+        }
+
+        bytes.extend_from_slice(&present);
+        bytes.extend_from_slice(&sides);
+
+        let index = |point: Point| self.point_to_index(point).unwrap();
+        let weighted = self
+            .cells
+            .iter()
+            .flatten()
+            .filter(|cell| cell.weight != 1)
+            .collect::<Vec<&Cell>>();
+
+        u32(&mut bytes, weighted.len());
+
+        for cell in weighted {
+            u32(&mut bytes, index(cell.point));
+            u32(&mut bytes, cell.weight);
+        }
+
+        let others = self
+            .cells
+            .iter()
+            .flatten()
+            .flat_map(|cell| {
+                let links = self.links.links(cell.point);
+                links.into_iter().map(move |link| (cell.point, link))
+            })
+            .collect::<Vec<(Point, Point)>>();
+
+        u32(&mut bytes, others.len());
+
+        for (from, to) in others {
+            u32(&mut bytes, index(from));
+            u32(&mut bytes, index(to));
+        }
+
+        u32(&mut bytes, self.entrances.len());
+
+        for entrance in &self.entrances {
+            for point in [entrance.cell, entrance.outside] {
+                bytes.extend_from_slice(&point.x.to_le_bytes());
+                bytes.extend_from_slice(&point.y.to_le_bytes());
+            }
+        }
+
+        u32(&mut bytes, self.locks.len());
+
+        for lock in &self.locks {
+            for point in [lock.door.0, lock.door.1, lock.key] {
+                u32(&mut bytes, index(point));
+            }
+        }
+
+        if distances {
+            u32(&mut bytes, self.distances.roots.len());
+
+            for &root in &self.distances.roots {
+                u32(&mut bytes, index(root));
+            }
+
+            for index in 0..count {
+                let point = Point::new((index % self.width) as i32, (index / self.width) as i32);
+                let distance = self.distances.distance(point);
+                bytes.extend_from_slice(&distance.map_or(UNREACHED, |d| d as u32).to_le_bytes());
+            }
+        }
+
+        writer.write_all(&bytes)?;
+
+        return Ok(());
+    }
+
+    // rebuilds a maze written by `save`, along with its distances when they were saved.
+    pub fn load(mut reader: impl Read) -> Result<Self, MazeError> {
+        let mut file = Vec::new();
+        reader.read_to_end(&mut file)?;
+
+        let mut bytes = Bytes { bytes: &file };
+
+        if bytes.take(4).ok() != Some(MAGIC.as_slice()) {
+            return Err(invalid("not a .maze file"));
+        }
+
+        let version = bytes.u8()?;
+
+        if version != VERSION {
+            return Err(invalid(format!(
+                "version {} of the .maze format isn't supported",
+                version
+            )));
+        }
+
+        let flags = bytes.u8()?;
+        let (width, height) = (bytes.usize()?, bytes.usize()?);
+        let count = width
+            .checked_mul(height)
+            .ok_or_else(|| invalid("the maze is too big"))?;
+
+        // read before the grid is made, so a broken size fails without building it.
+        let present = bytes.take(count.div_ceil(8))?;
+        let sides = bytes.take(count.div_ceil(2))?;
+
+        let mut grid = RectangularGrid::new(width, height);
+        let cell = |index: usize| {
+            (index < count)
+                .then(|| Point::new((index % width) as i32, (index / width) as i32))
+                .ok_or_else(|| invalid(format!("cell {} is outside the maze", index)))
+        };
+
+        for index in 0..count {
+            if present[index / 8] & (1 << (index % 8)) == 0 {
+                grid.cells[index] = None;
+            }
+
+            let point = cell(index)?;
+            let bits = sides[index / 2] >> (4 * (index % 2));
+
+            for (neighbor, bit) in [
+                (point.north(), OPEN_NORTH),
+                (point.east(), OPEN_EAST),
+                (point.south(), OPEN_SOUTH),
+                (point.west(), OPEN_WEST),
+            ] {
+                if bits & bit != 0 {
+                    grid.sides.link(point, neighbor, false);
+                }
+            }
+        }
+
+        for _ in 0..bytes.usize()? {
+            let index = bytes.usize()?;
+            let weight = bytes.usize()?;
+
+            cell(index)?;
+
+            if let Some(cell) = grid.cells[index].as_mut() {
+                cell.weight = weight;
+            }
+        }
+
+        for _ in 0..bytes.usize()? {
+            let (from, to) = (cell(bytes.usize()?)?, cell(bytes.usize()?)?);
+            grid.links.link(from, to, false);
+        }
+
+        for _ in 0..bytes.usize()? {
+            let mut point = || Ok::<Point, MazeError>(Point::new(bytes.i32()?, bytes.i32()?));
+            let (cell, outside) = (point()?, point()?);
+
+            grid.entrances.push(Entrance::new(cell, outside));
+        }
+
+        for _ in 0..bytes.usize()? {
+            let door = (cell(bytes.usize()?)?, cell(bytes.usize()?)?);
+            let key = cell(bytes.usize()?)?;
+
+            grid.locks.push(Lock::new(door, key));
+        }
+
+        if flags & HAS_DISTANCES != 0 {
+            let roots = (0..bytes.usize()?)
+                .map(|_| cell(bytes.usize()?))
+                .collect::<Result<Vec<Point>, MazeError>>()?;
+            let mut distances = Distances::new_multi(roots);
+
+            for index in 0..count {
+                let distance = bytes.u32()?;
+
+                if distance != UNREACHED {
+                    distances.set(cell(index)?, distance as usize);
+                }
+            }
+
+            grid.distances = distances;
+        } else {
+            // measured from the entrance when there is one, as they were when it was carved.
+            let start = grid.entrances.first().map(|entrance| entrance.cell);
+
+            if let Some(start) = start.or_else(|| grid.first_cell()) {
+                grid.distances = Distances::new(start);
+            }
+        }
+
+        return Ok(grid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 5 by 3 maze, an odd number of cells so the last byte of sides is half used, with a
+    // corner masked out, weighted cells, a portal, entrances and a locked door.
+    fn maze() -> RectangularGrid {
+        let mut mask = Mask::new(5, 3);
+        mask.set(Point::new(0, 0), false);
+
+        return MazeBuilder::new()
+            .mask(mask)
+            .seed(11)
+            .lava(2, 7)
+            .portal(Point::new(1, 0), Point::new(4, 2))
+            .entrances(EntranceType::Opposite)
+            .locks(1)
+            .build();
+    }
+
+    fn saved(grid: &RectangularGrid, distances: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        grid.save(&mut bytes, distances).unwrap();
+
+        return bytes;
+    }
+
+    #[test]
+    fn round_trips_a_maze() {
+        let grid = maze();
+
+        assert_eq!(grid.cells.len() % 2, 1);
+        assert!(grid.cells.iter().flatten().any(|cell| cell.weight == 7));
+        assert!(!grid.entrances.is_empty());
+        assert!(!grid.locks.is_empty());
+        assert!(grid.linked(Point::new(1, 0), Point::new(4, 2)));
+
+        assert_eq!(
+            RectangularGrid::load(saved(&grid, false).as_slice()).unwrap(),
+            grid
+        );
+    }
+
+    #[test]
+    fn round_trips_distances() {
+        let mut grid = maze();
+        grid.distances = grid.distances.measure(&grid);
+
+        let loaded = RectangularGrid::load(saved(&grid, true).as_slice()).unwrap();
+
+        assert_eq!(loaded.distances, grid.distances);
+    }
+
+    #[test]
+    fn rejects_a_file_cut_short() {
+        let bytes = saved(&maze(), true);
+
+        for length in 0..bytes.len() {
+            assert!(matches!(
+                RectangularGrid::load(&bytes[..length]),
+                Err(MazeError::InvalidSave(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_another_version() {
+        let mut bytes = saved(&maze(), false);
+        bytes[4] = VERSION + 1;
+
+        assert!(matches!(
+            RectangularGrid::load(bytes.as_slice()),
+            Err(MazeError::InvalidSave(_))
+        ));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::nbt::Nbt;
+use crate::prelude::*;
+
+// the version of Minecraft the structure is saved for, 1.20.1.
+const DATA_VERSION: i32 = 3465;
+
+// StructureOptions : The blocks a maze is built from in Minecraft, and how high its walls are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructureOptions {
+    pub wall: String,
+    pub floor: Option<String>,
+    pub height: usize,
+}
+
+// block names without a namespace are taken to be Minecraft's own.
+fn block(name: &str) -> Nbt {
+    let name = if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{}", name)
+    };
+
+    return Nbt::Compound(vec![(String::from("Name"), Nbt::String(name))]);
+}
+
+impl RectangularGrid {
+    // the maze as a Minecraft structure file, to be placed with a structure block. Its blocks
+    // follow the maze's lattice, one block for each post, wall and cell, with air left in the
+    // passages so they're carved out of whatever the maze is placed in.
+    pub fn to_structure(&self, options: &StructureOptions) -> Vec<u8> {
+        let lattice = self.lattice();
+        let bottom = options.floor.is_some() as usize;
+        let (air, wall, floor) = (0, 1, 2);
+
+        let mut palette = vec![block("air"), block(&options.wall)];
+        palette.extend(options.floor.as_deref().map(block));
+
+        let position = |x: usize, y: usize, z: usize| {
+            Nbt::List(vec![
+                Nbt::Int(x as i32),
+                Nbt::Int(y as i32),
+                Nbt::Int(z as i32),
+            ])
+        };
+        let mut blocks = Vec::new();
+
+        for z in 0..lattice.rows {
+            for x in 0..lattice.columns {
+                let index = z * lattice.columns + x;
+
+                // blocks outside of every cell are left as they are.
+                if !lattice.floor[index] {
+                    continue;
+                }
+
+                let mut states = vec![(0, floor); bottom];
+                let state = if lattice.walls[index] { wall } else { air };
+                states.extend((bottom..bottom + options.height).map(|y| (y, state)));
+
+                for (y, state) in states {
+                    blocks.push(Nbt::Compound(vec![
+                        (String::from("pos"), position(x, y, z)),
+                        (String::from("state"), Nbt::Int(state)),
+                    ]));
+                }
+            }
+        }
+
+        let structure = Nbt::Compound(vec![
+            (String::from("DataVersion"), Nbt::Int(DATA_VERSION)),
+            (
+                String::from("size"),
+                position(lattice.columns, bottom + options.height, lattice.rows),
+            ),
+            (String::from("palette"), Nbt::List(palette)),
+            (String::from("blocks"), Nbt::List(blocks)),
+            (String::from("entities"), Nbt::List(Vec::new())),
+        ]);
+
+        return structure.to_file();
+    }
+}
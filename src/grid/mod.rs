@@ -0,0 +1,1881 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    fmt::Display,
+    ops::{Index, IndexMut},
+    slice::ChunksExact,
+};
+
+use rand::seq::SliceRandom;
+use rayon::prelude::*;
+
+use crate::prelude::*;
+
+mod any;
+mod cylinder;
+mod dxf;
+mod graph;
+mod grid3d;
+mod hex;
+mod links;
+mod live;
+mod locks;
+mod maze_file;
+mod minecraft;
+mod model;
+mod nested;
+mod one_way;
+mod penrose;
+mod portals;
+mod print;
+mod raycast;
+mod rooms;
+mod saved;
+mod sides;
+mod sphere;
+mod stitch;
+mod stream;
+mod symmetry;
+mod tileset;
+mod tmx;
+mod triangle;
+mod upsilon;
+mod walker;
+mod weave;
+
+pub use any::*;
+pub use cylinder::*;
+pub use graph::*;
+pub use grid3d::*;
+pub use hex::*;
+pub use links::*;
+pub use live::*;
+pub use locks::*;
+pub use minecraft::*;
+pub use penrose::*;
+pub use portals::*;
+pub use raycast::*;
+pub use rooms::*;
+pub use sides::*;
+pub use sphere::*;
+pub use symmetry::*;
+pub use tileset::*;
+pub use triangle::*;
+pub use upsilon::*;
+pub use walker::*;
+pub use weave::*;
+
+impl Iterator for dyn Grid {
+    type Item = Cell;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return None;
+    }
+}
+
+impl Index<usize> for dyn Grid {
+    type Output = Option<Cell>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        return &self.cells()[index];
+    }
+}
+
+impl Index<Point> for dyn Grid {
+    type Output = Option<Cell>;
+
+    fn index(&self, index: Point) -> &Self::Output {
+        return &self.cells()[self.point_to_index(index).unwrap()];
+    }
+}
+
+impl IndexMut<usize> for dyn Grid {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        return &mut self.cells_mut()[index];
+    }
+}
+
+/// Grid : The cells of a maze and the passages carved between them. Each kind of grid decides
+/// which cells neighbor each other, and algorithms carve any grid through this trait alone.
+/// Grids can be shared between threads, so they can be searched and drawn in parallel.
+pub trait Grid: Sync {
+    fn cells(&self) -> &Vec<Option<Cell>>;
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>>;
+
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn distances(&self) -> &Distances;
+
+    // the passages carved in the grid, kept here rather than on the cells they join so
+    // there's only the one place to look them up. Grids that keep some passages more
+    // compactly override the methods below that use this.
+    fn passages(&self) -> &Links;
+    fn passages_mut(&mut self) -> &mut Links;
+
+    // the indices of the cells the grid has, kept so a cell can be picked at random without
+    // walking them all. Grids forget them whenever their cells are handed out to be changed.
+    fn live_cells(&self) -> &LiveCells;
+
+    fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        if self.get(a).is_none() || self.get(b).is_none() {
+            return;
+        }
+
+        self.passages_mut().link(a, b, bidi);
+    }
+
+    fn unlink(&mut self, a: Point, b: Point, bidi: bool) {
+        self.passages_mut().unlink(a, b, bidi);
+    }
+
+    // links the cells that `transform` moves each passage of `source` onto, keeping the way
+    // one-way passages lead. Passages moved onto cells this grid doesn't have are left out.
+    fn copy_links(&mut self, source: &dyn Grid, transform: &dyn Fn(Point) -> Point) {
+        for cell in source.cells().iter().flatten() {
+            for link in source.links(cell.point) {
+                let (a, b) = (transform(cell.point), transform(link));
+
+                if self.get(a).is_some() && self.get(b).is_some() {
+                    self.link(a, b, false);
+                }
+            }
+        }
+    }
+
+    fn links(&self, point: Point) -> Vec<Point> {
+        return self.passages().links(point);
+    }
+
+    fn linked(&self, a: Point, b: Point) -> bool {
+        return self.passages().linked(a, b);
+    }
+
+    // the cells with a passage leading into `point`. These are the cells `point` leads to
+    // unless some of its passages only go one way.
+    fn links_into(&self, point: Point) -> Vec<Point> {
+        let mut into = self.links(point);
+        into.extend(self.neighbors(point));
+        into.sort();
+        into.dedup();
+        into.retain(|&other| self.linked(other, point));
+
+        return into;
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        let mut neighbors = Vec::new();
+
+        if let Some(north) = self.get(point.north()) {
+            neighbors.push(north.point);
+        }
+
+        if let Some(south) = self.get(point.south()) {
+            neighbors.push(south.point);
+        }
+
+        if let Some(east) = self.get(point.east()) {
+            neighbors.push(east.point);
+        }
+
+        if let Some(west) = self.get(point.west()) {
+            neighbors.push(west.point);
+        }
+
+        return neighbors;
+    }
+
+    fn get(&self, point: Point) -> Option<&Cell> {
+        let index = self.point_to_index(point)?;
+
+        return self.cells()[index].as_ref();
+    }
+
+    // the cells of the solution from start to finish, which only rectangular grids are
+    // solved for.
+    fn path(&self) -> &[Point] {
+        return &[];
+    }
+
+    // the openings in the boundary, which only rectangular grids have.
+    fn entrances(&self) -> &[Entrance] {
+        return &[];
+    }
+
+    // the first cell the grid has in the order it keeps them, or None when every cell is
+    // masked out. A new grid measures its distances from here until it's given a start.
+    fn first_cell(&self) -> Option<Point> {
+        return self.cells().iter().flatten().next().map(|cell| cell.point);
+    }
+
+    // the cells that can be reached from `start` by stepping between neighbors, whether or
+    // not there's a passage between them.
+    fn reachable_from(&self, start: Point) -> HashSet<Point> {
+        let mut reachable = HashSet::from([start]);
+        let mut frontier = vec![start];
+
+        while let Some(point) = frontier.pop() {
+            for neighbor in self.neighbors(point) {
+                if reachable.insert(neighbor) {
+                    frontier.push(neighbor);
+                }
+            }
+        }
+
+        return reachable;
+    }
+
+    // how many pieces the grid's cells fall into, with no neighbors between one piece and
+    // another. A grid is in one piece unless its mask cuts some of it off.
+    fn regions(&self) -> usize {
+        let mut reached = HashSet::new();
+        let mut regions = 0;
+
+        for cell in self.cells().iter().flatten() {
+            if !reached.contains(&cell.point) {
+                reached.extend(self.reachable_from(cell.point));
+                regions += 1;
+            }
+        }
+
+        return regions;
+    }
+
+    // a cell picked uniformly from those the grid has, with a single draw however sparse its
+    // mask is, or None when every cell is masked out.
+    fn random_cell(&self) -> Option<&Cell> {
+        let live = self.live_cells().indices(self.cells());
+
+        if live.is_empty() {
+            return None;
+        }
+
+        let index = live[random::rng().gen_range(0..live.len())];
+
+        return self.cells()[index].as_ref();
+    }
+
+    // the cells with only a single passage leading out of them.
+    fn dead_ends(&self) -> Vec<Point> {
+        return self
+            .cells()
+            .iter()
+            .flatten()
+            .filter(|cell| self.links(cell.point).len() == 1)
+            .map(|cell| cell.point)
+            .collect();
+    }
+
+    // gives `count` distinct cells picked at random a cost of `weight` to step into.
+    fn weigh_random_cells(&mut self, count: usize, weight: usize) {
+        let mut cells = self
+            .cells_mut()
+            .iter_mut()
+            .flatten()
+            .collect::<Vec<&mut Cell>>();
+        cells.shuffle(&mut random::rng());
+
+        for cell in cells.into_iter().take(count) {
+            cell.weight = weight;
+        }
+    }
+
+    // removes dead ends, each with probability `p`, by opening a passage to a neighbor. A
+    // neighbor that is a dead end too is preferred, clearing both with a single passage.
+    fn braid(&mut self, p: f64) {
+        let mut random = random::rng();
+        let mut dead_ends = self.dead_ends();
+        dead_ends.shuffle(&mut random);
+
+        for point in dead_ends {
+            if self.links(point).len() != 1 || !random.gen_bool(p.clamp(0.0, 1.0)) {
+                continue;
+            }
+
+            let closed = self
+                .neighbors(point)
+                .into_iter()
+                .filter(|&neighbor| !self.linked(point, neighbor))
+                .collect::<Vec<Point>>();
+            let best = closed
+                .iter()
+                .copied()
+                .filter(|&neighbor| self.links(neighbor).len() == 1)
+                .collect::<Vec<Point>>();
+
+            let choices = if best.is_empty() { closed } else { best };
+
+            if let Some(&neighbor) = choices.choose(&mut random) {
+                self.link(point, neighbor, true);
+            }
+        }
+    }
+
+    // opens `count` passages through walls between neighboring cells, each adding a loop.
+    // Each wall is picked as far through the maze as it can be from those opened before it,
+    // so the loops are spread out. Returns how many were opened, which is fewer when the
+    // maze runs out of walls.
+    fn add_loops(&mut self, count: usize) -> usize {
+        let mut walls = self
+            .cells()
+            .iter()
+            .flatten()
+            .flat_map(|cell| {
+                self.neighbors(cell.point)
+                    .into_iter()
+                    .map(move |neighbor| (cell.point, neighbor))
+            })
+            .filter(|&(a, b)| a < b && !self.linked(a, b) && !self.linked(b, a))
+            .collect::<Vec<(Point, Point)>>();
+        walls.shuffle(&mut random::rng());
+
+        let mut opened = Vec::new();
+
+        for _ in 0..count {
+            // how many steps each cell is from the nearest passage opened so far.
+            let mut spacing = HashMap::new();
+            let mut queue = VecDeque::new();
+
+            for &point in opened.iter() {
+                spacing.insert(point, 0);
+                queue.push_back(point);
+            }
+
+            while let Some(point) = queue.pop_front() {
+                let steps = spacing[&point] + 1;
+
+                for link in self.links(point) {
+                    if let Entry::Vacant(entry) = spacing.entry(link) {
+                        entry.insert(steps);
+                        queue.push_back(link);
+                    }
+                }
+            }
+
+            let apart = |point: &Point| spacing.get(point).copied().unwrap_or(usize::MAX);
+            let best = walls
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, (a, b))| apart(a).min(apart(b)))
+                .map(|(i, _)| i);
+
+            let Some(best) = best else {
+                break;
+            };
+
+            let (a, b) = walls.swap_remove(best);
+            self.link(a, b, true);
+            opened.extend([a, b]);
+        }
+
+        return opened.len() / 2;
+    }
+
+    fn iter_rows(&self) -> ChunksExact<'_, Option<Cell>> {
+        self.cells().chunks_exact(self.width())
+    }
+
+    // the cells inside `rect` as a grid of their own, with the corner of `rect` moved to the
+    // origin. Passages leaving the region are cut, as are any a rectangular grid can't hold
+    // such as the diagonals of a hex grid. Distances measured inside the region are kept, as
+    // are the entrances and the cells of the solution inside it.
+    fn crop(&self, rect: Rect) -> RectangularGrid {
+        let mut grid = RectangularGrid::new(rect.width, rect.height);
+        let origin = rect.origin();
+        let distances = self.distances();
+
+        for y in 0..rect.height as i32 {
+            for x in 0..rect.width as i32 {
+                if self.get(origin + Point::new(x, y)).is_none() {
+                    let index = grid.point_to_index(Point::new(x, y)).unwrap();
+                    grid.cells[index] = None;
+                }
+            }
+        }
+
+        for y in 0..rect.height as i32 {
+            for x in 0..rect.width as i32 {
+                let point = Point::new(x, y);
+
+                if grid.get(point).is_none() {
+                    continue;
+                }
+
+                for link in self.links(origin + point) {
+                    let link = link - origin;
+                    let adjacent = point.direction_to(link).is_some();
+
+                    if adjacent && grid.get(link).is_some() {
+                        grid.link(point, link, false);
+                    }
+                }
+
+                if let Some(distance) = distances.distance(origin + point) {
+                    grid.distances.set(point, distance);
+                }
+            }
+        }
+
+        let roots = distances
+            .roots
+            .iter()
+            .filter(|&&root| rect.contains(root))
+            .map(|&root| root - origin)
+            .collect::<Vec<Point>>();
+
+        if !roots.is_empty() {
+            grid.distances.roots = roots;
+        } else if let Some(start) = grid.first_cell() {
+            grid.distances.roots = vec![start];
+        }
+
+        grid.entrances = self
+            .entrances()
+            .iter()
+            .filter(|entrance| rect.contains(entrance.cell))
+            .map(|entrance| Entrance::new(entrance.cell - origin, entrance.outside - origin))
+            .collect();
+        grid.path = self
+            .path()
+            .iter()
+            .filter(|&&point| rect.contains(point))
+            .map(|&point| point - origin)
+            .collect();
+
+        return grid;
+    }
+
+    fn point_to_index(&self, point: Point) -> Option<usize> {
+        if point.x < 0 || point.y < 0 || point.z != 0 || point.x >= self.width() as i32 {
+            return None;
+        }
+
+        let index = (point.y * self.width() as i32 + point.x) as usize;
+
+        if index >= self.cells().len() {
+            return None;
+        }
+
+        return Some(index);
+    }
+}
+
+// Entrance : An opening in the boundary of a maze, leading from a cell on the edge of the
+// maze to the point just outside of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Entrance {
+    pub cell: Point,
+    pub outside: Point,
+}
+
+impl Entrance {
+    pub fn new(cell: Point, outside: Point) -> Self {
+        Self { cell, outside }
+    }
+
+    pub fn joins(&self, a: Point, b: Point) -> bool {
+        return (self.cell == a && self.outside == b) || (self.cell == b && self.outside == a);
+    }
+}
+
+// EntranceType : Where the two entrances of a rectangular maze are opened.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntranceType {
+    // at the two ends of the longest path between cells on the boundary.
+    Longest,
+    // at two places on the boundary picked at random.
+    Random,
+    // on the west side and across from it on the east side.
+    Opposite,
+}
+
+/// RectangularGrid : A maze of square cells in rows and columns, and the only grid that can
+/// have entrances, be solved and be exported to every format.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RectangularGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub distances: Distances,
+    pub entrances: Vec<Entrance>,
+    // the cells of the solution from start to finish, once the maze has been solved.
+    pub path: Vec<Point>,
+    // the locked doors, in the order they're met on the way through the maze.
+    pub locks: Vec<Lock>,
+    // the passages between cells side by side, and every other passage such as a portal.
+    pub sides: Sides,
+    pub links: Links,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl RectangularGrid {
+    fn new(width: usize, height: usize) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(Some(Cell::new(Point::new(x as i32, y as i32))));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            distances: Distances::new(Point::new(0, 0)),
+            entrances: Vec::new(),
+            path: Vec::new(),
+            locks: Vec::new(),
+            sides: Sides::new(width, height),
+            links: Links::new(),
+            live: LiveCells::new(),
+        }
+    }
+
+    // finds the shortest path from the entrance to the exit, or between the first and last
+    // cells when the maze has no entrances.
+    pub fn solve(&mut self) -> &Vec<Point> {
+        if let Some((start, goal)) = self.ends() {
+            let mut distances = Distances::new(start);
+            distances.compute(self);
+
+            if distances.distance(goal).is_some() {
+                self.path = distances.path_to(self, goal);
+            }
+        }
+
+        return &self.path;
+    }
+
+    // where a solution starts and finishes, the cells of the entrance and the exit or the
+    // first and last cells when the maze has no entrances.
+    pub fn ends(&self) -> Option<(Point, Point)> {
+        match self.entrances.as_slice() {
+            [start, exit, ..] => Some((start.cell, exit.cell)),
+            _ => Some((
+                self.first_cell()?,
+                self.cells.iter().flatten().last()?.point,
+            )),
+        }
+    }
+
+    // the maze drawn with +, - and | characters. Given a style, each cell is colored by its
+    // distance as a heat map instead of having the distance written in it.
+    pub fn to_ascii(&self, heat: Option<&Style>) -> String {
+        let mut output = String::from("+");
+
+        for x in 0..self.width as i32 {
+            let point = Point::new(x, 0);
+
+            if self.open(point, point.north()) {
+                output.push_str("   +");
+            } else {
+                output.push_str("---+");
+            }
+        }
+
+        output.push('\n');
+
+        let heat = heat.map(|style| (style, self.distances.max(self).0));
+        let path = self.path.iter().copied().collect::<HashSet<Point>>();
+
+        for (y, row) in self.iter_rows().enumerate() {
+            let west = Point::new(0, y as i32);
+            let mut top = if self.open(west, west.west()) {
+                String::from(" ")
+            } else {
+                String::from("|")
+            };
+            let mut bottom = String::from("+");
+
+            for (x, cell) in row.iter().enumerate() {
+                let point = Point::new(x as i32, y as i32);
+                let body = self.body_of(cell.as_ref(), heat, &path);
+
+                // one-way passages are marked with the way they can be walked, and locked
+                // doors with a #.
+                let east_boundary = match (
+                    self.linked(point, point.east()),
+                    self.linked(point.east(), point),
+                ) {
+                    _ if self.lock_at(point, point.east()).is_some() => "#",
+                    (true, false) => ">",
+                    (false, true) => "<",
+                    _ if self.open(point, point.east()) => " ",
+                    _ => "|",
+                };
+                top.push_str(body.as_str());
+                top.push_str(east_boundary);
+
+                let south_boundary = match (
+                    self.linked(point, point.south()),
+                    self.linked(point.south(), point),
+                ) {
+                    _ if self.lock_at(point, point.south()).is_some() => " # ",
+                    (true, false) => " v ",
+                    (false, true) => " ^ ",
+                    _ if self.open(point, point.south()) => "   ",
+                    _ => "---",
+                };
+
+                bottom.push_str(south_boundary);
+                bottom.push('+');
+            }
+
+            output.push_str(&top);
+            output.push('\n');
+            output.push_str(&bottom);
+            output.push('\n');
+        }
+
+        return output;
+    }
+
+    // the maze drawn with box drawing characters, each corner joining up the walls that
+    // meet at it. Unlike the ASCII form, masked out cells are left blank.
+    pub fn to_unicode(&self, heat: Option<&Style>) -> String {
+        let heat = heat.map(|style| (style, self.distances.max(self).0));
+        let path = self.path.iter().copied().collect::<HashSet<Point>>();
+        let wall = |a: Point, b: Point| {
+            return (self.get(a).is_some() || self.get(b).is_some()) && !self.open(a, b);
+        };
+        let mut output = String::new();
+
+        for y in 0..=self.height as i32 {
+            // the corners along the top of row y and the walls running between them.
+            for x in 0..=self.width as i32 {
+                let (north_west, south_east) = (Point::new(x - 1, y - 1), Point::new(x, y));
+                let (north_east, south_west) = (Point::new(x, y - 1), Point::new(x - 1, y));
+
+                let up = wall(north_west, north_east);
+                let down = wall(south_west, south_east);
+                let left = wall(north_west, south_west);
+                let right = wall(north_east, south_east);
+
+                output.push(match (up, down, left, right) {
+                    (false, false, false, false) => ' ',
+                    (true, false, false, false) => '╵',
+                    (false, true, false, false) => '╷',
+                    (false, false, true, false) => '╴',
+                    (false, false, false, true) => '╶',
+                    (true, true, false, false) => '│',
+                    (false, false, true, true) => '─',
+                    (false, true, false, true) => '┌',
+                    (false, true, true, false) => '┐',
+                    (true, false, false, true) => '└',
+                    (true, false, true, false) => '┘',
+                    (true, true, false, true) => '├',
+                    (true, true, true, false) => '┤',
+                    (false, true, true, true) => '┬',
+                    (true, false, true, true) => '┴',
+                    (true, true, true, true) => '┼',
+                });
+
+                if x < self.width as i32 {
+                    output.push_str(if right { "───" } else { "   " });
+                }
+            }
+
+            output.push('\n');
+
+            if y == self.height as i32 {
+                break;
+            }
+
+            for x in 0..=self.width as i32 {
+                let point = Point::new(x, y);
+
+                output.push(if wall(point.west(), point) {
+                    '│'
+                } else {
+                    ' '
+                });
+
+                if x < self.width as i32 {
+                    output.push_str(&self.body_of(self.get(point), heat, &path));
+                }
+            }
+
+            output.push('\n');
+        }
+
+        return output;
+    }
+
+    // every wall as the two corners it runs between, measured in cells. Walls shared by
+    // two cells are only given once, by the cell to the south or east of them.
+    pub fn walls(&self) -> Vec<(Point, Point)> {
+        let mut walls = Vec::new();
+
+        for cell in self.cells.iter().flatten() {
+            let point = cell.point;
+            let (x, y) = (point.x, point.y);
+
+            if !self.open(point, point.north()) {
+                walls.push((Point::new(x, y), Point::new(x + 1, y)));
+            }
+
+            if !self.open(point, point.west()) {
+                walls.push((Point::new(x, y), Point::new(x, y + 1)));
+            }
+
+            if self.get(point.east()).is_none() && !self.open(point, point.east()) {
+                walls.push((Point::new(x + 1, y), Point::new(x + 1, y + 1)));
+            }
+
+            if self.get(point.south()).is_none() && !self.open(point, point.south()) {
+                walls.push((Point::new(x, y + 1), Point::new(x + 1, y + 1)));
+            }
+        }
+
+        return walls;
+    }
+
+    // the points, in pixels, that the drawn solution passes through. It runs through the
+    // middle of each cell, and out through the entrances at either end of it.
+    fn solution_line(&self, cell_width: usize, cell_height: usize) -> Vec<(i32, i32)> {
+        let center = |point: Point| {
+            (
+                point.x * cell_width as i32 + cell_width as i32 / 2,
+                point.y * cell_height as i32 + cell_height as i32 / 2,
+            )
+        };
+        let edge = |entrance: &Entrance| {
+            let (inside, outside) = (center(entrance.cell), center(entrance.outside));
+            ((inside.0 + outside.0) / 2, (inside.1 + outside.1) / 2)
+        };
+
+        let mut line = self.path.iter().map(|&p| center(p)).collect::<Vec<_>>();
+
+        if let (Some(&first), Some(&last)) = (self.path.first(), self.path.last()) {
+            if let Some(entrance) = self.entrances.iter().find(|e| e.cell == first) {
+                line.insert(0, edge(entrance));
+            }
+
+            if let Some(exit) = self.entrances.iter().rev().find(|e| e.cell == last) {
+                line.push(edge(exit));
+            }
+        }
+
+        return line;
+    }
+
+    // the solved path as JSON, its length counts the steps taken from start to goal.
+    pub fn solution_json(&self, seed: u64) -> String {
+        let points = self
+            .path
+            .iter()
+            .map(|point| format!("{{\"x\": {}, \"y\": {}}}", point.x, point.y))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        return format!(
+            "{{\"seed\": {}, \"length\": {}, \"path\": [{}]}}",
+            seed,
+            self.path.len().saturating_sub(1),
+            points
+        );
+    }
+
+    // opens the boundary at the two ends of the longest path that starts and ends next to
+    // the boundary, returning the two entrances.
+    pub fn open_longest_entrances(&mut self) -> (Entrance, Entrance) {
+        let exterior = self.exterior();
+        let outside = |point: Point| {
+            [point.north(), point.west(), point.south(), point.east()]
+                .into_iter()
+                .filter(|side| exterior.contains(side))
+                .collect::<Vec<Point>>()
+        };
+
+        let path = Distances::longest_path_between(self, |point| !outside(point).is_empty());
+        let (_, goal) = path.max(self);
+
+        // a maze with no cells at all has nowhere to open, so fall back to the corner.
+        let start = match outside(path.root()).first() {
+            Some(&side) => Entrance::new(path.root(), side),
+            None => Entrance::new(path.root(), path.root().north()),
+        };
+        let exit = match outside(goal)
+            .into_iter()
+            .find(|&side| side != start.outside)
+        {
+            Some(side) => Entrance::new(goal, side),
+            None => start,
+        };
+
+        self.entrances = vec![start, exit];
+
+        return (start, exit);
+    }
+
+    // opens the boundary at two different places picked at random, returning the entrances.
+    pub fn open_random_entrances(&mut self) -> (Entrance, Entrance) {
+        let mut openings = self.boundary_openings();
+        openings.shuffle(&mut random::rng());
+
+        return self.open_entrances(openings.first().copied(), openings.get(1).copied());
+    }
+
+    // opens the boundary on the west side of the maze and across from it on the east side,
+    // each at a random spot along the side, returning the two entrances.
+    pub fn open_opposite_entrances(&mut self) -> (Entrance, Entrance) {
+        let openings = self.boundary_openings();
+        // the openings facing one way that sit furthest out that way.
+        let side = |direction: Point| {
+            let facing = openings
+                .iter()
+                .filter(|e| e.outside == e.cell + direction)
+                .copied()
+                .collect::<Vec<Entrance>>();
+            let furthest = facing.iter().map(|e| e.cell.x * direction.x).max();
+
+            return facing
+                .into_iter()
+                .filter(|e| Some(e.cell.x * direction.x) == furthest)
+                .collect::<Vec<Entrance>>()
+                .choose(&mut random::rng())
+                .copied();
+        };
+
+        return self.open_entrances(side(Point::new(-1, 0)), side(Point::new(1, 0)));
+    }
+
+    fn open_entrances(
+        &mut self,
+        start: Option<Entrance>,
+        exit: Option<Entrance>,
+    ) -> (Entrance, Entrance) {
+        // a maze with no cells at all has nowhere to open, so fall back to the corner.
+        let corner = Point::new(0, 0);
+        let start = start.unwrap_or(Entrance::new(corner, corner.north()));
+        let exit = exit.unwrap_or(start);
+
+        self.entrances = vec![start, exit];
+
+        return (start, exit);
+    }
+
+    // every place the boundary could be opened, each side of a cell that faces the outside.
+    fn boundary_openings(&self) -> Vec<Entrance> {
+        let exterior = self.exterior();
+
+        return self
+            .cells
+            .iter()
+            .flatten()
+            .flat_map(|cell| {
+                let point = cell.point;
+                [point.north(), point.west(), point.south(), point.east()]
+                    .into_iter()
+                    .filter(|side| exterior.contains(side))
+                    .map(move |side| Entrance::new(point, side))
+            })
+            .collect();
+    }
+
+    // the cells with a side open to the outside of the maze.
+    pub fn boundary(&self) -> Vec<Point> {
+        let exterior = self.exterior();
+
+        return self
+            .cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.point)
+            .filter(|point| {
+                [point.north(), point.west(), point.south(), point.east()]
+                    .iter()
+                    .any(|side| exterior.contains(side))
+            })
+            .collect();
+    }
+
+    // the points around and between the cells that can be reached from beyond the edge of
+    // the grid, which leaves out holes a mask cuts inside of the maze.
+    fn exterior(&self) -> HashSet<Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        let start = Point::new(-1, -1);
+        let mut exterior = HashSet::from([start]);
+        let mut frontier = vec![start];
+
+        while let Some(point) = frontier.pop() {
+            for side in [point.north(), point.west(), point.south(), point.east()] {
+                let inside = (-1..=width).contains(&side.x) && (-1..=height).contains(&side.y);
+
+                if inside && self.get(side).is_none() && exterior.insert(side) {
+                    frontier.push(side);
+                }
+            }
+        }
+
+        return exterior;
+    }
+
+    // whether nothing stands between two points, either a passage or an entrance.
+    fn open(&self, a: Point, b: Point) -> bool {
+        return self.linked(a, b)
+            || self.linked(b, a)
+            || self.entrances.iter().any(|e| e.joins(a, b));
+    }
+
+    fn format_radix(mut x: u128, radix: u32) -> String {
+        let mut result = vec![];
+
+        loop {
+            let m = x % radix as u128;
+            x /= radix as u128;
+
+            // will panic if you use a bad radix (< 2 or > 36).
+            result.push(std::char::from_digit(m as u32, radix).unwrap());
+            if x == 0 {
+                break;
+            }
+        }
+
+        return result.into_iter().rev().collect();
+    }
+
+    // the three characters inside a cell. A heat map colors the cell with the renderer's
+    // background for it, using the terminal's 256 color palette. `path` holds the cells of
+    // the solution, gathered once for the whole maze.
+    fn body_of(
+        &self,
+        cell: Option<&Cell>,
+        heat: Option<(&Style, usize)>,
+        path: &HashSet<Point>,
+    ) -> String {
+        if let (Some(cell), Some((style, max_distance))) = (cell, heat) {
+            let color = self.background_color_for(cell, &self.distances, max_distance, style);
+            let mark = if path.contains(&cell.point) { "*" } else { " " };
+
+            if color != style.background {
+                return format!("\x1b[48;5;{}m {} \x1b[0m", ansi_256(color), mark);
+            }
+
+            return format!(" {} ", mark);
+        }
+
+        return format!(" {} ", self.contents_of(cell, path));
+    }
+
+    fn contents_of(&self, cell: Option<&Cell>, path: &HashSet<Point>) -> String {
+        if let Some(cell) = cell {
+            if self.key_at(cell.point).is_some() {
+                return String::from("k");
+            }
+
+            // the ends of each portal share a capital letter.
+            if let Some(portal) = self.portal_at(cell.point) {
+                return char::from(b'A' + (portal % 26) as u8).to_string();
+            }
+
+            // a solved maze shows its path in place of the distances.
+            if !path.is_empty() {
+                if path.contains(&cell.point) {
+                    return String::from("*");
+                }
+            } else if let Some(distance) = self.distances.distance(cell.point) {
+                return RectangularGrid::format_radix(distance as u128, 36);
+            }
+
+            if cell.weight > 1 {
+                return String::from("~");
+            }
+        }
+
+        return String::from(" ");
+    }
+}
+
+impl Grid for RectangularGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn path(&self) -> &[Point] {
+        &self.path
+    }
+
+    fn entrances(&self) -> &[Entrance] {
+        &self.entrances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        if self.get(a).is_none() || self.get(b).is_none() {
+            return;
+        }
+
+        if !self.sides.link(a, b, bidi) {
+            self.links.link(a, b, bidi);
+        }
+    }
+
+    fn unlink(&mut self, a: Point, b: Point, bidi: bool) {
+        self.sides.unlink(a, b, bidi);
+        self.links.unlink(a, b, bidi);
+    }
+
+    fn links(&self, point: Point) -> Vec<Point> {
+        let mut links = self.sides.links(point);
+        links.extend(self.links.links(point));
+
+        return links;
+    }
+
+    fn linked(&self, a: Point, b: Point) -> bool {
+        return self.sides.linked(a, b) || self.links.linked(a, b);
+    }
+}
+
+// how many rows of cells each thread draws at a time.
+const BAND_ROWS: usize = 16;
+
+impl RectangularGrid {
+    // draws the `width` by `height` pixels of the maze's image starting at `left`, `top`,
+    // skipping the cells that fall outside of them. The cells are drawn in bands of rows
+    // side by side, and everything drawn over them once they're merged.
+    fn render(&self, style: &Style, left: i32, top: i32, width: u32, height: u32) -> RgbImage {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let band = (BAND_ROWS * cell_height) as u32;
+        // each band is drawn a cell and a wall taller on either side, so the walls along
+        // its edges come out as they would on the whole image.
+        let pad = (cell_width.max(cell_height) + style.wall_thickness) as u32;
+
+        let bands = (0..height)
+            .step_by(band as usize)
+            .collect::<Vec<u32>>()
+            .into_par_iter()
+            .map(|y| {
+                let (from, to) = (y.saturating_sub(pad), (y + band + pad).min(height));
+                let mut image = RgbImage::from_pixel(width, to - from, style.background);
+                self.draw_cells(&mut image, style, max_distance, left, top + from as i32);
+
+                let start = ((y - from) * width * 3) as usize;
+                let end = start + (band.min(height - y) * width * 3) as usize;
+
+                image.into_raw()[start..end].to_vec()
+            })
+            .collect::<Vec<Vec<u8>>>();
+
+        let mut imgbuf = RgbImage::from_raw(width, height, bands.concat()).unwrap();
+
+        self.draw_portals(&mut imgbuf, style, left, top);
+        self.draw_locks(&mut imgbuf, style, left, top);
+
+        for (from, to) in self.one_way_passages() {
+            if let Some(arrow) = self.arrow(from, to, style) {
+                let arrow = arrow.map(|(x, y)| (x - left, y - top));
+                RectangularGrid::fill_polygon(&mut imgbuf, &arrow, style.wall_color);
+            }
+        }
+
+        let line = self.solution_line(cell_width, cell_height);
+
+        for pair in line.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+
+            // the solution jumps through a portal rather than being drawn across the maze.
+            if (x2 - x1).abs() > cell_width as i32 || (y2 - y1).abs() > cell_height as i32 {
+                continue;
+            }
+
+            RectangularGrid::draw_line(
+                &mut imgbuf,
+                x1 - left,
+                y1 - top,
+                x2 - left,
+                y2 - top,
+                style.wall_thickness,
+                PATH,
+            );
+        }
+
+        return imgbuf;
+    }
+
+    // redraws the cells at `points` onto `image`, a whole image of the maze drawn in `style`
+    // before they changed, and leaves the rest of it as it was. The walls around the cells
+    // and anything drawn over them, such as the solution, are redrawn along with them, so
+    // the image comes out as `to_grid_image` would draw it after a carve, a wall edit or a
+    // step along the path.
+    pub fn redraw(&self, image: &mut RgbImage, style: &Style, points: &[Point]) {
+        let (cell_width, cell_height) = (style.cell_width as i64, style.cell_height as i64);
+        let margin = style.wall_thickness as i64;
+        let (width, height) = (image.width() as i64, image.height() as i64);
+
+        for point in points {
+            let (x, y) = (point.x as i64 * cell_width, point.y as i64 * cell_height);
+            let (left, top) = ((x - margin).max(0), (y - margin).max(0));
+            let right = (x + cell_width + margin + 1).min(width);
+            let bottom = (y + cell_height + margin + 1).min(height);
+
+            if left >= right || top >= bottom {
+                continue;
+            }
+
+            let patch = self.to_window_image(
+                style,
+                left as u32,
+                top as u32,
+                (right - left) as u32,
+                (bottom - top) as u32,
+            );
+
+            imageops::replace(image, &patch, left, top);
+        }
+    }
+
+    // draws the background and walls of each cell on `imgbuf`, which holds the pixels of the
+    // maze's image starting at `left`, `top`. Only the rows of cells it covers are looked at.
+    fn draw_cells(
+        &self,
+        imgbuf: &mut RgbImage,
+        style: &Style,
+        max_distance: usize,
+        left: i32,
+        top: i32,
+    ) {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (width, height) = imgbuf.dimensions();
+        let rows = (top.max(0) as usize / cell_height).saturating_sub(1)
+            ..((top + height as i32).max(0) as usize / cell_height + 1).min(self.height);
+
+        for mode in ["background", "walls"] {
+            for cell in self.cells[rows.start * self.width..rows.end * self.width]
+                .iter()
+                .flatten()
+            {
+                let (x1, x2, y1, y2) = (
+                    cell.point.x * cell_width as i32 - left,
+                    (cell.point.x + 1) * cell_width as i32 - left,
+                    cell.point.y * cell_height as i32 - top,
+                    (cell.point.y + 1) * cell_height as i32 - top,
+                );
+
+                if x2 < 0 || y2 < 0 || x1 > width as i32 || y1 > height as i32 {
+                    continue;
+                }
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    RectangularGrid::fill_polygon(
+                        imgbuf,
+                        &[(x1, y1), (x2, y1), (x2, y2), (x1, y2)],
+                        color,
+                    );
+                } else {
+                    if !self.open(cell.point, cell.point.north()) {
+                        RectangularGrid::draw_line(
+                            imgbuf,
+                            x1,
+                            y1,
+                            x2,
+                            y1,
+                            style.wall_thickness,
+                            style.wall_color,
+                        );
+                    }
+
+                    if !self.open(cell.point, cell.point.west()) {
+                        RectangularGrid::draw_line(
+                            imgbuf,
+                            x1,
+                            y1,
+                            x1,
+                            y2,
+                            style.wall_thickness,
+                            style.wall_color,
+                        );
+                    }
+
+                    if !self.open(cell.point, cell.point.east()) {
+                        RectangularGrid::draw_line(
+                            imgbuf,
+                            x2,
+                            y1,
+                            x2,
+                            y2,
+                            style.wall_thickness,
+                            style.wall_color,
+                        );
+                    }
+
+                    if !self.open(cell.point, cell.point.south()) {
+                        RectangularGrid::draw_line(
+                            imgbuf,
+                            x1,
+                            y2,
+                            x2,
+                            y2,
+                            style.wall_thickness,
+                            style.wall_color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drawable for RectangularGrid {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (width, height) = self.image_size(style);
+
+        return self.render(style, 0, 0, width, height);
+    }
+
+    fn image_size(&self, style: &Style) -> (u32, u32) {
+        return (
+            (self.width * style.cell_width + 1) as u32,
+            (self.height * style.cell_height + 1) as u32,
+        );
+    }
+
+    // only the cells near the window are drawn, on an image a little larger than it so the
+    // walls crossing its edges come out as they would in the whole image.
+    fn to_window_image(&self, style: &Style, x: u32, y: u32, width: u32, height: u32) -> RgbImage {
+        let (full_width, full_height) = self.image_size(style);
+        let pad = (style.cell_width.max(style.cell_height) + style.wall_thickness) as u32;
+        let (left, top) = (x.saturating_sub(pad), y.saturating_sub(pad));
+        let right = (x + width + pad).min(full_width);
+        let bottom = (y + height + pad).min(full_height);
+
+        let image = self.render(style, left as i32, top as i32, right - left, bottom - top);
+
+        return imageops::crop_imm(&image, x - left, y - top, width, height).to_image();
+    }
+
+    fn to_svg(&self, style: &Style) -> Option<String> {
+        let (cell_width, cell_height) = (style.cell_width as i32, style.cell_height as i32);
+        let (max_distance, _) = self.distances.max(self);
+        let mut body = String::new();
+
+        for cell in self.cells.iter().flatten() {
+            let (x1, y1) = (cell.point.x * cell_width, cell.point.y * cell_height);
+            let color = self.background_color_for(cell, &self.distances, max_distance, style);
+
+            if color != style.background {
+                body.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                    x1,
+                    y1,
+                    cell_width,
+                    cell_height,
+                    svg_color(color)
+                ));
+            }
+        }
+
+        let walls = self
+            .walls()
+            .into_iter()
+            .map(|(from, to)| {
+                format!(
+                    "M{} {}L{} {}",
+                    from.x * cell_width,
+                    from.y * cell_height,
+                    to.x * cell_width,
+                    to.y * cell_height
+                )
+            })
+            .collect::<String>();
+
+        body.push_str(&svg_path(&walls, style.wall_color, style.wall_thickness));
+
+        let inset = cell_width.min(cell_height) / 4;
+
+        for (i, (a, b)) in self.portals().into_iter().enumerate() {
+            for point in [a, b] {
+                body.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                    point.x * cell_width + inset,
+                    point.y * cell_height + inset,
+                    cell_width - 2 * inset,
+                    cell_height - 2 * inset,
+                    svg_color(portal_color(i))
+                ));
+            }
+        }
+
+        for (shape, color) in self.lock_shapes(style) {
+            let points = shape
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            body.push_str(&format!(
+                "<polygon points=\"{}\" fill=\"{}\"/>\n",
+                points,
+                svg_color(color)
+            ));
+        }
+
+        for (from, to) in self.one_way_passages() {
+            if let Some(arrow) = self.arrow(from, to, style) {
+                let points = arrow
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", x, y))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                body.push_str(&format!(
+                    "<polygon points=\"{}\" fill=\"{}\"/>\n",
+                    points,
+                    svg_color(style.wall_color)
+                ));
+            }
+        }
+
+        let line = self.solution_line(style.cell_width, style.cell_height);
+
+        if let Some(&(x, y)) = line.first() {
+            let mut path = format!("M{} {}", x, y);
+
+            for pair in line.windows(2) {
+                let ((x1, y1), (x, y)) = (pair[0], pair[1]);
+                let jump = (x - x1).abs() > cell_width || (y - y1).abs() > cell_height;
+                path.push_str(&format!("{}{} {}", if jump { "M" } else { "L" }, x, y));
+            }
+
+            body.push_str(&svg_path(&path, PATH, style.wall_thickness));
+        }
+
+        return Some(svg_document(
+            self.width * style.cell_width,
+            self.height * style.cell_height,
+            &body,
+            style.background,
+        ));
+    }
+}
+
+impl Display for RectangularGrid {
+    // the alternate form, `{:#}`, draws the maze with box drawing characters.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.to_unicode(None));
+        }
+
+        write!(f, "{}", self.to_ascii(None))
+    }
+}
+
+impl Maskable for RectangularGrid {
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = RectangularGrid::new(mask.width, mask.height);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        for (i, value) in mask.mask.iter().enumerate() {
+            if !value {
+                self.cells[i] = None;
+            }
+        }
+    }
+}
+
+/// PolarGrid : A circular maze of rings around a single center cell, each ring split into more
+/// cells than the one inside it as it grows.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PolarGrid {
+    pub width: usize,
+    pub height: usize,
+    pub rings: Vec<usize>,
+    offsets: Vec<usize>,
+    pub cells: Vec<Option<Cell>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl PolarGrid {
+    // the center is a single cell, surrounded by a first ring of `width` cells. Each ring
+    // after that is subdivided whenever its cells would grow wider than they are tall, so
+    // outer cells stay roughly square.
+    fn new(width: usize, height: usize) -> Self {
+        let mut rings = Vec::with_capacity(height);
+        let mut offsets = Vec::with_capacity(height);
+        let mut cells = Vec::new();
+
+        for y in 0..height {
+            let count = match rings.last() {
+                None => 1,
+                Some(_) if y == 1 => width.max(1),
+                Some(&previous) => {
+                    let circumference = 2.0 * std::f32::consts::PI * y as f32;
+                    let cell_width = circumference / previous as f32;
+                    let ratio = cell_width.round().max(1.0) as usize;
+
+                    previous * ratio
+                }
+            };
+
+            offsets.push(cells.len());
+
+            for x in 0..count {
+                cells.push(Some(Cell::new(Point::new(x as i32, y as i32))));
+            }
+
+            rings.push(count);
+        }
+
+        Self {
+            width,
+            height,
+            rings,
+            offsets,
+            cells,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    fn ring_size(&self, y: i32) -> i32 {
+        return self.rings[y as usize] as i32;
+    }
+
+    // rows are rings counted outwards from the center, and a cell's x is its position
+    // around the ring, which wraps so the last cell of a ring touches the first. The center
+    // cell is the only cell of ring 0, so every cell of ring 1 is outward of it.
+    pub fn clockwise(&self, point: Point) -> Point {
+        Point::new((point.x + 1).rem_euclid(self.ring_size(point.y)), point.y)
+    }
+
+    pub fn counter_clockwise(&self, point: Point) -> Point {
+        Point::new((point.x - 1).rem_euclid(self.ring_size(point.y)), point.y)
+    }
+
+    pub fn inward(&self, point: Point) -> Point {
+        if point.y == 0 {
+            return Point::new(point.x, -1);
+        }
+
+        let ratio = self.ring_size(point.y) / self.ring_size(point.y - 1);
+
+        return Point::new(point.x / ratio, point.y - 1);
+    }
+
+    // a subdivided ring puts several cells outward of each cell of the ring inside it.
+    pub fn outward(&self, point: Point) -> Vec<Point> {
+        if point.y + 1 >= self.height as i32 {
+            return Vec::new();
+        }
+
+        let ratio = self.ring_size(point.y + 1) / self.ring_size(point.y);
+
+        return (0..ratio)
+            .map(|i| Point::new(point.x * ratio + i, point.y + 1))
+            .collect();
+    }
+}
+
+impl Grid for PolarGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        if !self.neighbors(a).contains(&b) {
+            return;
+        }
+
+        self.links.link(a, b, bidi);
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        if self.get(point).is_none() {
+            return Vec::new();
+        }
+
+        let mut neighbors = vec![
+            self.inward(point),
+            self.clockwise(point),
+            self.counter_clockwise(point),
+        ];
+        neighbors.extend(self.outward(point));
+
+        let mut unique = Vec::new();
+
+        for neighbor in neighbors {
+            if neighbor != point && self.get(neighbor).is_some() && !unique.contains(&neighbor) {
+                unique.push(neighbor);
+            }
+        }
+
+        return unique;
+    }
+
+    fn point_to_index(&self, point: Point) -> Option<usize> {
+        if point.y < 0 || point.y >= self.height as i32 || point.z != 0 {
+            return None;
+        }
+
+        if point.x < 0 || point.x >= self.ring_size(point.y) {
+            return None;
+        }
+
+        return Some(self.offsets[point.y as usize] + point.x as usize);
+    }
+}
+
+impl Drawable for PolarGrid {
+    // polar cells already change width from ring to ring, so only their height is used,
+    // as the thickness of each ring.
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let cell_size = style.cell_height;
+        let img_size = 2 * cell_size * self.height;
+
+        let mut imgbuf =
+            image::ImageBuffer::from_fn(img_size as u32 + 1, img_size as u32 + 1, |_, _| {
+                return style.background;
+            });
+
+        let center = (img_size / 2) as i32;
+        let (max_distance, _) = self.distances.max(self);
+
+        // each cell is an annular sector, filled by tracing along its outer arc and back
+        // along its inner one.
+        for cell in self.cells.iter().flatten() {
+            let color = self.background_color_for(cell, &self.distances, max_distance, style);
+
+            if color == style.background {
+                continue;
+            }
+
+            let theta = 2.0 * std::f32::consts::PI / self.ring_size(cell.point.y) as f32;
+            let inner_radius = (cell.point.y as usize * cell_size) as f32;
+            let outer_radius = inner_radius + cell_size as f32;
+            let steps = ((theta * outer_radius / 4.0).ceil() as usize).max(1);
+            let at = |radius: f32, step: usize| {
+                let angle = (cell.point.x as f32 + step as f32 / steps as f32) * theta;
+
+                return (
+                    center + (radius * angle.cos()).round() as i32,
+                    center + (radius * angle.sin()).round() as i32,
+                );
+            };
+
+            let mut corners = (0..=steps)
+                .map(|step| at(outer_radius, step))
+                .collect::<Vec<(i32, i32)>>();
+            corners.extend((0..=steps).rev().map(|step| at(inner_radius, step)));
+
+            PolarGrid::fill_polygon(&mut imgbuf, &corners, color);
+        }
+
+        // the walls around the center cell are drawn as the inward walls of the first ring.
+        for cell in self.cells.iter().flatten().filter(|cell| cell.point.y > 0) {
+            let cells_in_row = self.ring_size(cell.point.y);
+
+            let theta = 2.0 * std::f32::consts::PI / cells_in_row as f32;
+            let inner_radius = cell.point.y * cell_size as i32;
+            let outer_radius = (cell.point.y + 1) * cell_size as i32;
+
+            let theta_ccw = cell.point.x as f32 * theta;
+            let theta_cw = (cell.point.x + 1) as f32 * theta;
+
+            let ax = center + (inner_radius as f32 * theta_ccw.cos()).round() as i32;
+            let ay = center + (inner_radius as f32 * theta_ccw.sin()).round() as i32;
+            //let bx = center + (outer_radius as f32 * theta_ccw.cos()).round() as i32;
+            //let by = center + (outer_radius as f32 * theta_ccw.sin()).round() as i32;
+            let cx = center + (inner_radius as f32 * theta_cw.cos()).round() as i32;
+            let cy = center + (inner_radius as f32 * theta_cw.sin()).round() as i32;
+            let dx = center + (outer_radius as f32 * theta_cw.cos()).round() as i32;
+            let dy = center + (outer_radius as f32 * theta_cw.sin()).round() as i32;
+
+            if !self.linked(cell.point, self.inward(cell.point)) {
+                RectangularGrid::draw_line(
+                    &mut imgbuf,
+                    ax,
+                    ay,
+                    cx,
+                    cy,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            }
+
+            if !self.linked(cell.point, self.clockwise(cell.point)) {
+                RectangularGrid::draw_line(
+                    &mut imgbuf,
+                    cx,
+                    cy,
+                    dx,
+                    dy,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            }
+        }
+
+        let radius = self.height * cell_size;
+        PolarGrid::circle(
+            &mut imgbuf,
+            center as u32,
+            center as u32,
+            radius,
+            style.wall_color,
+        );
+
+        // thick outer walls grow inward, keeping them inside the image.
+        if style.wall_thickness > 1 {
+            let inner = radius.saturating_sub(style.wall_thickness - 1) as f32;
+            let center = center as f32;
+
+            for (x, y, pixel) in imgbuf.enumerate_pixels_mut() {
+                let distance = (x as f32 - center).hypot(y as f32 - center).round();
+
+                if distance >= inner && distance <= radius as f32 {
+                    *pixel = style.wall_color;
+                }
+            }
+        }
+
+        return imgbuf;
+    }
+
+    // inward walls are drawn as true arcs, rather than the straight chords of the PNG.
+    fn to_svg(&self, style: &Style) -> Option<String> {
+        let cell_size = style.cell_height as f64;
+        let img_size = 2.0 * cell_size * self.height as f64;
+        let center = img_size / 2.0;
+        let (max_distance, _) = self.distances.max(self);
+        let mut body = String::new();
+        let mut walls = String::new();
+
+        for cell in self.cells.iter().flatten() {
+            let color = self.background_color_for(cell, &self.distances, max_distance, style);
+
+            if color == style.background {
+                continue;
+            }
+
+            let theta = 2.0 * std::f64::consts::PI / self.ring_size(cell.point.y) as f64;
+            let inner_radius = cell.point.y as f64 * cell_size;
+            let outer_radius = inner_radius + cell_size;
+            let at = |radius: f64, half: f64| {
+                let angle = (cell.point.x as f64 + half / 2.0) * theta;
+                format!(
+                    "{:.2} {:.2}",
+                    center + radius * angle.cos(),
+                    center + radius * angle.sin()
+                )
+            };
+
+            // each arc is drawn in two halves, so neither is more than a semicircle even
+            // for a cell going all the way around.
+            let (outer, inner) = (
+                format!("A{:.2} {:.2} 0 0 1 ", outer_radius, outer_radius),
+                format!("A{:.2} {:.2} 0 0 0 ", inner_radius, inner_radius),
+            );
+
+            body.push_str(&format!(
+                "<path d=\"M{}{}{}{}{}L{}{}{}{}{}Z\" fill=\"{}\"/>\n",
+                at(outer_radius, 0.0),
+                outer,
+                at(outer_radius, 1.0),
+                outer,
+                at(outer_radius, 2.0),
+                at(inner_radius, 2.0),
+                inner,
+                at(inner_radius, 1.0),
+                inner,
+                at(inner_radius, 0.0),
+                svg_color(color)
+            ));
+        }
+
+        for cell in self.cells.iter().flatten().filter(|cell| cell.point.y > 0) {
+            let theta = 2.0 * std::f64::consts::PI / self.ring_size(cell.point.y) as f64;
+            let inner_radius = cell.point.y as f64 * cell_size;
+            let outer_radius = (cell.point.y + 1) as f64 * cell_size;
+
+            let theta_ccw = cell.point.x as f64 * theta;
+            let theta_cw = (cell.point.x + 1) as f64 * theta;
+            let at = |radius: f64, angle: f64| {
+                (center + radius * angle.cos(), center + radius * angle.sin())
+            };
+
+            let (ax, ay) = at(inner_radius, theta_ccw);
+            let (cx, cy) = at(inner_radius, theta_cw);
+            let (dx, dy) = at(outer_radius, theta_cw);
+
+            if !self.linked(cell.point, self.inward(cell.point)) {
+                walls.push_str(&format!(
+                    "M{:.2} {:.2}A{:.2} {:.2} 0 0 1 {:.2} {:.2}",
+                    ax, ay, inner_radius, inner_radius, cx, cy
+                ));
+            }
+
+            if !self.linked(cell.point, self.clockwise(cell.point)) {
+                walls.push_str(&format!("M{:.2} {:.2}L{:.2} {:.2}", cx, cy, dx, dy));
+            }
+        }
+
+        body.push_str(&svg_path(&walls, style.wall_color, style.wall_thickness));
+        body.push_str(&format!(
+            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"none\" stroke=\"{}\" \
+             stroke-width=\"{}\"/>\n",
+            center,
+            center,
+            center,
+            svg_color(style.wall_color),
+            style.wall_thickness
+        ));
+
+        let size = img_size as usize;
+
+        return Some(svg_document(size, size, &body, style.background));
+    }
+}
+
+impl Maskable for PolarGrid {
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = PolarGrid::new(mask.width, mask.height);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    // each ring is stretched across its row of the mask, so subdivided rings sample the
+    // same mask column for several cells.
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        let mut index = 0;
+
+        for (y, &count) in self.rings.iter().enumerate() {
+            for x in 0..count {
+                let column = x * mask.width / count;
+
+                if !mask.mask[y * mask.width + column] {
+                    self.cells[index] = None;
+                }
+
+                index += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crop_keeps_the_passages_entrances_and_solution_inside() {
+        let grid = MazeBuilder::new()
+            .size(8, 6)
+            .seed(4)
+            .entrances(EntranceType::Opposite)
+            .solve()
+            .build();
+        let rect = Rect::new(0, 1, 4, 5);
+        let origin = rect.origin();
+        let cropped = grid.crop(rect);
+
+        assert_eq!((cropped.width, cropped.height), (4, 5));
+
+        for cell in cropped.cells.iter().flatten() {
+            let mut links = cropped.links(cell.point);
+            let mut expected = grid
+                .links(cell.point + origin)
+                .into_iter()
+                .filter(|&link| rect.contains(link))
+                .map(|link| link - origin)
+                .collect::<Vec<Point>>();
+            links.sort();
+            expected.sort();
+
+            assert_eq!(links, expected);
+        }
+
+        let path = grid
+            .path
+            .iter()
+            .filter(|&&point| rect.contains(point))
+            .map(|&point| point - origin)
+            .collect::<Vec<Point>>();
+
+        assert!(!path.is_empty());
+        assert_eq!(cropped.path, path);
+        assert_eq!(cropped.entrances.len(), 1);
+        assert_eq!(cropped.entrances[0].cell, grid.entrances[0].cell - origin);
+    }
+}
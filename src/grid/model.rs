@@ -0,0 +1,154 @@
+use crate::prelude::*;
+
+// Lattice : The maze laid out on a grid of blocks `2 * width + 1` by `2 * height + 1`, with
+// posts at the corners of cells, walls between the posts and the cells themselves in between.
+// `floor` covers the blocks of every cell that isn't masked out and the walls around it, and
+// `walls` the posts and walls that are standing.
+pub struct Lattice {
+    pub columns: usize,
+    pub rows: usize,
+    pub floor: Vec<bool>,
+    pub walls: Vec<bool>,
+}
+
+impl RectangularGrid {
+    pub fn lattice(&self) -> Lattice {
+        let (columns, rows) = (2 * self.width + 1, 2 * self.height + 1);
+        let mut floor = vec![false; columns * rows];
+        let mut walls = vec![false; columns * rows];
+
+        for cell in self.cells.iter().flatten() {
+            let (x, y) = (2 * cell.point.x as usize, 2 * cell.point.y as usize);
+
+            for j in y..=y + 2 {
+                for i in x..=x + 2 {
+                    floor[j * columns + i] = true;
+                }
+            }
+        }
+
+        // every wall is a block between the two posts at its ends.
+        for (from, to) in self.walls() {
+            let (i, j) = (2 * from.x as usize, 2 * from.y as usize);
+            let (end_i, end_j) = (2 * to.x as usize, 2 * to.y as usize);
+
+            for j in j..=end_j {
+                for i in i..=end_i {
+                    walls[j * columns + i] = true;
+                }
+            }
+        }
+
+        return Lattice {
+            columns,
+            rows,
+            floor,
+            walls,
+        };
+    }
+
+    // the maze as a solid model with its walls standing on a base plate, sized by `options`.
+    // The model is built out of the blocks of its lattice. Posts are as thick as the walls and
+    // cells fill the rest. Only the sides of blocks facing empty space become faces, so the
+    // surface is closed and has no faces inside of it. The base plate is given the floor
+    // material and the walls standing on it the wall material.
+    pub fn to_mesh(&self, options: &MeshOptions) -> Mesh {
+        let Lattice {
+            columns,
+            rows,
+            mut floor,
+            walls,
+        } = self.lattice();
+
+        if options.base <= 0.0 {
+            floor.fill(false);
+        }
+
+        let layers = [floor, walls];
+        let solid = |i: i64, j: i64, layer: i64| {
+            if i < 0 || j < 0 || layer < 0 || i >= columns as i64 || j >= rows as i64 {
+                return false;
+            }
+
+            return layers
+                .get(layer as usize)
+                .is_some_and(|blocks| blocks[j as usize * columns + i as usize]);
+        };
+
+        let start =
+            |i: usize| (i / 2) as f32 * options.cell_size + (i % 2) as f32 * options.wall_thickness;
+        let depth = start(rows);
+        let heights = [0.0, options.base, options.base + options.wall_height];
+
+        let mut mesh = Mesh::new(options.cell_size);
+        let materials = [Material::Floor, Material::Wall];
+
+        for layer in 0..layers.len() {
+            for j in 0..rows {
+                for i in 0..columns {
+                    let (i, j, layer) = (i as i64, j as i64, layer as i64);
+
+                    if !solid(i, j, layer) {
+                        continue;
+                    }
+
+                    // rows run down the maze, so y is flipped to keep the model from being
+                    // a mirror image of it.
+                    let (x0, x1) = (start(i as usize), start(i as usize + 1));
+                    let (y0, y1) = (depth - start(j as usize + 1), depth - start(j as usize));
+                    let (z0, z1) = (heights[layer as usize], heights[layer as usize + 1]);
+
+                    if !solid(i + 1, j, layer) {
+                        mesh.add_quad(
+                            [[x1, y0, z0], [x1, y1, z0], [x1, y1, z1], [x1, y0, z1]],
+                            [1.0, 0.0, 0.0],
+                            materials[layer as usize],
+                        );
+                    }
+
+                    if !solid(i - 1, j, layer) {
+                        mesh.add_quad(
+                            [[x0, y1, z0], [x0, y0, z0], [x0, y0, z1], [x0, y1, z1]],
+                            [-1.0, 0.0, 0.0],
+                            materials[layer as usize],
+                        );
+                    }
+
+                    if !solid(i, j - 1, layer) {
+                        mesh.add_quad(
+                            [[x1, y1, z0], [x0, y1, z0], [x0, y1, z1], [x1, y1, z1]],
+                            [0.0, 1.0, 0.0],
+                            materials[layer as usize],
+                        );
+                    }
+
+                    if !solid(i, j + 1, layer) {
+                        mesh.add_quad(
+                            [[x0, y0, z0], [x1, y0, z0], [x1, y0, z1], [x0, y0, z1]],
+                            [0.0, -1.0, 0.0],
+                            materials[layer as usize],
+                        );
+                    }
+
+                    if !solid(i, j, layer + 1) {
+                        mesh.add_quad(
+                            [[x0, y0, z1], [x1, y0, z1], [x1, y1, z1], [x0, y1, z1]],
+                            [0.0, 0.0, 1.0],
+                            materials[layer as usize],
+                        );
+                    }
+
+                    if !solid(i, j, layer - 1) {
+                        mesh.add_quad(
+                            [[x0, y0, z0], [x0, y1, z0], [x1, y1, z0], [x1, y0, z0]],
+                            [0.0, 0.0, -1.0],
+                            materials[layer as usize],
+                        );
+                    }
+                }
+            }
+        }
+
+        return mesh;
+    }
+}
@@ -0,0 +1,78 @@
+use crate::prelude::*;
+
+impl RectangularGrid {
+    // copies the passages carved in `inner` into this grid, with the inner grid's origin
+    // placed at `offset`.
+    pub fn stamp(&mut self, inner: &RectangularGrid, offset: Point) {
+        self.copy_links(inner, &|point| point + offset);
+    }
+
+    // a grid where each cell of this maze becomes a block `size` cells wide. Each block is
+    // filled with an inner maze carved by `algorithm` with `chance` probability, and is left
+    // open as a single room otherwise. Passages of this maze open up the middle of the
+    // shared side of the two blocks, so the inner mazes join up along the outer maze.
+    pub fn nest(&self, size: usize, chance: f64, algorithm: &mut Algorithm) -> RectangularGrid {
+        let mut rng = random::rng();
+        let mut nested = RectangularGrid::new(self.width * size, self.height * size);
+        let size = size as i32;
+
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let outer = Point::new(x, y);
+                let offset = Point::new(x * size, y * size);
+
+                if self.get(outer).is_none() {
+                    for inner_y in 0..size {
+                        for inner_x in 0..size {
+                            let index =
+                                nested.point_to_index(offset + Point::new(inner_x, inner_y));
+                            nested.cells[index.unwrap()] = None;
+                        }
+                    }
+
+                    continue;
+                }
+
+                let mut inner = RectangularGrid::new(size as usize, size as usize);
+
+                if rng.gen_bool(chance.clamp(0.0, 1.0)) {
+                    algorithm.on(&mut inner);
+                } else {
+                    let points: Vec<Point> =
+                        inner.cells.iter().flatten().map(|c| c.point).collect();
+
+                    for point in points {
+                        for neighbor in inner.neighbors(point) {
+                            inner.link(point, neighbor, true);
+                        }
+                    }
+                }
+
+                nested.stamp(&inner, offset);
+            }
+        }
+
+        // only east and south links are followed so each outer passage is opened once.
+        let middle = size / 2;
+
+        for cell in self.cells.iter().flatten() {
+            let offset = Point::new(cell.point.x * size, cell.point.y * size);
+
+            if self.linked(cell.point, cell.point.east()) {
+                let from = offset + Point::new(size - 1, middle);
+                nested.link(from, from.east(), true);
+            }
+
+            if self.linked(cell.point, cell.point.south()) {
+                let from = offset + Point::new(middle, size - 1);
+                nested.link(from, from.south(), true);
+            }
+        }
+
+        if let Some(start) = nested.first_cell() {
+            nested.distances = Distances::new(start);
+        }
+
+        return nested;
+    }
+}
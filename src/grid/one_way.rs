@@ -0,0 +1,93 @@
+use crate::prelude::*;
+
+impl RectangularGrid {
+    // turns each passage between neighboring cells, with probability `p`, into one that can
+    // only be walked one way. Each leads away from the start of the maze, so every cell that
+    // could be reached from the start still can be, though not always back again. Returns
+    // how many passages were turned.
+    pub fn make_one_way(&mut self, p: f64) -> usize {
+        let start = match self.ends() {
+            Some((start, _)) => start,
+            None => return 0,
+        };
+
+        let mut distances = Distances::new(start);
+        distances.compute(self);
+
+        let mut random = random::rng();
+        let mut turned = 0;
+        let points = self
+            .cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.point)
+            .collect::<Vec<Point>>();
+
+        for point in points {
+            for next in [point.east(), point.south()] {
+                if !self.linked(point, next) || !self.linked(next, point) || !random.gen_bool(p) {
+                    continue;
+                }
+
+                // passages between cells as far from the start as each other, as loops can
+                // have, may go either way.
+                let (from, to) = match (distances.distance(point), distances.distance(next)) {
+                    (Some(a), Some(b)) if a < b => (point, next),
+                    (Some(a), Some(b)) if a > b => (next, point),
+                    _ if random.gen_bool(0.5) => (point, next),
+                    _ => (next, point),
+                };
+
+                self.unlink(to, from, false);
+                turned += 1;
+            }
+        }
+
+        return turned;
+    }
+
+    // every passage that can only be walked one way, as the cell it leads from and the cell
+    // it leads to.
+    pub fn one_way_passages(&self) -> Vec<(Point, Point)> {
+        let mut passages = Vec::new();
+
+        for cell in self.cells.iter().flatten() {
+            for link in self.links(cell.point) {
+                if !self.linked(link, cell.point) {
+                    passages.push((cell.point, link));
+                }
+            }
+        }
+
+        return passages;
+    }
+
+    // the corners of an arrow drawn across the wall between the ends of a one-way passage
+    // between neighbors, pointing the way it can be walked.
+    pub(crate) fn arrow(&self, from: Point, to: Point, style: &Style) -> Option<[(i32, i32); 3]> {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+
+        if dx.abs() + dy.abs() != 1 {
+            return None;
+        }
+
+        let (cell_width, cell_height) = (style.cell_width as i32, style.cell_height as i32);
+        let size = (cell_width.min(cell_height) / 5).max(1);
+        let (middle_x, middle_y) = (
+            from.x * cell_width + cell_width / 2 + dx * cell_width / 2,
+            from.y * cell_height + cell_height / 2 + dy * cell_height / 2,
+        );
+
+        return Some([
+            (middle_x + dx * size, middle_y + dy * size),
+            (
+                middle_x - dx * size - dy * size,
+                middle_y - dy * size - dx * size,
+            ),
+            (
+                middle_x - dx * size + dy * size,
+                middle_y - dy * size + dx * size,
+            ),
+        ]);
+    }
+}
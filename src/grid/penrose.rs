@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+type Vertex = (f64, f64);
+
+// vertices are compared after rounding, since the same corner is reached through
+// different subdivisions with slightly different floating point error.
+type VertexKey = (i64, i64);
+
+// the most generations a mask will ask for, the tile count grows by about 2.6x each time.
+const MAX_GENERATIONS: usize = 10;
+
+// half of a rhombus, split along one of its diagonals. `b` and `c` are the ends of the
+// diagonal and `a` is the corner across from it. Acute halves have a 36 degree corner at
+// `a` and obtuse halves a 108 degree one.
+#[derive(Clone, Copy)]
+struct Half {
+    acute: bool,
+    a: Vertex,
+    b: Vertex,
+    c: Vertex,
+}
+
+fn golden_ratio() -> f64 {
+    (1.0 + 5f64.sqrt()) / 2.0
+}
+
+fn lerp(from: Vertex, to: Vertex, t: f64) -> Vertex {
+    (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t)
+}
+
+fn key(vertex: Vertex) -> VertexKey {
+    (
+        (vertex.0 * 1e6).round() as i64,
+        (vertex.1 * 1e6).round() as i64,
+    )
+}
+
+fn edge_key(a: Vertex, b: Vertex) -> (VertexKey, VertexKey) {
+    let (a, b) = (key(a), key(b));
+
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+// PenroseGrid : A maze over a Penrose rhombus tiling (P3), which covers the plane without
+// ever repeating itself. The tiling is grown from a wheel of ten triangles that are split
+// into smaller ones a number of times, pairs of triangles sharing a diagonal then make up
+// the rhombi. Halves cut off at the edge of the wheel are kept as triangular cells. Cells
+// are identified by the order their tiles were found, Point::new(id, 0).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PenroseGrid {
+    pub generations: usize,
+    pub cells: Vec<Option<Cell>>,
+    // the corners of each tile on a wheel of radius 1.
+    pub tiles: Vec<Vec<Vertex>>,
+    // the tile across each side, side i runs from corner i to corner i + 1.
+    pub sides: Vec<Vec<Option<Point>>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl PenroseGrid {
+    pub fn new(generations: usize) -> Self {
+        let mut halves = PenroseGrid::wheel();
+
+        for _ in 0..generations {
+            halves = PenroseGrid::subdivide(&halves);
+        }
+
+        // pair up the halves that share a diagonal.
+        let mut pairs: Vec<Vec<Half>> = Vec::new();
+        let mut diagonals: HashMap<(bool, (VertexKey, VertexKey)), usize> = HashMap::new();
+
+        for half in halves {
+            let diagonal = (half.acute, edge_key(half.b, half.c));
+
+            match diagonals.get(&diagonal) {
+                Some(&i) => pairs[i].push(half),
+                None => {
+                    diagonals.insert(diagonal, pairs.len());
+                    pairs.push(vec![half]);
+                }
+            }
+        }
+
+        let tiles: Vec<Vec<Vertex>> = pairs
+            .iter()
+            .map(|pair| match pair.as_slice() {
+                [first, second] => vec![first.a, first.b, second.a, first.c],
+                [half] => vec![half.a, half.b, half.c],
+                _ => unreachable!(),
+            })
+            .collect();
+
+        let mut edges: HashMap<(VertexKey, VertexKey), Vec<usize>> = HashMap::new();
+
+        for (i, tile) in tiles.iter().enumerate() {
+            for j in 0..tile.len() {
+                let edge = edge_key(tile[j], tile[(j + 1) % tile.len()]);
+                edges.entry(edge).or_default().push(i);
+            }
+        }
+
+        let sides = tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| {
+                (0..tile.len())
+                    .map(|j| {
+                        let edge = edge_key(tile[j], tile[(j + 1) % tile.len()]);
+
+                        edges[&edge]
+                            .iter()
+                            .find(|&&other| other != i)
+                            .map(|&other| Point::new(other as i32, 0))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let cells = (0..tiles.len())
+            .map(|i| Some(Cell::new(Point::new(i as i32, 0))))
+            .collect();
+
+        Self {
+            generations,
+            cells,
+            tiles,
+            sides,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    // ten acute halves around the origin, mirrored every other one so that neighboring
+    // halves share their diagonals.
+    fn wheel() -> Vec<Half> {
+        return (0..10)
+            .map(|i| {
+                let angle = |step: i32| step as f64 * std::f64::consts::PI / 10.0;
+                let b = angle(2 * i - 1);
+                let c = angle(2 * i + 1);
+                let (b, c) = ((b.cos(), b.sin()), (c.cos(), c.sin()));
+                let (b, c) = if i % 2 == 0 { (c, b) } else { (b, c) };
+
+                Half {
+                    acute: true,
+                    a: (0.0, 0.0),
+                    b,
+                    c,
+                }
+            })
+            .collect();
+    }
+
+    fn subdivide(halves: &[Half]) -> Vec<Half> {
+        let phi = golden_ratio();
+        let mut result = Vec::with_capacity(halves.len() * 3);
+
+        for &Half { acute, a, b, c } in halves {
+            if acute {
+                let p = lerp(a, b, 1.0 / phi);
+
+                result.push(Half {
+                    acute: true,
+                    a: c,
+                    b: p,
+                    c: b,
+                });
+                result.push(Half {
+                    acute: false,
+                    a: p,
+                    b: c,
+                    c: a,
+                });
+            } else {
+                let q = lerp(b, a, 1.0 / phi);
+                let r = lerp(b, c, 1.0 / phi);
+
+                result.push(Half {
+                    acute: false,
+                    a: r,
+                    b: c,
+                    c: a,
+                });
+                result.push(Half {
+                    acute: false,
+                    a: q,
+                    b: r,
+                    c: b,
+                });
+                result.push(Half {
+                    acute: true,
+                    a: r,
+                    b: q,
+                    c: a,
+                });
+            }
+        }
+
+        return result;
+    }
+
+    fn centroid(&self, index: usize) -> Vertex {
+        let tile = &self.tiles[index];
+        let count = tile.len() as f64;
+
+        return (
+            tile.iter().map(|v| v.0).sum::<f64>() / count,
+            tile.iter().map(|v| v.1).sum::<f64>() / count,
+        );
+    }
+}
+
+impl Grid for PenroseGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn height(&self) -> usize {
+        1
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        if !self.neighbors(a).contains(&b) {
+            return;
+        }
+
+        self.links.link(a, b, bidi);
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        match self.point_to_index(point) {
+            Some(index) => self.sides[index]
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|&p| self.get(p).is_some())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Drawable for PenroseGrid {
+    // a tile's side spans `cell_width` pixels across and `cell_height` pixels down.
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let scale = golden_ratio().powi(self.generations as i32);
+        let (radius_x, radius_y) = (cell_width as f64 * scale, cell_height as f64 * scale);
+        let img_width = (2.0 * radius_x).ceil() as u32 + 1;
+        let img_height = (2.0 * radius_y).ceil() as u32 + 1;
+
+        let mut imgbuf = image::ImageBuffer::from_fn(img_width, img_height, |_, _| {
+            return style.background;
+        });
+
+        let at = |vertex: Vertex| {
+            (
+                (radius_x + vertex.0 * radius_x).round() as i32,
+                (radius_y + vertex.1 * radius_y).round() as i32,
+            )
+        };
+
+        for mode in ["background", "walls"] {
+            for cell in self.cells.iter().flatten() {
+                let index = cell.point.x as usize;
+                let corners: Vec<(i32, i32)> = self.tiles[index].iter().map(|&v| at(v)).collect();
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    PenroseGrid::fill_polygon(&mut imgbuf, &corners, color);
+                    continue;
+                }
+
+                for (j, side) in self.sides[index].iter().enumerate() {
+                    if side.is_some_and(|s| self.linked(cell.point, s)) {
+                        continue;
+                    }
+
+                    let (x1, y1) = corners[j];
+                    let (x2, y2) = corners[(j + 1) % corners.len()];
+                    PenroseGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+            }
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Maskable for PenroseGrid {
+    // enough generations are used to give about as many tiles as the mask has cells.
+    fn from_mask(mask: &Mask) -> Self {
+        let target = (mask.width * mask.height) as f64;
+        let generations = (0..MAX_GENERATIONS)
+            .find(|&n| 5.0 * golden_ratio().powi(2 * n as i32) >= target)
+            .unwrap_or(MAX_GENERATIONS);
+
+        let mut grid = PenroseGrid::new(generations);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    // the mask is centered on the wheel with its longest side across it, a tile is kept when
+    // the mask cell under its center is.
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        let longest = mask.width.max(mask.height) as f64;
+
+        for i in 0..self.tiles.len() {
+            let (x, y) = self.centroid(i);
+            let column = (x + 1.0) / 2.0 * longest - (longest - mask.width as f64) / 2.0;
+            let row = (y + 1.0) / 2.0 * longest - (longest - mask.height as f64) / 2.0;
+
+            let inside = (0.0..mask.width as f64).contains(&column)
+                && (0.0..mask.height as f64).contains(&row);
+
+            if !inside || !mask.mask[row as usize * mask.width + column as usize] {
+                self.cells[i] = None;
+            }
+        }
+    }
+}
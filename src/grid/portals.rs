@@ -0,0 +1,143 @@
+use rand::seq::SliceRandom;
+
+use crate::prelude::*;
+
+// how many tries each portal gets to find two cells far enough apart.
+const PORTAL_ATTEMPTS: usize = 50;
+
+// the colors portal pairs are marked in, taken in turn so each pair can be told apart.
+const PORTAL_COLORS: [Rgb<u8>; 6] = [
+    Rgb([250, 160, 0]),
+    Rgb([0, 170, 190]),
+    Rgb([150, 60, 220]),
+    Rgb([220, 30, 60]),
+    Rgb([90, 180, 40]),
+    Rgb([240, 90, 190]),
+];
+
+impl RectangularGrid {
+    // joins the cells at `a` and `b` with a portal, a passage between two cells that needn't
+    // be next to each other. Walking into one comes out of the other, and distances and
+    // solutions pass through it like any other passage. Returns whether both cells exist.
+    pub fn link_portal(&mut self, a: Point, b: Point) -> bool {
+        if a == b || self.get(a).is_none() || self.get(b).is_none() {
+            return false;
+        }
+
+        self.link(a, b, true);
+
+        return true;
+    }
+
+    // adds up to `count` portals between cells picked at random, each pair at least a third
+    // of the way across the maze from each other. A cell is only ever one end of one portal.
+    pub fn add_random_portals(&mut self, count: usize) -> Vec<(Point, Point)> {
+        let apart = ((self.width + self.height) / 3).max(2) as i32;
+        let mut used = self
+            .portals()
+            .into_iter()
+            .flat_map(|(a, b)| [a, b])
+            .collect::<Vec<Point>>();
+        let cells = self
+            .cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.point)
+            .collect::<Vec<Point>>();
+        let mut added = Vec::new();
+        let mut random = random::rng();
+
+        for _ in 0..count * PORTAL_ATTEMPTS {
+            if added.len() == count {
+                break;
+            }
+
+            let (a, b) = match (cells.choose(&mut random), cells.choose(&mut random)) {
+                (Some(&a), Some(&b)) => (a, b),
+                _ => break,
+            };
+
+            let far = (a.x - b.x).abs() + (a.y - b.y).abs() >= apart;
+
+            if far && !used.contains(&a) && !used.contains(&b) && self.link_portal(a, b) {
+                used.extend([a, b]);
+                added.push((a, b));
+            }
+        }
+
+        return added;
+    }
+
+    // both ends of every portal, the passages between cells that aren't next to each other.
+    pub fn portals(&self) -> Vec<(Point, Point)> {
+        let mut portals = Vec::new();
+
+        for cell in self.cells.iter().flatten() {
+            for link in self.links(cell.point) {
+                let (dx, dy) = (link.x - cell.point.x, link.y - cell.point.y);
+
+                if dx.abs() + dy.abs() > 1 && cell.point < link {
+                    portals.push((cell.point, link));
+                }
+            }
+        }
+
+        return portals;
+    }
+
+    // the index of the portal with an end at `point`, which names and colors its marker.
+    pub(crate) fn portal_at(&self, point: Point) -> Option<usize> {
+        return self
+            .portals()
+            .iter()
+            .position(|&(a, b)| a == point || b == point);
+    }
+
+    // marks both ends of every portal with a square of its own color, a quarter of a cell in
+    // from the walls.
+    pub(crate) fn draw_portals(&self, image: &mut RgbImage, style: &Style, left: i32, top: i32) {
+        let (cell_width, cell_height) = (style.cell_width as i32, style.cell_height as i32);
+        let inset = cell_width.min(cell_height) / 4;
+
+        for (i, (a, b)) in self.portals().into_iter().enumerate() {
+            for point in [a, b] {
+                let (x1, y1) = (
+                    point.x * cell_width + inset - left,
+                    point.y * cell_height + inset - top,
+                );
+                let (x2, y2) = (
+                    (point.x + 1) * cell_width - inset - left,
+                    (point.y + 1) * cell_height - inset - top,
+                );
+
+                RectangularGrid::fill_polygon(
+                    image,
+                    &[(x1, y1), (x2, y1), (x2, y2), (x1, y2)],
+                    portal_color(i),
+                );
+            }
+        }
+    }
+}
+
+pub(crate) fn portal_color(index: usize) -> Rgb<u8> {
+    return PORTAL_COLORS[index % PORTAL_COLORS.len()];
+}
+
+// reads a portal given as the cells at either end, x1,y1,x2,y2 like 0,0,9,9.
+pub fn parse_portal(value: &str) -> Result<(Point, Point), String> {
+    let numbers = value
+        .split(',')
+        .map(|v| v.trim().parse::<i32>())
+        .collect::<Result<Vec<i32>, _>>();
+
+    match numbers.as_deref() {
+        Ok(&[x1, y1, x2, y2]) => return Ok((Point::new(x1, y1), Point::new(x2, y2))),
+        _ => {
+            return Err(format!(
+                "'{}' is not given as x1,y1,x2,y2, like 0,0,9,9",
+                value
+            ))
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::prelude::*;
+
+impl RectangularGrid {
+    // a print ready PDF of the maze, as large as fits `page` inside `margin` millimeters with
+    // cells in the proportions of `style`. Black walls are drawn on white paper, and with
+    // `solution` a second page shows the solved maze.
+    pub fn to_pdf(&self, page: PageSize, margin: f64, style: &Style, solution: bool) -> Vec<u8> {
+        let (page_width, page_height) = page.dimensions();
+        let margin = margin * POINTS_PER_MM;
+        let (cell_width, cell_height) = (style.cell_width as f64, style.cell_height as f64);
+
+        let scale = ((page_width - 2.0 * margin) / (self.width as f64 * cell_width))
+            .min((page_height - 2.0 * margin) / (self.height as f64 * cell_height))
+            .max(0.0);
+        let (cell_width, cell_height) = (cell_width * scale, cell_height * scale);
+
+        // the maze is centered on the page, PDF measures y upwards from the bottom.
+        let left = (page_width - self.width as f64 * cell_width) / 2.0;
+        let top = page_height - (page_height - self.height as f64 * cell_height) / 2.0;
+        let at = |x: f64, y: f64| (left + x * cell_width, top - y * cell_height);
+
+        let walls = self
+            .walls()
+            .into_iter()
+            .map(|(from, to)| {
+                vec![
+                    at(from.x as f64, from.y as f64),
+                    at(to.x as f64, to.y as f64),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let thickness = (cell_width.min(cell_height) / 8.0).clamp(0.5, 3.0);
+        let maze = pdf_stroke(&walls, BLACK, thickness);
+
+        let mut pdf = Pdf::new(page_width, page_height);
+        pdf.add_page(maze.clone());
+
+        if solution {
+            let mut solved = self.clone();
+
+            if solved.path.is_empty() {
+                solved.solve();
+            }
+
+            // measured in half cells, so the middle of each cell falls on a whole number.
+            let line = solved
+                .solution_line(2, 2)
+                .into_iter()
+                .map(|(x, y)| at(x as f64 / 2.0, y as f64 / 2.0))
+                .collect::<Vec<_>>();
+
+            pdf.add_page(maze + &pdf_stroke(&[line], PATH, thickness));
+        }
+
+        return pdf.to_bytes();
+    }
+}
@@ -0,0 +1,155 @@
+use std::f64::consts::FRAC_PI_2;
+
+use crate::prelude::*;
+
+// how wide the view is, in radians.
+const FIELD_OF_VIEW: f64 = 66.0 * std::f64::consts::PI / 180.0;
+// how many cells away walls fade completely into the background.
+const VIEW_DISTANCE: f64 = 12.0;
+
+// Camera : Where a first person view of a maze is taken from, measured in cells so the
+// middle of the cell at (x, y) is at x + 0.5, y + 0.5. The angle it faces is in radians
+// clockwise from east, as y grows southward.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub x: f64,
+    pub y: f64,
+    pub angle: f64,
+}
+
+impl Camera {
+    // standing in the middle of the cell at `point`, facing `direction`.
+    pub fn at(point: Point, direction: Direction) -> Self {
+        let angle = match direction {
+            Direction::East => 0.0,
+            Direction::South => FRAC_PI_2,
+            Direction::West => 2.0 * FRAC_PI_2,
+            Direction::North => 3.0 * FRAC_PI_2,
+        };
+
+        return Self {
+            x: point.x as f64 + 0.5,
+            y: point.y as f64 + 0.5,
+            angle,
+        };
+    }
+}
+
+impl RectangularGrid {
+    // the camera at the start of the maze, looking in through its entrance, or down the
+    // first passage out of the start when there's no entrance.
+    pub fn start_camera(&self) -> Option<Camera> {
+        let (start, _) = self.ends()?;
+        let facing = match self.entrances.first() {
+            Some(entrance) => entrance.outside.direction_to(entrance.cell),
+            None => self
+                .links(start)
+                .first()
+                .and_then(|&link| start.direction_to(link)),
+        };
+
+        return Some(Camera::at(start, facing.unwrap_or(Direction::East)));
+    }
+
+    // what the maze looks like from `camera`, `width` by `height` pixels. Walls are cast in
+    // the style's wall color and fade into its background with distance, with north and
+    // south facing walls a little darker than east and west facing ones.
+    pub fn to_first_person(
+        &self,
+        camera: &Camera,
+        width: u32,
+        height: u32,
+        style: &Style,
+    ) -> RgbImage {
+        let floor = blend(style.background, style.wall_color, 0.15);
+        let mut image = RgbImage::from_fn(width, height, |_, y| {
+            if y < height / 2 {
+                style.background
+            } else {
+                floor
+            }
+        });
+
+        let (dir_x, dir_y) = (camera.angle.cos(), camera.angle.sin());
+        let plane = (FIELD_OF_VIEW / 2.0).tan();
+        let (plane_x, plane_y) = (-dir_y * plane, dir_x * plane);
+
+        for column in 0..width {
+            let offset = 2.0 * column as f64 / width as f64 - 1.0;
+            let ray = (dir_x + plane_x * offset, dir_y + plane_y * offset);
+
+            let (distance, shaded) = match self.cast(camera, ray) {
+                Some(hit) => hit,
+                None => continue,
+            };
+
+            let wall_height = (height as f64 / distance.max(0.01)) as i64;
+            let top = (height as i64 - wall_height) / 2;
+            let fade = (distance / VIEW_DISTANCE).min(1.0);
+            let mut color = blend(style.wall_color, style.background, fade);
+
+            if shaded {
+                color = blend(color, style.background, 0.25);
+            }
+
+            for y in top.max(0)..(top + wall_height).min(height as i64) {
+                image.put_pixel(column, y as u32, color);
+            }
+        }
+
+        return image;
+    }
+
+    // follows a ray from the camera cell by cell until it meets a wall, giving how far away
+    // the wall is, measured straight ahead of the camera so walls don't bulge, and whether
+    // it faces north or south.
+    fn cast(&self, camera: &Camera, (ray_x, ray_y): (f64, f64)) -> Option<(f64, bool)> {
+        let mut cell = Point::new(camera.x.floor() as i32, camera.y.floor() as i32);
+        let (delta_x, delta_y) = ((1.0 / ray_x).abs(), (1.0 / ray_y).abs());
+        let step = Point::new(ray_x.signum() as i32, ray_y.signum() as i32);
+
+        // how far along the ray the next crossing of a column and of a row lies.
+        let mut side_x = if ray_x < 0.0 {
+            (camera.x - cell.x as f64) * delta_x
+        } else {
+            (cell.x as f64 + 1.0 - camera.x) * delta_x
+        };
+        let mut side_y = if ray_y < 0.0 {
+            (camera.y - cell.y as f64) * delta_y
+        } else {
+            (cell.y as f64 + 1.0 - camera.y) * delta_y
+        };
+
+        for _ in 0..=self.width + self.height {
+            // a ray that has left the maze has nothing more to meet.
+            self.get(cell)?;
+
+            let (next, distance, shaded) = if side_x < side_y {
+                side_x += delta_x;
+                (Point::new(cell.x + step.x, cell.y), side_x - delta_x, false)
+            } else {
+                side_y += delta_y;
+                (Point::new(cell.x, cell.y + step.y), side_y - delta_y, true)
+            };
+
+            if !self.open(cell, next) {
+                return Some((distance, shaded));
+            }
+
+            cell = next;
+        }
+
+        return None;
+    }
+}
+
+// `from` moved `amount` of the way towards `to`.
+fn blend(from: Rgb<u8>, to: Rgb<u8>, amount: f64) -> Rgb<u8> {
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount).round() as u8;
+
+    return Rgb([
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+    ]);
+}
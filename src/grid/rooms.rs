@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+
+use crate::prelude::*;
+
+// how many tries each room gets to find a spot before it's left out.
+const ROOM_ATTEMPTS: usize = 50;
+
+impl RectangularGrid {
+    // picks spots for up to `count` more rooms beside those `placed` already, each `min` to
+    // `max` cells wide and tall, where every cell they cover is part of the maze. Rooms keep
+    // a cell's gap from each other, so there's always maze between them. Rooms that find no
+    // spot are left out.
+    pub fn place_rooms(&self, placed: &[Rect], count: usize, min: usize, max: usize) -> Vec<Rect> {
+        let mut random = random::rng();
+        let (min, max) = (min.max(1), max.max(min.max(1)));
+        let mut rooms = placed.to_vec();
+
+        for _ in 0..count * ROOM_ATTEMPTS {
+            if rooms.len() == placed.len() + count {
+                break;
+            }
+
+            let (width, height) = (random.gen_range(min..=max), random.gen_range(min..=max));
+
+            if width > self.width || height > self.height {
+                continue;
+            }
+
+            let x = random.gen_range(0..=self.width - width) as i32;
+            let y = random.gen_range(0..=self.height - height) as i32;
+            let room = Rect::new(x, y, width, height);
+            // the room with the gap around it that no other room may cover.
+            let spaced = Rect::new(x - 1, y - 1, width + 2, height + 2);
+
+            let fits = room_cells(&room).all(|point| self.get(point).is_some());
+            let apart = rooms
+                .iter()
+                .all(|other| room_cells(other).all(|point| !spaced.contains(point)));
+
+            if fits && apart {
+                rooms.push(room);
+            }
+        }
+
+        return rooms.split_off(placed.len());
+    }
+
+    // opens each of `rooms` up into one space, with passages between all of its cells, and
+    // keeps only as many of the passages through its walls as are needed for the maze to
+    // stay connected. Those left are the room's doors, picked at random.
+    pub fn open_rooms(&mut self, rooms: &[Rect]) {
+        let area = |point: Point| rooms.iter().position(|room| room.contains(point));
+
+        for room in rooms.iter() {
+            for point in room_cells(room) {
+                for next in [point.east(), point.south()] {
+                    if room.contains(next) && self.get(next).is_some() {
+                        self.link(point, next, true);
+                    }
+                }
+            }
+        }
+
+        // the cells joined up by every passage that doesn't pass through a room's walls,
+        // with each group named by one of its cells.
+        let mut groups = HashMap::new();
+        let mut doors = Vec::new();
+
+        for cell in self.cells.iter().flatten() {
+            for link in self.links(cell.point) {
+                if area(cell.point) == area(link) {
+                    join(&mut groups, cell.point, link);
+                } else if cell.point < link {
+                    doors.push((cell.point, link));
+                }
+            }
+        }
+
+        doors.shuffle(&mut random::rng());
+
+        for (a, b) in doors {
+            if group(&mut groups, a) != group(&mut groups, b) {
+                join(&mut groups, a, b);
+                continue;
+            }
+
+            self.unlink(a, b, true);
+        }
+    }
+}
+
+fn room_cells(room: &Rect) -> impl Iterator<Item = Point> + '_ {
+    return (0..room.height as i32)
+        .flat_map(move |y| (0..room.width as i32).map(move |x| room.origin() + Point::new(x, y)));
+}
+
+// the cell that names the group `point` is in.
+fn group(groups: &mut HashMap<Point, Point>, point: Point) -> Point {
+    let parent = *groups.get(&point).unwrap_or(&point);
+
+    if parent == point {
+        return point;
+    }
+
+    let root = group(groups, parent);
+    groups.insert(point, root);
+
+    return root;
+}
+
+fn join(groups: &mut HashMap<Point, Point>, a: Point, b: Point) {
+    let (a, b) = (group(groups, a), group(groups, b));
+
+    if a != b {
+        groups.insert(a, b);
+    }
+}
+
+// reads rooms given as count,min,max, like 5,3,6 for five rooms of three to six cells a side.
+pub fn parse_rooms(value: &str) -> Result<(usize, usize, usize), String> {
+    let numbers = value
+        .split(',')
+        .map(|v| v.trim().parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>();
+
+    match numbers.as_deref() {
+        Ok(&[count, min, max]) if min > 0 && min <= max => return Ok((count, min, max)),
+        _ => {
+            return Err(format!(
+                "'{}' is not given as count,min,max, like 5,3,6",
+                value
+            ))
+        }
+    }
+}
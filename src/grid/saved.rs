@@ -0,0 +1,102 @@
+use crate::prelude::*;
+
+fn invalid(message: impl Into<String>) -> MazeError {
+    return MazeError::InvalidSave(message.into());
+}
+
+impl RectangularGrid {
+    // the whole maze as JSON, as serde writes it: every cell with its weight, the passages
+    // between them, the entrances, locked doors, solution and distances.
+    pub fn to_json(&self) -> String {
+        // points are only ever written as values, never as the keys JSON needs as strings.
+        return serde_json::to_string(self).expect("a maze is always valid JSON") + "\n";
+    }
+
+    // rebuilds a maze written by `to_json`, checking that its cells and passages fit the
+    // size it claims.
+    pub fn from_json(text: &str) -> Result<Self, MazeError> {
+        let mut grid =
+            serde_json::from_str::<RectangularGrid>(text).map_err(|e| invalid(e.to_string()))?;
+
+        if grid.cells.len() != grid.width * grid.height || !grid.sides.fits(grid.width, grid.height)
+        {
+            return Err(invalid(format!(
+                "the cells don't fill a {} by {} maze",
+                grid.width, grid.height
+            )));
+        }
+
+        for (index, cell) in grid.cells.iter().enumerate() {
+            let point = Point::new((index % grid.width) as i32, (index / grid.width) as i32);
+
+            match cell {
+                Some(cell) if cell.point != point => {
+                    return Err(invalid(format!(
+                        "cell {} is at ({}, {}) rather than ({}, {})",
+                        index, cell.point.x, cell.point.y, point.x, point.y
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        for cell in grid.cells.iter().flatten() {
+            let point = cell.point;
+
+            for link in grid.links(point) {
+                if grid.get(link).is_none() {
+                    return Err(invalid(format!(
+                        "({}, {}) has a passage to ({}, {}), which isn't a cell",
+                        point.x, point.y, link.x, link.y
+                    )));
+                }
+            }
+        }
+
+        // distances are measured from the entrance, as they were when the maze was carved.
+        if grid.distances.roots.is_empty() {
+            let start = grid.entrances.first().map(|entrance| entrance.cell);
+
+            if let Some(start) = start.or_else(|| grid.first_cell()) {
+                grid.distances = Distances::new(start);
+            }
+        }
+
+        return Ok(grid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_json() {
+        let grid = MazeBuilder::new()
+            .size(8, 6)
+            .seed(3)
+            .lava(3, 5)
+            .random_portals(1)
+            .entrances(EntranceType::Longest)
+            .locks(1)
+            .solve()
+            .build();
+
+        assert_eq!(RectangularGrid::from_json(&grid.to_json()).unwrap(), grid);
+    }
+
+    #[test]
+    fn rejects_mazes_that_dont_fit_their_size() {
+        let mut grid = MazeBuilder::new().size(4, 4).seed(3).build();
+        grid.width = 5;
+
+        assert!(matches!(
+            RectangularGrid::from_json(&grid.to_json()),
+            Err(MazeError::InvalidSave(_))
+        ));
+        assert!(matches!(
+            RectangularGrid::from_json("{\"width\": 2}"),
+            Err(MazeError::InvalidSave(_))
+        ));
+    }
+}
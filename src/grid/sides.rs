@@ -0,0 +1,202 @@
+use crate::prelude::*;
+
+// the bit for each side of a cell, numbered as the tiles of a tileset are.
+pub(crate) const OPEN_NORTH: u8 = 1;
+pub(crate) const OPEN_EAST: u8 = 2;
+pub(crate) const OPEN_SOUTH: u8 = 4;
+pub(crate) const OPEN_WEST: u8 = 8;
+
+// Sides : The passages of a rectangular grid between cells side by side, kept as a byte for
+// each cell with a bit set for every side it has a passage out through. A one-way passage
+// only sets the bit of the cell it leads out of.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Sides {
+    width: usize,
+    height: usize,
+    bits: Vec<u8>,
+}
+
+impl Sides {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: vec![0; width * height],
+        }
+    }
+
+    pub fn link(&mut self, a: Point, b: Point, bidi: bool) -> bool {
+        let (index, bit) = match self.side(a, b) {
+            Some(side) => side,
+            None => return false,
+        };
+
+        self.bits[index] |= bit;
+
+        if bidi {
+            self.link(b, a, false);
+        }
+
+        return true;
+    }
+
+    pub fn unlink(&mut self, a: Point, b: Point, bidi: bool) {
+        if let Some((index, bit)) = self.side(a, b) {
+            self.bits[index] &= !bit;
+        }
+
+        if bidi {
+            self.unlink(b, a, false);
+        }
+    }
+
+    // the cells `point` has passages out to, north, east, south and then west.
+    pub fn links(&self, point: Point) -> Vec<Point> {
+        let bits = self.bits(point);
+
+        return [
+            (point.north(), OPEN_NORTH),
+            (point.east(), OPEN_EAST),
+            (point.south(), OPEN_SOUTH),
+            (point.west(), OPEN_WEST),
+        ]
+        .into_iter()
+        .filter(|&(_, bit)| bits & bit != 0)
+        .map(|(neighbor, _)| neighbor)
+        .collect();
+    }
+
+    pub fn linked(&self, a: Point, b: Point) -> bool {
+        return self
+            .side(a, b)
+            .is_some_and(|(index, bit)| self.bits[index] & bit != 0);
+    }
+
+    // whether these are the sides of a `width` by `height` grid, a bit for each of its cells.
+    pub fn fits(&self, width: usize, height: usize) -> bool {
+        return (self.width, self.height) == (width, height) && self.bits.len() == width * height;
+    }
+
+    // the sides of the cell at `point` with passages out through them.
+    pub fn bits(&self, point: Point) -> u8 {
+        return self.index(point).map_or(0, |index| self.bits[index]);
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let inside = (0..self.width as i32).contains(&point.x)
+            && (0..self.height as i32).contains(&point.y)
+            && point.z == 0;
+
+        return inside.then(|| point.y as usize * self.width + point.x as usize);
+    }
+
+    // the index of `a` and the bit of its side facing `b`, when both are in the grid and
+    // side by side.
+    fn side(&self, a: Point, b: Point) -> Option<(usize, u8)> {
+        let bit = match (b.x - a.x, b.y - a.y) {
+            (0, -1) => OPEN_NORTH,
+            (1, 0) => OPEN_EAST,
+            (0, 1) => OPEN_SOUTH,
+            (-1, 0) => OPEN_WEST,
+            _ => return None,
+        };
+
+        self.index(b)?;
+
+        return Some((self.index(a)?, bit));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linking_sets_and_unlinking_clears_both_cells() {
+        let mut sides = Sides::new(3, 3);
+        let (a, b) = (Point::new(1, 1), Point::new(2, 1));
+
+        assert!(sides.link(a, b, true));
+        assert_eq!((sides.bits(a), sides.bits(b)), (OPEN_EAST, OPEN_WEST));
+        assert!(sides.linked(a, b) && sides.linked(b, a));
+
+        sides.unlink(a, b, true);
+
+        assert_eq!((sides.bits(a), sides.bits(b)), (0, 0));
+        assert!(!sides.linked(a, b) && !sides.linked(b, a));
+
+        // a one-way passage only sets the bit of the cell it leads out of.
+        sides.link(a, b, false);
+
+        assert_eq!((sides.bits(a), sides.bits(b)), (OPEN_EAST, 0));
+    }
+
+    #[test]
+    fn leaves_passages_out_of_the_grid_to_others() {
+        let mut sides = Sides::new(2, 2);
+        let corner = Point::new(0, 0);
+
+        assert!(!sides.link(corner, corner.north(), true));
+        assert!(!sides.link(corner, corner.west(), true));
+        assert!(!sides.link(corner, Point::new(1, 1), true));
+        assert_eq!(sides.bits(corner), 0);
+        assert_eq!(sides.bits(corner.north()), 0);
+        assert!(sides.links(corner).is_empty());
+
+        // nor does the grid keep them anywhere else.
+        let mut grid = MazeBuilder::new()
+            .size(2, 2)
+            .algorithm(Algorithm::None)
+            .build();
+        grid.link(corner, corner.north(), true);
+
+        assert!(grid.links(corner).is_empty());
+    }
+
+    #[test]
+    fn sets_the_bit_of_each_side() {
+        let center = Point::new(1, 1);
+
+        for (neighbor, bit) in [
+            (center.north(), OPEN_NORTH),
+            (center.east(), OPEN_EAST),
+            (center.south(), OPEN_SOUTH),
+            (center.west(), OPEN_WEST),
+        ] {
+            let mut sides = Sides::new(3, 3);
+            sides.link(center, neighbor, false);
+
+            assert_eq!(sides.bits(center), bit);
+            assert_eq!(sides.links(center), vec![neighbor]);
+        }
+    }
+
+    #[test]
+    fn matches_links_on_a_carved_maze() {
+        let grid = MazeBuilder::new().size(9, 7).seed(8).build();
+        let (mut sides, mut links) = (Sides::new(9, 7), Links::new());
+
+        for cell in grid.cells.iter().flatten() {
+            for link in grid.links(cell.point) {
+                sides.link(cell.point, link, false);
+                links.link(cell.point, link, false);
+            }
+        }
+
+        for cell in grid.cells.iter().flatten() {
+            let mut expected = links.links(cell.point);
+            expected.sort();
+            let mut found = sides.links(cell.point);
+            found.sort();
+
+            assert_eq!(found, expected);
+
+            for neighbor in grid.neighbors(cell.point) {
+                assert_eq!(
+                    sides.linked(cell.point, neighbor),
+                    links.linked(cell.point, neighbor)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use crate::prelude::*;
+
+type Vector = [i32; 3];
+
+// a face of the cube, placed in a cube `size` cells wide. Cells run along `u` and rows
+// along `v` from the `origin` corner, and `normal` points out of the cube.
+struct Face {
+    origin: Vector,
+    u: Vector,
+    v: Vector,
+    normal: Vector,
+    // where the face sits in the unfolded cross, in faces.
+    net: (i32, i32),
+}
+
+// the six faces unfold into a cross with the top face above the front face and the bottom
+// face below it.
+const FACES: [Face; 6] = [
+    // top
+    Face {
+        origin: [0, 1, 0],
+        u: [1, 0, 0],
+        v: [0, 0, 1],
+        normal: [0, 1, 0],
+        net: (1, 0),
+    },
+    // left
+    Face {
+        origin: [0, 1, 0],
+        u: [0, 0, 1],
+        v: [0, -1, 0],
+        normal: [-1, 0, 0],
+        net: (0, 1),
+    },
+    // front
+    Face {
+        origin: [0, 1, 1],
+        u: [1, 0, 0],
+        v: [0, -1, 0],
+        normal: [0, 0, 1],
+        net: (1, 1),
+    },
+    // right
+    Face {
+        origin: [1, 1, 1],
+        u: [0, 0, -1],
+        v: [0, -1, 0],
+        normal: [1, 0, 0],
+        net: (2, 1),
+    },
+    // back
+    Face {
+        origin: [1, 1, 0],
+        u: [-1, 0, 0],
+        v: [0, -1, 0],
+        normal: [0, 0, -1],
+        net: (3, 1),
+    },
+    // bottom
+    Face {
+        origin: [0, 0, 1],
+        u: [1, 0, 0],
+        v: [0, 0, -1],
+        normal: [0, -1, 0],
+        net: (1, 2),
+    },
+];
+
+fn scale(a: Vector, s: i32) -> Vector {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Vector, b: Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Vector, b: Vector) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+// SphereGrid : Six square faces folded into a cube, which covers a sphere with a maze that
+// has no boundary at all. Points use z for the face a cell is on, and cells on the edge of
+// a face neighbor the cells across the fold on the adjacent face.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SphereGrid {
+    pub size: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl SphereGrid {
+    fn new(size: usize) -> Self {
+        let mut cells = Vec::with_capacity(6 * size * size);
+
+        for face in 0..6 {
+            for y in 0..size {
+                for x in 0..size {
+                    cells.push(Some(Cell::new(Point::new_3d(x as i32, y as i32, face))));
+                }
+            }
+        }
+
+        Self {
+            size,
+            cells,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    // positions on the cube are doubled so that cell centers and edges land on integers.
+    fn center(&self, point: Point) -> Vector {
+        let face = &FACES[point.z as usize];
+        let origin = scale(face.origin, 2 * self.size as i32);
+
+        return add(
+            origin,
+            add(
+                scale(face.u, 2 * point.x + 1),
+                scale(face.v, 2 * point.y + 1),
+            ),
+        );
+    }
+
+    // the cell one step from `point` along `direction`, which is one of the face's axes.
+    fn step(&self, point: Point, direction: Vector) -> Option<Point> {
+        let face = &FACES[point.z as usize];
+        let center = self.center(point);
+        let next = add(center, scale(direction, 2));
+
+        if self.on_face(point.z, next) {
+            return Some(self.point_on(point.z, next));
+        }
+
+        // walk half a cell to the fold, then half a cell down the adjacent face.
+        let folded = sub(add(center, direction), face.normal);
+        let z = FACES.iter().position(|f| f.normal == direction)? as i32;
+
+        return Some(self.point_on(z, folded));
+    }
+
+    fn on_face(&self, z: i32, position: Vector) -> bool {
+        let face = &FACES[z as usize];
+        let relative = sub(position, scale(face.origin, 2 * self.size as i32));
+        let limit = 2 * self.size as i32;
+
+        return dot(relative, face.normal) == 0
+            && (0..limit).contains(&dot(relative, face.u))
+            && (0..limit).contains(&dot(relative, face.v));
+    }
+
+    fn point_on(&self, z: i32, position: Vector) -> Point {
+        let face = &FACES[z as usize];
+        let relative = sub(position, scale(face.origin, 2 * self.size as i32));
+
+        return Point::new_3d(
+            (dot(relative, face.u) - 1) / 2,
+            (dot(relative, face.v) - 1) / 2,
+            z,
+        );
+    }
+
+    // the neighbors to the north, east, south and west of a cell on its own face's axes.
+    fn sides(&self, point: Point) -> [Option<Point>; 4] {
+        let face = &FACES[point.z as usize];
+
+        [
+            self.step(point, scale(face.v, -1)),
+            self.step(point, face.u),
+            self.step(point, face.v),
+            self.step(point, scale(face.u, -1)),
+        ]
+    }
+}
+
+impl Grid for SphereGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.size
+    }
+
+    fn height(&self) -> usize {
+        self.size
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        if self.get(point).is_none() {
+            return Vec::new();
+        }
+
+        return self
+            .sides(point)
+            .into_iter()
+            .flatten()
+            .filter(|&p| self.get(p).is_some())
+            .collect();
+    }
+
+    fn point_to_index(&self, point: Point) -> Option<usize> {
+        let size = self.size as i32;
+
+        if !(0..size).contains(&point.x) || !(0..size).contains(&point.y) {
+            return None;
+        }
+
+        if !(0..6).contains(&point.z) {
+            return None;
+        }
+
+        return Some(((point.z * size + point.y) * size + point.x) as usize);
+    }
+}
+
+impl Drawable for SphereGrid {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let face_width = (self.size * cell_width) as i32;
+        let face_height = (self.size * cell_height) as i32;
+        let img_width = 4 * face_width + 1;
+        let img_height = 3 * face_height + 1;
+
+        let mut imgbuf =
+            image::ImageBuffer::from_fn(img_width as u32, img_height as u32, |_, _| {
+                return style.background;
+            });
+
+        for mode in ["background", "walls"] {
+            for cell in self.cells.iter().flatten() {
+                let point = cell.point;
+                let (net_x, net_y) = FACES[point.z as usize].net;
+                let (x1, x2, y1, y2) = (
+                    net_x * face_width + point.x * cell_width as i32,
+                    net_x * face_width + (point.x + 1) * cell_width as i32,
+                    net_y * face_height + point.y * cell_height as i32,
+                    net_y * face_height + (point.y + 1) * cell_height as i32,
+                );
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    SphereGrid::fill_polygon(
+                        &mut imgbuf,
+                        &[(x1, y1), (x2, y1), (x2, y2), (x1, y2)],
+                        color,
+                    );
+                    continue;
+                }
+
+                // every face draws all of its own walls, so edges along folds that are cut
+                // apart in the net still show whether the maze crosses them.
+                let [north, east, south, west] = self.sides(point);
+                let open = |side: Option<Point>| side.is_some_and(|s| self.linked(point, s));
+
+                if !open(north) {
+                    SphereGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y1,
+                        x2,
+                        y1,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !open(east) {
+                    SphereGrid::draw_line(
+                        &mut imgbuf,
+                        x2,
+                        y1,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !open(south) {
+                    SphereGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y2,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !open(west) {
+                    SphereGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y1,
+                        x1,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+            }
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Maskable for SphereGrid {
+    // faces are square, so the grid is as wide as the mask and the mask is applied to
+    // every face.
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = SphereGrid::new(mask.width);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        let face = self.size * self.size;
+
+        for z in 0..6 {
+            for (i, value) in mask.mask.iter().take(face).enumerate() {
+                if !value {
+                    self.cells[z * face + i] = None;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use rand::seq::SliceRandom;
+
+use crate::prelude::*;
+
+impl RectangularGrid {
+    // merges this grid with `other` placed at `offset`, as one grid wide and tall enough to
+    // hold both. Where the two overlap `other` wins. Up to `connectors` passages are carved at
+    // random between neighboring cells of the two grids, and any piece of this grid the
+    // overlap cuts off is joined back on, so the result is still a maze.
+    pub fn stitch(&self, other: &RectangularGrid, offset: Point, connectors: usize) -> Self {
+        // the merged grid starts at whichever grid is further up and to the left.
+        let shift = Point::new(-offset.x.min(0), -offset.y.min(0));
+        let other_shift = offset + shift;
+
+        let width = (shift.x + self.width as i32).max(other_shift.x + other.width as i32);
+        let height = (shift.y + self.height as i32).max(other_shift.y + other.height as i32);
+
+        let mut grid = RectangularGrid::new(width as usize, height as usize);
+        let mut owner = vec![None; grid.cells.len()];
+
+        for (source, (part, part_shift)) in [(self, shift), (other, other_shift)]
+            .into_iter()
+            .enumerate()
+        {
+            for cell in part.cells.iter().flatten() {
+                let index = grid.point_to_index(cell.point + part_shift).unwrap();
+                owner[index] = Some(source);
+            }
+        }
+
+        for (index, source) in owner.iter().enumerate() {
+            if source.is_none() {
+                grid.cells[index] = None;
+            }
+        }
+
+        // overlapping cells are taken from `other`, so only stamp this grid's passages
+        // between cells it still owns.
+        for cell in self.cells.iter().flatten() {
+            for link in self.links(cell.point) {
+                let (a, b) = (cell.point + shift, link + shift);
+                let owned = |p: Point| grid.point_to_index(p).and_then(|i| owner[i]) == Some(0);
+
+                if owned(a) && owned(b) {
+                    grid.link(a, b, false);
+                }
+            }
+        }
+
+        grid.stamp(other, other_shift);
+
+        // every pair of neighboring cells where one belongs to each grid could be joined.
+        let mut seams = Vec::new();
+
+        for cell in grid.cells.iter().flatten() {
+            let index = grid.point_to_index(cell.point).unwrap();
+
+            if owner[index] != Some(0) {
+                continue;
+            }
+
+            for neighbor in grid.neighbors(cell.point) {
+                if owner[grid.point_to_index(neighbor).unwrap()] == Some(1) {
+                    seams.push((cell.point, neighbor));
+                }
+            }
+        }
+
+        seams.shuffle(&mut random::rng());
+
+        for &(a, b) in seams.iter().take(connectors) {
+            grid.link(a, b, true);
+        }
+
+        if connectors > 0 {
+            grid.join_pieces();
+        }
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    // where `other` is laid over part of this grid, what's left of it can be cut into pieces
+    // that no passage leads to. Each piece is joined to the cells already reached by a
+    // passage across one of its sides, picked at random.
+    fn join_pieces(&mut self) {
+        let start = match self.first_cell() {
+            Some(start) => start,
+            None => return,
+        };
+        let mut reached = self.reachable(start, &[]);
+        let mut random = random::rng();
+
+        loop {
+            let sides = self
+                .cells
+                .iter()
+                .flatten()
+                .filter(|cell| !reached.contains(&cell.point))
+                .flat_map(|cell| {
+                    self.neighbors(cell.point)
+                        .into_iter()
+                        .filter(|neighbor| reached.contains(neighbor))
+                        .map(move |neighbor| (cell.point, neighbor))
+                })
+                .collect::<Vec<(Point, Point)>>();
+
+            let (a, b) = match sides.choose(&mut random) {
+                Some(&side) => side,
+                None => return,
+            };
+
+            self.link(a, b, true);
+            reached.extend(self.reachable(a, &[]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stitches_two_mazes_into_one() {
+        let left = MazeBuilder::new().size(4, 5).seed(1).build();
+        let right = MazeBuilder::new().size(3, 5).seed(2).build();
+        let grid = left.stitch(&right, Point::new(4, 0), 1);
+
+        assert_eq!((grid.width, grid.height), (7, 5));
+        assert_eq!(verify(&grid), Vec::new());
+
+        let seam = (0..5)
+            .filter(|&y| grid.linked(Point::new(3, y), Point::new(4, y)))
+            .count();
+
+        assert_eq!(seam, 1);
+    }
+}
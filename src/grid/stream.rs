@@ -0,0 +1,73 @@
+use std::io::{self, Write};
+
+use crate::prelude::*;
+
+// how many rows of cells are drawn at a time when streaming a maze.
+const STREAM_ROWS: usize = 64;
+
+impl RectangularGrid {
+    // carves a `width` by `height` maze with Eller's algorithm and writes its image to
+    // `writer` as a PNG a band of rows at a time, so neither the whole maze nor its image is
+    // ever held at once. The image is the one `to_grid_image` would draw of the same maze.
+    pub fn stream_png(
+        width: usize,
+        height: usize,
+        style: &Style,
+        writer: impl Write,
+    ) -> Result<(), MazeError> {
+        let image_width = (width * style.cell_width + 1) as u32;
+        let image_height = (height * style.cell_height + 1) as u32;
+
+        let mut encoder = png::Encoder::new(writer, image_width, image_height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut header = encoder.write_header().map_err(io::Error::from)?;
+        let mut stream = header.stream_writer().map_err(io::Error::from)?;
+
+        let mut ellers = Ellers::new(width, height);
+        let mut carved: Vec<CarvedRow> = Vec::new();
+        let mut drawn = 0;
+
+        for start in (0..height).step_by(STREAM_ROWS) {
+            let end = (start + STREAM_ROWS).min(height);
+            let carved_to = carved.last().map_or(0, |row| row.y + 1);
+
+            // the rows drawn, along with the row either side of them whose walls reach in.
+            carved.retain(|row| row.y + 1 >= start);
+            carved.extend(ellers.by_ref().take((end + 1).min(height) - carved_to));
+
+            let mut window = RectangularGrid::new(width, carved.len());
+
+            for (y, row) in carved.iter().enumerate() {
+                for x in 0..width {
+                    let point = Point::new(x as i32, y as i32);
+
+                    if row.east[x] {
+                        window.link(point, point.east(), true);
+                    }
+
+                    if row.south[x] {
+                        window.link(point, point.south(), true);
+                    }
+                }
+            }
+
+            // the bottom row of pixels is the wall along the bottom of the maze.
+            let top = (start - carved[0].y) * style.cell_height;
+            let rows = if end == height {
+                image_height - drawn
+            } else {
+                ((end - start) * style.cell_height) as u32
+            };
+
+            let image = window.to_window_image(style, 0, top as u32, image_width, rows);
+            stream.write_all(image.as_raw())?;
+            drawn += rows;
+        }
+
+        return stream
+            .finish()
+            .map_err(|e| MazeError::Io(io::Error::from(e)));
+    }
+}
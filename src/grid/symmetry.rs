@@ -0,0 +1,322 @@
+use rand::seq::SliceRandom;
+
+use crate::prelude::*;
+
+// Symmetry : How a symmetric maze is made of one carved part and its copies.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    // the west half mirrored onto the east half.
+    Horizontal,
+    // the north half mirrored onto the south half.
+    Vertical,
+    // the north half turned halfway around onto the south half.
+    TwoFold,
+    // one quarter turned onto each of the other three, for square mazes.
+    FourFold,
+}
+
+impl Symmetry {
+    // how many copies of the carved part make up the maze.
+    pub fn copies(&self) -> usize {
+        match self {
+            Symmetry::FourFold => 4,
+            _ => 2,
+        }
+    }
+
+    // where `point` lands in a `width` by `height` grid once mirrored, or turned on to the
+    // next copy around the middle.
+    fn image(&self, point: Point, width: usize, height: usize) -> Point {
+        let (right, bottom) = (width as i32 - 1, height as i32 - 1);
+
+        match self {
+            Symmetry::Horizontal => Point::new(right - point.x, point.y),
+            Symmetry::Vertical => Point::new(point.x, bottom - point.y),
+            Symmetry::TwoFold => Point::new(right - point.x, bottom - point.y),
+            Symmetry::FourFold => Point::new(right - point.y, point.x),
+        }
+    }
+
+    // whether `point` is in the part that's carved. A mirrored maze leaves out the middle
+    // column or row of a grid an odd number of cells across, and a turned one the middle
+    // cell.
+    fn carved(&self, point: Point, width: usize, height: usize) -> bool {
+        let (x, y) = (point.x as usize, point.y as usize);
+
+        match self {
+            Symmetry::Horizontal => x < width / 2,
+            Symmetry::Vertical => y < height / 2,
+            Symmetry::TwoFold => y * width + x < width * height / 2,
+            Symmetry::FourFold => x < width / 2 && y < width.div_ceil(2),
+        }
+    }
+}
+
+impl RectangularGrid {
+    // a maze over `mask` carved by `algorithm` in one part and copied onto the rest by
+    // `symmetry`. Four-fold symmetry needs a square mask, and other masks are carved as
+    // they are.
+    pub fn symmetric(mask: &Mask, symmetry: Symmetry, algorithm: &mut Algorithm) -> Self {
+        let (width, height) = (mask.width, mask.height);
+
+        match symmetry {
+            Symmetry::Horizontal | Symmetry::Vertical => {
+                return RectangularGrid::mirrored(mask, symmetry, algorithm);
+            }
+            Symmetry::FourFold if width != height => {
+                let mut grid = RectangularGrid::from_mask(mask);
+                algorithm.on(&mut grid);
+
+                return grid;
+            }
+            _ => (),
+        }
+
+        let mut grid = RectangularGrid::from_mask(mask);
+        carve_turned(
+            &mut grid,
+            symmetry.copies(),
+            &|point| symmetry.image(point, width, height),
+            &|point| symmetry.carved(point, width, height),
+            algorithm,
+        );
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    // one half mirrored onto the other. Cells whose mirror image the mask leaves out are
+    // left out too, so the two halves match. The halves are joined by a single passage
+    // across the axis, or through the middle when the grid is an odd number of cells
+    // across, which becomes a corridor along the axis.
+    fn mirrored(mask: &Mask, symmetry: Symmetry, algorithm: &mut Algorithm) -> Self {
+        let (width, height) = (mask.width, mask.height);
+        let mut matched = mask.clone();
+        let mut half_mask = mask.clone();
+
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let point = Point::new(x, y);
+                let enabled = mask.get(point) && mask.get(symmetry.image(point, width, height));
+
+                matched.set(point, enabled);
+                half_mask.set(point, enabled && symmetry.carved(point, width, height));
+            }
+        }
+
+        let mut half = RectangularGrid::from_mask(&half_mask);
+        algorithm.on(&mut half);
+
+        let mut grid = RectangularGrid::from_mask(&matched);
+        let image = |point: Point| symmetry.image(point, width, height);
+
+        grid.copy_links(&half, &|point| point);
+        grid.copy_links(&half, &image);
+
+        let mut random = random::rng();
+        let middle = match symmetry {
+            Symmetry::Vertical => height / 2,
+            _ => width / 2,
+        } as i32;
+        // the cell just before the axis, and the one beyond it, at `along` the axis.
+        let across = |along: i32| match symmetry {
+            Symmetry::Vertical => (Point::new(along, middle - 1), Point::new(along, middle)),
+            _ => (Point::new(middle - 1, along), Point::new(middle, along)),
+        };
+        let length = match symmetry {
+            Symmetry::Vertical => width,
+            _ => height,
+        } as i32;
+
+        let odd = match symmetry {
+            Symmetry::Vertical => height % 2 == 1,
+            _ => width % 2 == 1,
+        };
+
+        if !odd {
+            let seams = (0..length)
+                .map(across)
+                .filter(|&(a, b)| grid.get(a).is_some() && grid.get(b).is_some())
+                .collect::<Vec<(Point, Point)>>();
+
+            if let Some(&(a, b)) = seams.choose(&mut random) {
+                grid.link(a, b, true);
+            }
+
+            return grid;
+        }
+
+        // each unbroken run of middle cells becomes a corridor, joined to both halves at one
+        // cell picked at random.
+        let mut run: Vec<(Point, Point)> = Vec::new();
+
+        for along in 0..=length {
+            let (before, point) = across(along);
+
+            if along < length && grid.get(point).is_some() {
+                if let Some(&(_, last)) = run.last() {
+                    grid.link(last, point, true);
+                }
+
+                run.push((before, point));
+                continue;
+            }
+
+            let doors = run
+                .iter()
+                .filter(|(before, _)| grid.get(*before).is_some())
+                .copied()
+                .collect::<Vec<(Point, Point)>>();
+
+            if let Some(&(before, point)) = doors.choose(&mut random) {
+                grid.link(before, point, true);
+                grid.link(point, image(before), true);
+            }
+
+            run.clear();
+        }
+
+        return grid;
+    }
+}
+
+impl PolarGrid {
+    // a maze over `mask` carved by `algorithm` in one slice of the circle and turned onto
+    // the rest by `symmetry`. Mirrored symmetry, or a first ring that doesn't split evenly
+    // into the slices, carves the mask as it is.
+    pub fn symmetric(mask: &Mask, symmetry: Symmetry, algorithm: &mut Algorithm) -> Self {
+        let mut grid = PolarGrid::from_mask(mask);
+        let copies = symmetry.copies();
+        let turned = matches!(symmetry, Symmetry::TwoFold | Symmetry::FourFold);
+
+        if !turned || grid.rings.get(1).is_none_or(|ring| ring % copies != 0) {
+            algorithm.on(&mut grid);
+            return grid;
+        }
+
+        // each ring splits evenly too, as it's the first ring subdivided.
+        let rings = grid.rings.clone();
+        let slice = |point: Point| rings[point.y as usize] / copies;
+
+        carve_turned(
+            &mut grid,
+            copies,
+            &|point| {
+                let ring = rings[point.y as usize];
+                Point::new(
+                    (point.x as usize + slice(point)) as i32 % ring as i32,
+                    point.y,
+                )
+            },
+            &|point| point.y > 0 && (point.x as usize) < slice(point),
+            algorithm,
+        );
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+}
+
+// carves `grid` by `algorithm` in the cells `carved` picks out, and copies the passages onto
+// the rest by turning them with `turn` up to `copies - 1` times. Cells missing any of their
+// turned copies are left out so each copy matches. The copies are joined through a cell
+// that turns onto itself when there is one. Otherwise they're joined by a passage across
+// the seam and its copies, which leaves a loop around the middle unless the passage turns
+// onto itself.
+fn carve_turned<G: Grid + Clone>(
+    grid: &mut G,
+    copies: usize,
+    turn: &dyn Fn(Point) -> Point,
+    carved: &dyn Fn(Point) -> bool,
+    algorithm: &mut Algorithm,
+) {
+    let turns = |point: Point, times: usize| (0..times).fold(point, |point, _| turn(point));
+    let points = grid
+        .cells()
+        .iter()
+        .flatten()
+        .map(|cell| cell.point)
+        .collect::<Vec<Point>>();
+
+    let missing = points
+        .iter()
+        .copied()
+        .filter(|&point| (1..copies).any(|times| grid.get(turns(point, times)).is_none()))
+        .collect::<Vec<Point>>();
+
+    for point in missing {
+        if let Some(index) = grid.point_to_index(point) {
+            grid.cells_mut()[index] = None;
+        }
+    }
+
+    let mut part = grid.clone();
+
+    for point in points.iter().copied().filter(|&point| !carved(point)) {
+        if let Some(index) = part.point_to_index(point) {
+            part.cells_mut()[index] = None;
+        }
+    }
+
+    algorithm.on(&mut part);
+
+    for times in 0..copies {
+        grid.copy_links(&part, &|point| turns(point, times));
+    }
+
+    let mut random = random::rng();
+    let middle = points
+        .iter()
+        .copied()
+        .find(|&point| grid.get(point).is_some() && turn(point) == point);
+
+    let (from, to) = match middle {
+        Some(middle) => {
+            let spokes = grid
+                .neighbors(middle)
+                .into_iter()
+                .filter(|&neighbor| carved(neighbor))
+                .collect::<Vec<Point>>();
+
+            match spokes.choose(&mut random) {
+                Some(&spoke) => (middle, spoke),
+                None => return,
+            }
+        }
+        None => {
+            let seams = points
+                .iter()
+                .copied()
+                .filter(|&point| carved(point) && grid.get(point).is_some())
+                .flat_map(|point| {
+                    grid.neighbors(point)
+                        .into_iter()
+                        .filter(|&neighbor| !carved(neighbor))
+                        .map(move |neighbor| (point, neighbor))
+                })
+                .collect::<Vec<(Point, Point)>>();
+            let turning = seams
+                .iter()
+                .copied()
+                .filter(|&(a, b)| turn(a) == b)
+                .collect::<Vec<(Point, Point)>>();
+
+            let choices = if turning.is_empty() { seams } else { turning };
+
+            match choices.choose(&mut random) {
+                Some(&seam) => seam,
+                None => return,
+            }
+        }
+    };
+
+    for times in 0..copies {
+        grid.link(turns(from, times), turns(to, times), true);
+    }
+}
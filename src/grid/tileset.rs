@@ -0,0 +1,87 @@
+use crate::prelude::*;
+
+// how many tiles across a tileset `width` by `height` pixels is laid out, either a single row
+// of 16 or a 4 by 4 sheet.
+pub fn tileset_columns(width: u32, height: u32) -> Result<u32, String> {
+    if width == 16 * height {
+        return Ok(16);
+    } else if width == height && width.is_multiple_of(4) {
+        return Ok(4);
+    }
+
+    return Err(format!(
+        "a {}x{} tileset is neither a row of 16 square tiles nor a 4 by 4 sheet",
+        width, height
+    ));
+}
+
+impl RectangularGrid {
+    // which sides of the cell at `point` have passages, or entrances, through them.
+    pub fn openings(&self, point: Point) -> u8 {
+        let sides = [
+            (point.north(), OPEN_NORTH),
+            (point.east(), OPEN_EAST),
+            (point.south(), OPEN_SOUTH),
+            (point.west(), OPEN_WEST),
+        ];
+
+        return sides
+            .into_iter()
+            .filter(|&(neighbor, _)| self.open(point, neighbor))
+            .fold(0, |openings, (_, bit)| openings | bit);
+    }
+
+    // the maze drawn with the 16 tiles of `tileset`, each cell taking the tile numbered by its
+    // openings. The tiles are read left to right and top to bottom, from either a single row
+    // of 16 or a 4 by 4 sheet. Masked out cells are left black.
+    pub fn to_tiled_image(&self, tileset: &RgbImage) -> Result<RgbImage, String> {
+        let (width, height) = tileset.dimensions();
+        let columns = tileset_columns(width, height)?;
+        let tile = width / columns;
+        let mut imgbuf = RgbImage::new(self.width as u32 * tile, self.height as u32 * tile);
+
+        for cell in self.cells.iter().flatten() {
+            let index = self.openings(cell.point) as u32;
+            let (tile_x, tile_y) = (index % columns * tile, index / columns * tile);
+            let (x, y) = (cell.point.x as u32 * tile, cell.point.y as u32 * tile);
+
+            for dy in 0..tile {
+                for dx in 0..tile {
+                    let pixel = *tileset.get_pixel(tile_x + dx, tile_y + dy);
+                    imgbuf.put_pixel(x + dx, y + dy, pixel);
+                }
+            }
+        }
+
+        return Ok(imgbuf);
+    }
+
+    // a row of 16 tiles drawn in `style`, numbered the way `to_tiled_image` reads them, with
+    // walls along the sides of each tile that aren't open.
+    pub fn tileset(style: &Style) -> RgbImage {
+        let (width, height) = (style.cell_width as u32, style.cell_height as u32);
+        let mut imgbuf = RgbImage::new(16 * width, height);
+
+        for index in 0..16u8 {
+            let mut tile = RgbImage::from_pixel(width, height, style.background);
+            let (right, bottom) = (width as i32 - 1, height as i32 - 1);
+            let sides = [
+                (OPEN_NORTH, (0, 0, right, 0)),
+                (OPEN_EAST, (right, 0, right, bottom)),
+                (OPEN_SOUTH, (0, bottom, right, bottom)),
+                (OPEN_WEST, (0, 0, 0, bottom)),
+            ];
+
+            for (bit, (x0, y0, x1, y1)) in sides {
+                if index & bit == 0 {
+                    let thickness = style.wall_thickness;
+                    Self::draw_line(&mut tile, x0, y0, x1, y1, thickness, style.wall_color);
+                }
+            }
+
+            imgbuf.copy_from(&tile, index as u32 * width, 0).unwrap();
+        }
+
+        return imgbuf;
+    }
+}
@@ -0,0 +1,64 @@
+use crate::prelude::*;
+
+fn escape(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+}
+
+impl RectangularGrid {
+    // the maze as a Tiled map with a single layer of walls. Each cell is given the tile of the
+    // tileset image at `source` numbered by its openings, the same tile `to_tiled_image` draws
+    // it with, and masked out cells are left empty. `size` is the tileset's size in pixels.
+    pub fn to_tmx(&self, source: &str, size: (u32, u32)) -> Result<String, String> {
+        let (width, height) = size;
+        let columns = tileset_columns(width, height)?;
+        let tile = width / columns;
+
+        // Tiled numbers the tiles of a map's first tileset from 1, leaving 0 for no tile.
+        let rows = self
+            .cells
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Some(cell) => (self.openings(cell.point) + 1).to_string(),
+                        None => String::from("0"),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(",")
+            })
+            .collect::<Vec<String>>()
+            .join(",\n");
+
+        return Ok(format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" \
+             width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" infinite=\"0\" \
+             nextlayerid=\"2\" nextobjectid=\"1\">\n \
+             <tileset firstgid=\"1\" name=\"walls\" tilewidth=\"{}\" tileheight=\"{}\" \
+             tilecount=\"16\" columns=\"{}\">\n  \
+             <image source=\"{}\" width=\"{}\" height=\"{}\"/>\n \
+             </tileset>\n \
+             <layer id=\"1\" name=\"walls\" width=\"{}\" height=\"{}\">\n  \
+             <data encoding=\"csv\">\n{}\n</data>\n \
+             </layer>\n\
+             </map>\n",
+            self.width,
+            self.height,
+            tile,
+            tile,
+            tile,
+            tile,
+            columns,
+            escape(source),
+            width,
+            height,
+            self.width,
+            self.height,
+            rows
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::prelude::*;
+
+// TriangleGrid : A delta maze of alternating upward and downward pointing triangles. Every
+// cell touches its east and west neighbors, upward triangles also touch the cell below
+// their base and downward triangles the cell above theirs.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TriangleGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl TriangleGrid {
+    fn new(width: usize, height: usize) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(Some(Cell::new(Point::new(x as i32, y as i32))));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    pub fn upright(point: Point) -> bool {
+        return (point.x + point.y) % 2 == 0;
+    }
+}
+
+impl Grid for TriangleGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        let base = if TriangleGrid::upright(point) {
+            point.south()
+        } else {
+            point.north()
+        };
+
+        return [point.west(), point.east(), base]
+            .into_iter()
+            .filter(|&p| self.get(p).is_some())
+            .collect();
+    }
+}
+
+impl Drawable for TriangleGrid {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let size = cell_width as f32;
+        let half_width = size / 2.0;
+        let height = cell_height as f32 * 3f32.sqrt() / 2.0;
+        let half_height = height / 2.0;
+
+        let img_width = (size * (self.width + 1) as f32 / 2.0).ceil() as u32 + 1;
+        let img_height = (height * self.height as f32).ceil() as u32 + 1;
+
+        let mut imgbuf = image::ImageBuffer::from_fn(img_width, img_height, |_, _| {
+            return style.background;
+        });
+
+        for mode in ["background", "walls"] {
+            for cell in self.cells.iter().flatten() {
+                let point = cell.point;
+                let upright = TriangleGrid::upright(point);
+
+                let cx = half_width + point.x as f32 * half_width;
+                let cy = half_height + point.y as f32 * height;
+
+                let west_x = (cx - half_width).round() as i32;
+                let mid_x = cx.round() as i32;
+                let east_x = (cx + half_width).round() as i32;
+
+                let (apex_y, base_y) = if upright {
+                    (cy - half_height, cy + half_height)
+                } else {
+                    (cy + half_height, cy - half_height)
+                };
+                let (apex_y, base_y) = (apex_y.round() as i32, base_y.round() as i32);
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    let corners = [(west_x, base_y), (mid_x, apex_y), (east_x, base_y)];
+                    TriangleGrid::fill_polygon(&mut imgbuf, &corners, color);
+                    continue;
+                }
+
+                if self.get(point.west()).is_none() {
+                    TriangleGrid::draw_line(
+                        &mut imgbuf,
+                        west_x,
+                        base_y,
+                        mid_x,
+                        apex_y,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                if !self.linked(point, point.east()) {
+                    TriangleGrid::draw_line(
+                        &mut imgbuf,
+                        east_x,
+                        base_y,
+                        mid_x,
+                        apex_y,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                // the horizontal edge between two rows belongs to the downward triangle
+                // above it, unless there is no such triangle to draw it.
+                let no_south = upright && self.get(point.south()).is_none();
+                let not_linked = !upright && !self.linked(point, point.north());
+
+                if no_south || not_linked {
+                    TriangleGrid::draw_line(
+                        &mut imgbuf,
+                        east_x,
+                        base_y,
+                        west_x,
+                        base_y,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+            }
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Maskable for TriangleGrid {
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = TriangleGrid::new(mask.width, mask.height);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        for (i, value) in mask.mask.iter().enumerate() {
+            if !value {
+                self.cells[i] = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::prelude::*;
+
+// UpsilonGrid : A checkerboard of octagons and squares. Octagons touch their eight
+// surrounding cells, the squares sitting in the gaps between them only touch the four
+// octagons to their north, east, south and west.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UpsilonGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub links: Links,
+    pub distances: Distances,
+    #[serde(skip)]
+    live: LiveCells,
+}
+
+impl UpsilonGrid {
+    fn new(width: usize, height: usize) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(Some(Cell::new(Point::new(x as i32, y as i32))));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    pub fn octagon(point: Point) -> bool {
+        return (point.x + point.y) % 2 == 0;
+    }
+
+    fn diagonals(point: Point) -> [Point; 4] {
+        [
+            Point::new(point.x + 1, point.y - 1),
+            Point::new(point.x + 1, point.y + 1),
+            Point::new(point.x - 1, point.y + 1),
+            Point::new(point.x - 1, point.y - 1),
+        ]
+    }
+}
+
+impl Grid for UpsilonGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        let mut neighbors = vec![point.north(), point.east(), point.south(), point.west()];
+
+        if UpsilonGrid::octagon(point) {
+            neighbors.extend(UpsilonGrid::diagonals(point));
+        }
+
+        return neighbors
+            .into_iter()
+            .filter(|&p| self.get(p).is_some())
+            .collect();
+    }
+}
+
+impl Drawable for UpsilonGrid {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let (size_x, size_y) = (cell_width as f32, cell_height as f32);
+
+        // octagons meet their diagonal neighbors edge to edge, which fixes their apothem
+        // and leaves exactly enough room between them for the squares. Both are measured
+        // in cells here and scaled to pixels per axis.
+        let radius = 1.0 / 2f32.sqrt();
+        let half_side = 1.0 - radius;
+
+        let img_width = (((self.width - 1) as f32 + 2.0 * radius) * size_x).ceil() as u32 + 1;
+        let img_height = (((self.height - 1) as f32 + 2.0 * radius) * size_y).ceil() as u32 + 1;
+
+        let mut imgbuf = image::ImageBuffer::from_fn(img_width, img_height, |_, _| {
+            return style.background;
+        });
+
+        for mode in ["background", "walls"] {
+            for cell in self.cells.iter().flatten() {
+                let point = cell.point;
+                let cx = radius + point.x as f32;
+                let cy = radius + point.y as f32;
+                let at = |dx: f32, dy: f32| {
+                    (
+                        ((cx + dx) * size_x).round() as i32,
+                        ((cy + dy) * size_y).round() as i32,
+                    )
+                };
+
+                // each side is listed with the neighbor on the other side of it, going
+                // clockwise from the north.
+                let (r, h) = (radius, half_side);
+                let sides = if UpsilonGrid::octagon(point) {
+                    let [northeast, southeast, southwest, northwest] =
+                        UpsilonGrid::diagonals(point);
+
+                    vec![
+                        (at(-h, -r), at(h, -r), point.north(), true),
+                        (at(h, -r), at(r, -h), northeast, true),
+                        (at(r, -h), at(r, h), point.east(), true),
+                        (at(r, h), at(h, r), southeast, true),
+                        (at(h, r), at(-h, r), point.south(), true),
+                        (at(-h, r), at(-r, h), southwest, false),
+                        (at(-r, h), at(-r, -h), point.west(), true),
+                        (at(-r, -h), at(-h, -r), northwest, false),
+                    ]
+                } else {
+                    vec![
+                        (at(-h, -h), at(h, -h), point.north(), false),
+                        (at(h, -h), at(h, h), point.east(), false),
+                        (at(h, h), at(-h, h), point.south(), false),
+                        (at(-h, h), at(-h, -h), point.west(), false),
+                    ]
+                };
+
+                if mode == "background" {
+                    let color =
+                        self.background_color_for(cell, &self.distances, max_distance, style);
+                    let corners = sides.iter().map(|side| side.0).collect::<Vec<_>>();
+                    UpsilonGrid::fill_polygon(&mut imgbuf, &corners, color);
+                    continue;
+                }
+
+                // a wall shared by two cells is drawn by the one that owns the side, any
+                // side facing the boundary or a masked out cell is always drawn.
+                for ((x1, y1), (x2, y2), neighbor, owned) in sides {
+                    let missing = self.get(neighbor).is_none();
+
+                    if missing || (owned && !self.linked(point, neighbor)) {
+                        UpsilonGrid::draw_line(
+                            &mut imgbuf,
+                            x1,
+                            y1,
+                            x2,
+                            y2,
+                            style.wall_thickness,
+                            style.wall_color,
+                        );
+                    }
+                }
+            }
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Maskable for UpsilonGrid {
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = UpsilonGrid::new(mask.width, mask.height);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        for (i, value) in mask.mask.iter().enumerate() {
+            if !value {
+                self.cells[i] = None;
+            }
+        }
+    }
+}
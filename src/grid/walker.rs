@@ -0,0 +1,143 @@
+use crate::prelude::*;
+
+// how the player and the cell they're headed for are drawn.
+const PLAYER: char = '@';
+const GOAL: char = 'X';
+
+/// Walker : A player finding their way through a rectangular maze, from its entrance to its
+/// exit, one step at a time. Steps can only be taken through passages, and every cell walked
+/// through is left on the trail behind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walker {
+    pub grid: RectangularGrid,
+    pub position: Point,
+    pub goal: Point,
+    // the steps taken so far, walking back over the trail counts too.
+    pub moves: usize,
+    pub trail: Vec<Point>,
+}
+
+impl Walker {
+    // starts at the maze's entrance, or its first cell, headed for the exit or last cell.
+    // A maze with no cells at all has nowhere to walk.
+    pub fn new(grid: RectangularGrid) -> Option<Self> {
+        let (start, goal) = grid.ends()?;
+
+        return Some(Self {
+            grid,
+            position: start,
+            goal,
+            moves: 0,
+            trail: vec![start],
+        });
+    }
+
+    // moves one cell in `direction` if there's a passage that way, returning whether it did.
+    pub fn step(&mut self, direction: Direction) -> bool {
+        let next = self.position + Point::in_direction(direction);
+
+        if self.finished() || !self.grid.linked(self.position, next) {
+            return false;
+        }
+
+        self.position = next;
+        self.moves += 1;
+
+        if !self.trail.contains(&next) {
+            self.trail.push(next);
+        }
+
+        return true;
+    }
+
+    pub fn finished(&self) -> bool {
+        return self.position == self.goal;
+    }
+
+    // the fewest steps the walk could have been finished in.
+    pub fn shortest(&self) -> usize {
+        let mut grid = self.grid.clone();
+
+        return grid.solve().len().saturating_sub(1);
+    }
+
+    // the maze in box drawing characters with the trail marked as a solution is, the player
+    // drawn over it and the goal shown until it's been reached.
+    pub fn to_unicode(&self) -> String {
+        let mut grid = self.grid.clone();
+        grid.path = self.trail.clone();
+
+        let mut lines = grid
+            .to_unicode(None)
+            .lines()
+            .map(|line| line.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+
+        // each row of cells is every other line, and each cell is four characters wide with
+        // its middle character free to mark.
+        let mut mark = |point: Point, c: char| {
+            let (x, y) = (point.x as usize * 4 + 2, point.y as usize * 2 + 1);
+
+            if let Some(spot) = lines.get_mut(y).and_then(|line| line.get_mut(x)) {
+                *spot = c;
+            }
+        };
+
+        mark(self.goal, GOAL);
+        mark(self.position, PLAYER);
+
+        return lines
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>() + "\n")
+            .collect();
+    }
+
+    // the maze drawn as an image in `style`, with the trail drawn as a solution is and the
+    // player and goal marked in their cells. With `fog`, only the cells within that many
+    // cells of the player, and those already walked through, can be seen.
+    pub fn to_image(&self, style: &Style, fog: Option<usize>) -> RgbImage {
+        let mut grid = self.grid.clone();
+        grid.path = self.trail.clone();
+
+        let mut image = grid.to_grid_image(style);
+        let (cell_width, cell_height) = (style.cell_width as i32, style.cell_height as i32);
+        let corners = |point: Point, inset: i32| {
+            let (x1, y1) = (point.x * cell_width + inset, point.y * cell_height + inset);
+            let (x2, y2) = (
+                (point.x + 1) * cell_width - inset,
+                (point.y + 1) * cell_height - inset,
+            );
+
+            return [(x1, y1), (x2, y1), (x2, y2), (x1, y2)];
+        };
+
+        let hidden = |point: Point| {
+            let (dx, dy) = (point.x - self.position.x, point.y - self.position.y);
+
+            return fog.is_some_and(|radius| {
+                let radius = radius as i32;
+                dx * dx + dy * dy > radius * radius && !self.trail.contains(&point)
+            });
+        };
+
+        for cell in grid
+            .cells
+            .iter()
+            .flatten()
+            .filter(|cell| hidden(cell.point))
+        {
+            RectangularGrid::fill_polygon(&mut image, &corners(cell.point, 0), FOG);
+        }
+
+        // the markers sit a quarter of a cell in from its walls.
+        let inset = cell_width.min(cell_height) / 4;
+
+        if !hidden(self.goal) {
+            RectangularGrid::fill_polygon(&mut image, &corners(self.goal, inset), GRADIENT);
+        }
+
+        RectangularGrid::fill_polygon(&mut image, &corners(self.position, inset), MARK);
+
+        return image;
+    }
+}
@@ -0,0 +1,452 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+const UNDER: i32 = 1;
+
+// WeaveGrid : A rectangular grid whose passages may tunnel beneath a neighboring cell. A
+// cell counts the cell two steps away as a neighbor whenever the cell in between is a
+// straight passage running across the tunnel. Linking to such a neighbor inserts an
+// "under" cell on the layer below the crossed cell, so algorithms never deal with
+// tunnels themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeaveGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Option<Cell>>,
+    pub under_cells: HashMap<Point, Cell>,
+    pub links: Links,
+    pub distances: Distances,
+    live: LiveCells,
+}
+
+impl WeaveGrid {
+    fn new(width: usize, height: usize) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(Some(Cell::new(Point::new(x as i32, y as i32))));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            under_cells: HashMap::new(),
+            links: Links::new(),
+            live: LiveCells::new(),
+            distances: Distances::new(Point::new(0, 0)),
+        }
+    }
+
+    pub fn under(point: Point) -> Point {
+        Point::new_3d(point.x, point.y, UNDER)
+    }
+
+    fn horizontal_passage(&self, point: Point) -> bool {
+        return self.linked(point, point.east())
+            && self.linked(point, point.west())
+            && !self.linked(point, point.north())
+            && !self.linked(point, point.south());
+    }
+
+    fn vertical_passage(&self, point: Point) -> bool {
+        return self.linked(point, point.north())
+            && self.linked(point, point.south())
+            && !self.linked(point, point.east())
+            && !self.linked(point, point.west());
+    }
+
+    // the cell passed under when moving from `a` to `b`, if the two are a tunnel apart.
+    fn crossed(a: Point, b: Point) -> Option<Point> {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+
+        if a.z != 0 || b.z != 0 || !matches!((dx.abs(), dy.abs()), (2, 0) | (0, 2)) {
+            return None;
+        }
+
+        return Some(Point::new(a.x + dx / 2, a.y + dy / 2));
+    }
+
+    fn can_tunnel(&self, from: Point, to: Point) -> bool {
+        let middle = match WeaveGrid::crossed(from, to) {
+            Some(middle) => middle,
+            None => return false,
+        };
+
+        if self.get(middle).is_none()
+            || self.get(to).is_none()
+            || self.under_cells.contains_key(&WeaveGrid::under(middle))
+        {
+            return false;
+        }
+
+        if from.x == to.x {
+            return self.horizontal_passage(middle);
+        }
+
+        return self.vertical_passage(middle);
+    }
+
+    // whether a cell's passage leaves it towards `neighbor`, either directly or through
+    // the under cell beneath it.
+    fn opens_towards(&self, point: Point, neighbor: Point) -> bool {
+        return self.linked(point, neighbor) || self.linked(point, WeaveGrid::under(neighbor));
+    }
+}
+
+impl Grid for WeaveGrid {
+    fn cells(&self) -> &Vec<Option<Cell>> {
+        self.cells.as_ref()
+    }
+
+    fn cells_mut(&mut self) -> &mut Vec<Option<Cell>> {
+        self.live.clear();
+        self.cells.as_mut()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    fn passages(&self) -> &Links {
+        &self.links
+    }
+
+    fn passages_mut(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    fn live_cells(&self) -> &LiveCells {
+        &self.live
+    }
+
+    fn get(&self, point: Point) -> Option<&Cell> {
+        if point.z == UNDER {
+            return self.under_cells.get(&point);
+        }
+
+        let index = self.point_to_index(point)?;
+
+        return self.cells[index].as_ref();
+    }
+
+    fn link(&mut self, a: Point, b: Point, bidi: bool) {
+        if self.get(a).is_none() || self.get(b).is_none() {
+            return;
+        }
+
+        if let Some(middle) = WeaveGrid::crossed(a, b) {
+            let under = WeaveGrid::under(middle);
+
+            self.under_cells.insert(under, Cell::new(under));
+            self.links.link(a, under, bidi);
+            self.links.link(under, b, bidi);
+            return;
+        }
+
+        self.links.link(a, b, bidi);
+    }
+
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        if point.z == UNDER {
+            return Vec::new();
+        }
+
+        let mut neighbors = Vec::new();
+
+        for step in [point.north(), point.south(), point.east(), point.west()] {
+            if self.get(step).is_some() {
+                neighbors.push(step);
+            }
+
+            let tunnel = step + (step - point);
+
+            if self.can_tunnel(point, tunnel) {
+                neighbors.push(tunnel);
+            }
+        }
+
+        return neighbors;
+    }
+}
+
+impl Drawable for WeaveGrid {
+    fn to_grid_image(&self, style: &Style) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+        let (cell_width, cell_height) = (style.cell_width, style.cell_height);
+        let (max_distance, _) = self.distances.max(self);
+        let img_width = self.width * cell_width + 1;
+        let img_height = self.height * cell_height + 1;
+
+        // passages are drawn narrower than their cells, which leaves the gap a tunnel needs
+        // to visibly pass beneath the cell above it.
+        let inset = (cell_width.min(cell_height) / 6).max(1) as i32;
+
+        let mut imgbuf =
+            image::ImageBuffer::from_fn(img_width as u32, img_height as u32, |_, _| {
+                return style.background;
+            });
+
+        let cells = self.cells.iter().flatten().chain(self.under_cells.values());
+
+        for cell in cells {
+            let point = cell.point;
+            let (x1, x4) = (
+                point.x * cell_width as i32,
+                (point.x + 1) * cell_width as i32,
+            );
+            let (y1, y4) = (
+                point.y * cell_height as i32,
+                (point.y + 1) * cell_height as i32,
+            );
+            let (x2, x3) = (x1 + inset, x4 - inset);
+            let (y2, y3) = (y1 + inset, y4 - inset);
+
+            if point.z == UNDER {
+                if self.linked(point, Point::new(point.x, point.y - 1)) {
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x2,
+                        y1,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x3,
+                        y1,
+                        x3,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x2,
+                        y3,
+                        x2,
+                        y4,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x3,
+                        y3,
+                        x3,
+                        y4,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                } else {
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y2,
+                        x2,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x1,
+                        y3,
+                        x2,
+                        y3,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x3,
+                        y2,
+                        x4,
+                        y2,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                    WeaveGrid::draw_line(
+                        &mut imgbuf,
+                        x3,
+                        y3,
+                        x4,
+                        y3,
+                        style.wall_thickness,
+                        style.wall_color,
+                    );
+                }
+
+                continue;
+            }
+
+            let color = self.background_color_for(cell, &self.distances, max_distance, style);
+            WeaveGrid::fill_polygon(
+                &mut imgbuf,
+                &[(x2, y2), (x3, y2), (x3, y3), (x2, y3)],
+                color,
+            );
+
+            if self.opens_towards(point, point.north()) {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x2,
+                    y1,
+                    x2,
+                    y2,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x3,
+                    y1,
+                    x3,
+                    y2,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            } else {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x2,
+                    y2,
+                    x3,
+                    y2,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            }
+
+            if self.opens_towards(point, point.south()) {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x2,
+                    y3,
+                    x2,
+                    y4,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x3,
+                    y3,
+                    x3,
+                    y4,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            } else {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x2,
+                    y3,
+                    x3,
+                    y3,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            }
+
+            if self.opens_towards(point, point.west()) {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x1,
+                    y2,
+                    x2,
+                    y2,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x1,
+                    y3,
+                    x2,
+                    y3,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            } else {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x2,
+                    y2,
+                    x2,
+                    y3,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            }
+
+            if self.opens_towards(point, point.east()) {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x3,
+                    y2,
+                    x4,
+                    y2,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x3,
+                    y3,
+                    x4,
+                    y3,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            } else {
+                WeaveGrid::draw_line(
+                    &mut imgbuf,
+                    x3,
+                    y2,
+                    x3,
+                    y3,
+                    style.wall_thickness,
+                    style.wall_color,
+                );
+            }
+        }
+
+        return imgbuf;
+    }
+}
+
+impl Maskable for WeaveGrid {
+    fn from_mask(mask: &Mask) -> Self {
+        let mut grid = WeaveGrid::new(mask.width, mask.height);
+        grid.mask(mask);
+
+        if let Some(start) = grid.first_cell() {
+            grid.distances = Distances::new(start);
+        }
+
+        return grid;
+    }
+
+    fn mask(&mut self, mask: &Mask) {
+        self.live.clear();
+
+        for (i, value) in mask.mask.iter().enumerate() {
+            if !value {
+                self.cells[i] = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use std::io::{self, Read};
+
+// the contents of the file at `path`, or of stdin when `path` is -.
+pub fn read_input(path: &str) -> io::Result<Vec<u8>> {
+    if path == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        return Ok(bytes);
+    }
+
+    // the error names the file, since it's shown as it is.
+    return std::fs::read(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)));
+}
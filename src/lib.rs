@@ -0,0 +1,129 @@
+#![allow(clippy::needless_return)]
+
+//! Generates mazes on grids of many shapes and draws them as images, text and models.
+//!
+//! The quickest way to a maze is a [`MazeBuilder`], which carves a rectangular maze from a
+//! handful of options.
+//!
+//! ```
+//! use rusty_mazes::{Algorithm, MazeBuilder};
+//!
+//! let grid = MazeBuilder::new()
+//!     .size(20, 10)
+//!     .algorithm(Algorithm::HuntAndKill)
+//!     .seed(5)
+//!     .build();
+//!
+//! println!("{}", grid.to_ascii(None));
+//! ```
+//!
+//! Underneath, a maze starts as a [`Mask`] of the cells it may use, which any grid can be made
+//! from with [`Maskable::from_mask`]. An [`Algorithm`] then carves passages between the cells
+//! of the [`Grid`], and [`Distances`] measures how far every cell is from a starting point.
+//! Grids that are [`Drawable`] can be rendered to images and SVG in a [`Style`].
+//!
+//! ```
+//! use rusty_mazes::{Algorithm, Mask, Maskable, PolarGrid};
+//!
+//! rusty_mazes::random::seed(5);
+//!
+//! let mut grid = PolarGrid::from_mask(&Mask::new(8, 8));
+//! Algorithm::Wilsons.on(&mut grid);
+//! ```
+//!
+//! Everything the crate offers, its colors and constants too, can be imported at once from
+//! the [`prelude`].
+
+// Texture : A Mazes texture represents the tendency that it has to create
+// a certain type of a maze. For example, a binary tree maze texture will
+// create a maze that has long corridors top-left to top-right (east to west in the north)
+// and bottom-right to top-right. (south to north in the east) as it has
+// to move north or east.
+// Bias : A tendency towards a texture.
+
+mod algorithms;
+mod analysis;
+mod animation;
+#[cfg(feature = "bevy")]
+mod bevy;
+mod builder;
+mod cell;
+mod connectivity;
+mod distances;
+mod drawable;
+mod error;
+mod grid;
+mod input;
+mod mask;
+mod mask_builder;
+mod mesh;
+mod nbt;
+mod pages;
+mod pairs;
+mod pdf;
+mod point;
+pub mod progress;
+pub mod random;
+pub mod server;
+mod svg;
+
+pub use prelude::{
+    Algorithm, Distances, Drawable, Grid, Mask, MaskBuilder, Maskable, MazeBuilder, MazeError,
+    PolarGrid, RectangularGrid, Style,
+};
+
+pub mod prelude {
+    pub use crate::algorithms::*;
+    pub use crate::analysis::*;
+    pub use crate::animation::*;
+    #[cfg(feature = "bevy")]
+    pub use crate::bevy::*;
+    pub use crate::builder::*;
+    pub use crate::cell::*;
+    pub use crate::connectivity::*;
+    pub use crate::distances::*;
+    pub use crate::drawable::*;
+    pub use crate::error::*;
+    pub use crate::grid::*;
+    pub use crate::input::*;
+    pub use crate::mask::*;
+    pub use crate::mask_builder::*;
+    pub use crate::mesh::*;
+    pub use crate::pages::*;
+    pub use crate::pdf::*;
+    pub use crate::point::*;
+    pub use crate::progress;
+    pub use crate::random;
+    pub use crate::server;
+
+    pub use image::*;
+    pub use rand::Rng;
+    pub use std::path::Path;
+
+    pub const GRID_WIDTH: usize = 8;
+    pub const GRID_HEIGHT: usize = 8;
+    pub const GRID_DEPTH: usize = 3;
+    pub const WHITE: Rgb<u8> = image::Rgb([255u8, 255u8, 255u8]);
+    pub const BLACK: Rgb<u8> = image::Rgb([0u8, 0u8, 0u8]);
+    pub const HEAVY: Rgb<u8> = image::Rgb([160u8, 40u8, 20u8]);
+    pub const PATH: Rgb<u8> = image::Rgb([60u8, 140u8, 255u8]);
+    pub const GRADIENT: Rgb<u8> = image::Rgb([0u8, 128u8, 0u8]);
+    pub const MARK: Rgb<u8> = image::Rgb([230u8, 0u8, 140u8]);
+    pub const FOG: Rgb<u8> = image::Rgb([48u8, 48u8, 48u8]);
+
+    #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Theme {
+        // white walls on black.
+        Dark,
+        // black walls on white.
+        Light,
+        // pale blue walls on a deep blue.
+        Blueprint,
+    }
+
+    #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum PageSize {
+        A4,
+        Letter,
+    }
+}
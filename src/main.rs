@@ -1,61 +1,383 @@
-// Texture : A Mazes texture represents the tendency that it has to create
-// a certain type of a maze. For example, a binary tree maze texture will
-// create a maze that has long corridors top-left to top-right (east to west in the north)
-// and bottom-right to top-right. (south to north in the east) as it has
-// to move north or east.
-// Bias : A tendency towards a texture.
-
-mod algorithms;
-mod distances;
-mod drawable;
-mod grid;
-mod cell;
-mod mask;
-mod point;
+#![allow(clippy::needless_return)]
 
 mod prelude {
-    pub use crate::algorithms::*;
-    pub use crate::distances::*;
-    pub use crate::drawable::*;
-    pub use crate::grid::*;
-    pub use crate::cell::*;
-    pub use crate::mask::*;
-    pub use crate::point::*;
+    pub use rusty_mazes::prelude::*;
 
     pub use clap::Parser;
-    pub use image::*;
-    pub use rand::Rng;
-    pub use std::{path::Path, process::Command};
 
-    pub const GRID_WIDTH: usize = 8;
-    pub const GRID_HEIGHT: usize = 8;
-    pub const WHITE: Rgb<u8> = image::Rgb([255u8, 255u8, 255u8]);
-    pub const BLACK: Rgb<u8> = image::Rgb([0u8, 0u8, 0u8]);
+    #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum DistanceRoots {
+        // the first cell of the maze, or its entrance when there is one.
+        Start,
+        // every cell on the boundary of the maze.
+        Boundary,
+        // every entrance of the maze.
+        Entrances,
+    }
 
-    #[derive(Parser, Debug)]
-    #[command(author, version, about, long_about = None)]
-    pub struct Args {
+    #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ReportFormat {
+        Text,
+        Json,
+    }
+
+    #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum CsvFormat {
+        Edges,
+        Adjacency,
+    }
+
+    #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Charset {
+        Ascii,
+        Unicode,
+        // the maze as drawn in images, in Braille dots. Any grid can be drawn this way.
+        Braille,
+    }
+
+    // MazeArgs : The maze to carve, its grid, mask and algorithm.
+    #[derive(clap::Args, Debug)]
+    pub struct MazeArgs {
         #[arg(
             short = 'w',
             long,
-            help = "A text mask to use for the maze, made of . and x characters. Input is the full path of the .txt file.",
+            help = "A text mask to use for the maze, made of . and x characters. Input is the full path of the .txt file, or - to read it from stdin.",
             conflicts_with = "mask_image"
         )]
         pub mask: Option<String>,
         #[arg(
             short,
             long,
-            help = "An image mask to use for the maze. Input is the full path of the image file.",
+            help = "An image mask to use for the maze. Input is the full path of the image file, or - to read it from stdin.",
             conflicts_with = "mask"
         )]
         pub mask_image: Option<String>,
+        #[arg(
+            long,
+            help = "Leave out the cells of an image mask darker than this luminance, from 0 to 255, rather than only those of black pixels."
+        )]
+        pub mask_threshold: Option<u8>,
+        #[arg(
+            long,
+            help = "Dither an image mask, so its shades of gray become scattered cells. The threshold is 128 without --mask-threshold."
+        )]
+        pub mask_dither: bool,
+        #[arg(
+            long,
+            help = "Resize the mask to width x height cells, like 60x40, so a large image can make a maze of any size. \
+                    Given only a width, the mask keeps its proportions.",
+            value_name = "SIZE",
+            value_parser = parse_mask_scale
+        )]
+        pub mask_scale: Option<(usize, Option<usize>)>,
+        #[arg(
+            long,
+            help = "Add the cells of another mask to the maze's. Input is a .txt, .svg or image file, and can be given more than once.",
+            value_name = "MASK"
+        )]
+        pub mask_union: Vec<String>,
+        #[arg(
+            long,
+            help = "Keep only the cells of the maze's mask that are also in another mask, applied after any unions.",
+            value_name = "MASK"
+        )]
+        pub mask_intersect: Vec<String>,
+        #[arg(
+            long,
+            help = "Take the cells of another mask out of the maze's, applied after any unions and intersections.",
+            value_name = "MASK"
+        )]
+        pub mask_subtract: Vec<String>,
+        #[arg(
+            long,
+            help = "Flip the mask, so the maze is carved in the cells it would have left out, the dark parts of an image mask."
+        )]
+        pub invert_mask: bool,
+        #[arg(
+            long,
+            help = "An SVG drawing to use as a mask, the cells inside its filled shapes making up the maze. Input is the full path of the .svg file, or - to read it from stdin.",
+            conflicts_with_all = ["mask", "mask_image"]
+        )]
+        pub mask_svg: Option<String>,
+        #[arg(
+            long,
+            help = "Use a built-in shape as the mask, --width cells across.",
+            value_enum,
+            conflicts_with_all = ["mask", "mask_image", "mask_svg"]
+        )]
+        pub shape: Option<MaskShape>,
+        #[arg(
+            long,
+            help = "The share of cells a noise shape starts out with, more giving larger, more open caves.",
+            default_value_t = 0.55
+        )]
+        pub noise_fill: f64,
+        #[arg(
+            long,
+            help = "How many cells across the hole in a ring is, half the ring's width without it."
+        )]
+        pub inner: Option<usize>,
+        #[arg(long, help = "How many points a star has.", default_value_t = 5)]
+        pub points: usize,
+        #[arg(
+            long,
+            help = "Join up a mask whose cells don't form one connected region, by removing the smaller regions or bridging them to the largest.",
+            value_enum
+        )]
+        pub repair_mask: Option<MaskRepair>,
+        #[arg(
+            long,
+            help = "Write the mask the maze is carved in, after any resizing, combining, flipping and repair, to this .txt or .png file, or - to show it on stdout."
+        )]
+        pub save_mask: Option<String>,
+        #[arg(
+            long,
+            help = "The number of cells across the maze, or across an SVG mask or shape.",
+            conflicts_with_all = ["mask", "mask_image"]
+        )]
+        pub width: Option<usize>,
+        #[arg(
+            long,
+            help = "The number of cells down the maze or a noise shape. An SVG mask keeps its proportions without it.",
+            conflicts_with_all = ["mask", "mask_image"]
+        )]
+        pub height: Option<usize>,
+        #[arg(
+            long,
+            help = "Carve a rectangular maze a row at a time with Eller's algorithm and write it straight to a --to-png image, without ever holding the whole maze or image. Only the PNG is written. Mazes of more than 4000000 cells are carved this way on their own when nothing else asked for would be left out."
+        )]
+        pub huge: bool,
+        #[arg(
+            long,
+            help = "Carve a maze even when the mask's cells don't form one connected region."
+        )]
+        pub allow_disconnected: bool,
         #[arg(
             short,
             long,
             help = "The algorithm to apply. Not all masks will work properly with all algorithms.",
             default_value = "recursivebacktracker",
+            value_parser = clap::builder::PossibleValuesParser::new(Algorithm::NAMES),
+            ignore_case = true
         )]
         pub algorithm: Option<String>,
+        #[arg(
+            short,
+            long,
+            help = "The shape of the cells making up the maze.",
+            value_enum,
+            default_value = "rectangular"
+        )]
+        pub grid: GridType,
+        #[arg(
+            long,
+            help = "A graph to use for the maze, made of node and edge lines. Input is the full path of the .txt file.",
+            required_if_eq("grid", "graph")
+        )]
+        pub graph: Option<String>,
+        #[arg(
+            long,
+            help = "The number of levels in a 3d maze.",
+            default_value_t = GRID_DEPTH
+        )]
+        pub depth: usize,
+        #[arg(
+            long,
+            help = "Direction weights for the recursive backtracker, e.g. n=3,e=1,s=3,w=1. Heavier directions are carved more often."
+        )]
+        pub weights: Option<DirectionWeights>,
+        #[arg(
+            long,
+            help = "Generate this many rectangular mazes and stitch them together left to right."
+        )]
+        pub stitch: Option<usize>,
+        #[arg(
+            long,
+            help = "The number of passages carved between each pair of stitched mazes.",
+            requires = "stitch",
+            default_value_t = 1
+        )]
+        pub connectors: usize,
+        #[arg(
+            long,
+            help = "Replace each cell of a rectangular maze with an inner maze this many cells wide."
+        )]
+        pub nest: Option<usize>,
+        #[arg(
+            long,
+            help = "The chance of a cell getting an inner maze when nesting, other cells are left open.",
+            requires = "nest",
+            default_value_t = 1.0
+        )]
+        pub nest_chance: f64,
+        #[arg(
+            long,
+            help = "Carve one part of a rectangular maze and copy it onto the rest. Horizontal and vertical mirror one half onto the other, two-fold turns one half around the middle and four-fold one quarter of a square maze. Polar mazes can be turned too.",
+            value_enum
+        )]
+        pub symmetry: Option<Symmetry>,
+        #[arg(
+            long,
+            help = "Open up rooms in a rectangular maze, given as count,min,max like 5,3,6 for five rooms of three to six cells a side, each joined to the maze by doors.",
+            value_parser = parse_rooms
+        )]
+        pub rooms: Option<(usize, usize, usize)>,
+        #[arg(
+            long,
+            help = "Open up a room in a rectangular maze at x,y,width,height, like 4,2,5,3. Can be given more than once."
+        )]
+        pub room: Vec<Rect>,
+        #[arg(
+            long,
+            help = "Add this many portals to a rectangular maze, each joining two distant cells picked at random."
+        )]
+        pub portals: Option<usize>,
+        #[arg(
+            long,
+            help = "Add a portal to a rectangular maze joining the cells at x1,y1 and x2,y2, like 0,0,9,9. Can be given more than once.",
+            value_parser = parse_portal
+        )]
+        pub portal: Vec<(Point, Point)>,
+        #[arg(
+            long,
+            help = "Turn passages of a rectangular maze into one-way passages with this probability. They lead away from the start, so the exit can always be reached."
+        )]
+        pub one_way: Option<f64>,
+        #[arg(
+            long,
+            help = "Lock this many doors along the solution of a rectangular maze, each with its key left in a dead end behind the door before it, so they're opened in order."
+        )]
+        pub locks: Option<usize>,
+        #[arg(
+            long,
+            help = "Open an entrance and an exit in the boundary of a rectangular maze.",
+            value_enum
+        )]
+        pub entrances: Option<EntranceType>,
+        #[arg(
+            long,
+            help = "Turn this many random cells of a rectangular maze into lava, which distances route around."
+        )]
+        pub lava: Option<usize>,
+        #[arg(
+            long,
+            help = "Remove dead ends from a rectangular maze with this probability, adding loops."
+        )]
+        pub braid: Option<f64>,
+        #[arg(
+            long,
+            help = "Open exactly this many passages through the walls of a rectangular maze, spread as far apart as they can be, each adding a loop."
+        )]
+        pub loops: Option<usize>,
+        #[arg(
+            long,
+            help = "Carve rectangular mazes from one seed after another until one is this hard to solve, judged by the length of its solution, the wrong turns along it and the dead ends beside it.",
+            value_enum
+        )]
+        pub difficulty: Option<Rating>,
+        #[arg(
+            long,
+            help = "The cost of stepping into a lava cell.",
+            requires = "lava",
+            default_value_t = 50
+        )]
+        pub lava_cost: usize,
+        #[arg(
+            long,
+            help = "Seed the random number generator so the same maze can be made again."
+        )]
+        pub seed: Option<u64>,
+        #[arg(
+            long,
+            help = "Carve the maze of the day, the same for everyone on the same date. Give a namespace to share a maze of the day of your own.",
+            value_name = "NAMESPACE",
+            conflicts_with = "seed"
+        )]
+        pub daily: Option<Option<String>>,
+        #[arg(
+            long,
+            help = "Show a progress bar on stderr while the slow algorithms, aldousbroder and wilsons, carve the maze."
+        )]
+        pub progress: bool,
+    }
+
+    // SolveArgs : Finding the path through the maze.
+    #[derive(clap::Args, Debug)]
+    pub struct SolveArgs {
+        #[arg(
+            long,
+            help = "Solve a rectangular maze from its entrance to its exit, or from its first cell to its last."
+        )]
+        pub solve: bool,
+        #[arg(
+            long,
+            help = "Write the solved path of a rectangular maze to this JSON file, or - for stdout.",
+            requires = "solve"
+        )]
+        pub solution_out: Option<String>,
+    }
+
+    // ReportArgs : What to report about the maze's texture.
+    #[derive(clap::Args, Debug)]
+    pub struct ReportArgs {
+        #[arg(long, help = "Print statistics about the generated maze.")]
+        pub stats: bool,
+        #[arg(
+            long,
+            help = "Print an analysis of the maze's texture, as text or JSON.",
+            value_enum
+        )]
+        pub analyze: Option<ReportFormat>,
+        #[arg(
+            long,
+            help = "Print a histogram of cells by how many passages lead out of them, as text or JSON.",
+            value_enum
+        )]
+        pub histogram: Option<ReportFormat>,
+        #[arg(
+            long,
+            help = "Carve this many mazes and print their averaged statistics instead of a maze."
+        )]
+        pub sample: Option<usize>,
+        #[arg(long, help = "Check that the maze is perfect and report any problems.")]
+        pub verify: bool,
+    }
+
+    // OutputArgs : The files the maze is written to, and how it is printed.
+    #[derive(clap::Args, Debug)]
+    pub struct OutputArgs {
+        #[arg(
+            long,
+            help = "Only output a region of a rectangular maze, given as x,y,width,height."
+        )]
+        pub crop: Option<Rect>,
+        #[arg(
+            long,
+            help = "Output the maze's passages as CSV, either a list of edges as maze_edges.csv \
+                    or an adjacency matrix as maze_adjacency.csv.",
+            value_enum
+        )]
+        pub to_csv: Option<CsvFormat>,
+        #[arg(
+            long,
+            help = "Output the maze's passages as a Graphviz graph, as maze.dot. The path found \
+                    by --solve is drawn in red."
+        )]
+        pub to_dot: bool,
+        #[arg(
+            long,
+            help = "Save a rectangular maze to this JSON file, or - for stdout. A file ending \
+                    in .maze is saved in the compact binary .maze format instead."
+        )]
+        pub save: Option<String>,
+        #[arg(
+            long,
+            help = "Where to write output files, which take their extension from what they hold. \
+                    {seed}, {algorithm} and {grid} are filled in, as in out/maze_{seed}.png. \
+                    - writes the one output asked for to stdout.",
+            default_value = "maze"
+        )]
+        pub out: String,
         #[arg(short, long, help = "Output the maze as a PNG image.")]
         pub to_png: bool,
         #[arg(
@@ -65,90 +387,1866 @@ mod prelude {
         )]
         pub to_polar_png: bool,
         #[arg(
-            short,
             long,
-            help = "Resolution of the output image.",
-            requires = "to_png",
-            default_value = "16"
+            help = "Output the maze as an SVG image, only rectangular and polar mazes can be."
         )]
-        pub resolution: Option<usize>,
+        pub to_svg: bool,
         #[arg(
-            short,
             long,
-            help = "Show Dijkstra distances in output.",
-            requires = "output",
-            default_value = "false"
+            help = "Output an animated GIF of a rectangular maze being solved, as maze.gif."
         )]
-        pub show_distances: bool,
+        pub animate: bool,
+        #[arg(
+            long,
+            help = "Output a rectangular maze as a printable PDF, as maze.pdf."
+        )]
+        pub to_pdf: bool,
+        #[arg(
+            long,
+            help = "The paper size of the PDF.",
+            value_enum,
+            requires = "to_pdf",
+            default_value = "a4"
+        )]
+        pub page_size: PageSize,
+        #[arg(
+            long,
+            help = "The margin around the maze in the PDF, in millimeters.",
+            requires = "to_pdf",
+            default_value_t = 15.0
+        )]
+        pub margin: f64,
+        #[arg(
+            long,
+            help = "Add a second page to the PDF with the maze solved.",
+            requires = "to_pdf"
+        )]
+        pub pdf_solution: bool,
+        #[arg(
+            long,
+            help = "Output a first person view of a rectangular maze from its entrance, as maze_first_person.png."
+        )]
+        pub to_first_person: bool,
+        #[arg(
+            long,
+            help = "The width of the first person view, in pixels.",
+            requires = "to_first_person",
+            default_value_t = 640
+        )]
+        pub view_width: u32,
+        #[arg(
+            long,
+            help = "The height of the first person view, in pixels.",
+            requires = "to_first_person",
+            default_value_t = 360
+        )]
+        pub view_height: u32,
+        #[arg(
+            long,
+            help = "Output a rectangular maze as a 3D printable STL model, as maze.stl."
+        )]
+        pub to_stl: bool,
+        #[arg(
+            long,
+            help = "Output a rectangular maze as a Wavefront OBJ model, as maze.obj and maze.mtl."
+        )]
+        pub to_obj: bool,
+        #[arg(
+            long,
+            help = "Output a rectangular maze as a glTF model, as maze.gltf."
+        )]
+        pub to_gltf: bool,
+        #[arg(
+            long,
+            help = "Draw a rectangular maze with the 16 tiles of a tileset image, as maze_tiles.png. \
+                    Tiles are numbered by a cell's openings, north 1, east 2, south 4 and west 8."
+        )]
+        pub tileset: Option<String>,
+        #[arg(
+            long,
+            help = "Output a rectangular maze as a Tiled map, as maze.tmx. Its tiles come from the --tileset \
+                    image, or from one drawn in the maze's style as maze_tileset.png."
+        )]
+        pub to_tmx: bool,
+        #[arg(
+            long,
+            help = "Output a rectangular maze as a Minecraft structure file, as maze.nbt."
+        )]
+        pub to_nbt: bool,
+        #[arg(
+            long,
+            help = "Output the walls of a rectangular maze as lines of a DXF drawing, as maze.dxf."
+        )]
+        pub to_dxf: bool,
+        #[arg(
+            long,
+            help = "The width of each cell of the DXF drawing, in millimeters.",
+            requires = "to_dxf",
+            default_value_t = 10.0
+        )]
+        pub dxf_cell_size: f32,
+        #[arg(
+            long,
+            help = "The block the walls of the Minecraft structure are built from.",
+            requires = "to_nbt",
+            default_value = "minecraft:stone_bricks"
+        )]
+        pub mc_wall: String,
+        #[arg(
+            long,
+            help = "The block the floor of the Minecraft structure is laid with, none for no floor.",
+            requires = "to_nbt",
+            default_value = "minecraft:smooth_stone"
+        )]
+        pub mc_floor: String,
+        #[arg(
+            long,
+            help = "How many blocks high the walls of the Minecraft structure are.",
+            requires = "to_nbt",
+            default_value_t = 3
+        )]
+        pub mc_height: usize,
+        #[arg(
+            long,
+            help = "The width of each cell of the model, in millimeters.",
+            default_value_t = 10.0
+        )]
+        pub mesh_cell_size: f32,
+        #[arg(
+            long,
+            help = "The thickness of the walls of the model, in millimeters.",
+            default_value_t = 1.6
+        )]
+        pub mesh_wall_thickness: f32,
+        #[arg(
+            long,
+            help = "The height of the walls of the model, in millimeters.",
+            default_value_t = 8.0
+        )]
+        pub mesh_wall_height: f32,
+        #[arg(
+            long,
+            help = "The thickness of the base plate under the model in millimeters, 0 for none.",
+            default_value_t = 2.0
+        )]
+        pub mesh_base: f32,
         #[arg(short, long, help = "Show maze in output.", default_value = "false")]
         pub output: bool,
+        #[arg(
+            long,
+            help = "The characters a maze is drawn with in output, only braille draws grids that aren't rectangular.",
+            value_enum,
+            requires = "output",
+            default_value = "ascii"
+        )]
+        pub charset: Charset,
+        #[arg(
+            long,
+            help = "Show only this level of a 3d maze in output, counting up from 1 at the bottom.",
+            requires = "output"
+        )]
+        pub level: Option<usize>,
+        #[arg(
+            long,
+            help = "Also write each level of a 3d maze to a PNG image of its own, with _level_<n> after its name."
+        )]
+        pub level_images: bool,
+        #[arg(
+            long,
+            help = "Color the cells of a rectangular maze by distance in the terminal, instead of writing distances.",
+            requires = "show_distances",
+            requires = "output"
+        )]
+        pub heatmap: bool,
     }
-}
 
-use prelude::*;
+    // StyleArgs : How the maze is drawn in images.
+    #[derive(clap::Args, Debug)]
+    pub struct StyleArgs {
+        #[arg(
+            short,
+            long,
+            help = "Resolution of the output image.",
+            default_value = "16"
+        )]
+        pub resolution: Option<usize>,
+        #[arg(
+            long,
+            help = "Thickness of the walls in the output image, in pixels.",
+            default_value_t = 1
+        )]
+        pub wall_thickness: usize,
+        #[arg(
+            long,
+            help = "Width of each cell in the output image, defaults to the resolution."
+        )]
+        pub cell_width: Option<usize>,
+        #[arg(
+            long,
+            help = "Height of each cell in the output image, defaults to the resolution."
+        )]
+        pub cell_height: Option<usize>,
+        #[arg(
+            short,
+            long,
+            help = "Show Dijkstra distances in output, as a gradient in PNG images.",
+            default_value = "false"
+        )]
+        pub show_distances: bool,
+        #[arg(
+            long,
+            help = "Color the distance gradient fades into, a name or #rrggbb.",
+            value_parser = parse_color,
+            requires = "show_distances"
+        )]
+        pub gradient: Option<Rgb<u8>>,
+        #[arg(
+            long,
+            help = "The colors mazes are drawn in.",
+            value_enum,
+            default_value = "dark"
+        )]
+        pub theme: Theme,
+        #[arg(
+            long,
+            help = "Color of the walls, a name or #rrggbb, instead of the theme's.",
+            value_parser = parse_color
+        )]
+        pub wall_color: Option<Rgb<u8>>,
+        #[arg(
+            long,
+            help = "Leave the background of PNG images, and any masked out cells, transparent."
+        )]
+        pub transparent: bool,
+        #[arg(
+            long,
+            help = "Also split the PNG image into pages to print, given as columns x rows like 3x2. \
+                    Each is written with _page_<row>_<column> after its name.",
+            value_parser = parse_pages
+        )]
+        pub pages: Option<(u32, u32)>,
+        #[arg(
+            long,
+            help = "How many pixels neighboring pages share, defaults to a cell.",
+            requires = "pages"
+        )]
+        pub page_overlap: Option<u32>,
+        #[arg(
+            long,
+            help = "Color of the background, a name or #rrggbb, instead of the theme's.",
+            value_parser = parse_color
+        )]
+        pub bg_color: Option<Rgb<u8>>,
+        #[arg(
+            long,
+            help = "Where distances in a rectangular maze are measured from.",
+            value_enum,
+            requires = "show_distances",
+            default_value = "start"
+        )]
+        pub distances_from: DistanceRoots,
+    }
+
+    #[derive(Parser, Debug)]
+    #[command(
+        author,
+        version,
+        about,
+        long_about = None,
+        args_conflicts_with_subcommands = true
+    )]
+    pub struct Cli {
+        #[command(subcommand)]
+        pub command: Option<Command>,
+        #[arg(
+            long,
+            global = true,
+            help = "Log each phase of making the maze to stderr, with the seconds since starting."
+        )]
+        pub verbose: bool,
+        // without a subcommand, the options are those of generate.
+        #[command(flatten)]
+        pub generate: Generate,
+    }
+
+    #[derive(clap::Subcommand, Debug)]
+    pub enum Command {
+        #[command(about = "Carve a new maze and write it out, the same as giving no subcommand.")]
+        Generate(Box<Generate>),
+        #[command(about = "Find the path through a maze saved with --save, and write it out.")]
+        Solve {
+            #[arg(help = "The saved maze to solve, or - to read it from stdin.")]
+            maze: String,
+            #[arg(
+                long,
+                help = "Write the solved path to this JSON file, or - for stdout."
+            )]
+            solution_out: Option<String>,
+            #[command(flatten)]
+            outputs: OutputArgs,
+            #[command(flatten)]
+            style: StyleArgs,
+        },
+        #[command(about = "Draw a maze saved with --save in any of the output formats.")]
+        Render {
+            #[arg(help = "The saved maze to draw, or - to read it from stdin.")]
+            maze: String,
+            #[command(flatten)]
+            outputs: OutputArgs,
+            #[command(flatten)]
+            style: StyleArgs,
+        },
+        #[command(about = "Carve mazes and report on their texture, without writing them out.")]
+        Analyze {
+            #[command(flatten)]
+            maze: MazeArgs,
+            #[command(flatten)]
+            reports: ReportArgs,
+        },
+        #[command(
+            about = "Check a mask, reporting its size and the separate regions of its cells."
+        )]
+        Mask {
+            #[arg(
+                help = "The mask to check, a .txt file of . and x characters or an image. \
+                        - reads a text mask from stdin."
+            )]
+            mask: String,
+        },
+        #[command(
+            about = "Walk through a rectangular maze in the terminal with the arrow keys, from its entrance to its exit."
+        )]
+        Play {
+            #[arg(
+                help = "A maze saved with --save to walk through, instead of carving a new one."
+            )]
+            maze: Option<String>,
+            #[arg(
+                long,
+                help = "Walk through the maze in a window drawn like the PNG image, instead of the terminal."
+            )]
+            window: bool,
+            #[arg(
+                long,
+                help = "Only show the cells within this many cells of the player, and those already walked through.",
+                requires = "window"
+            )]
+            fog: Option<usize>,
+            #[command(flatten)]
+            options: MazeArgs,
+            #[command(flatten)]
+            style: StyleArgs,
+        },
+        #[command(
+            about = "Answer HTTP requests for mazes, such as GET /maze?w=40&h=30&algorithm=wilsons&format=svg."
+        )]
+        Serve {
+            #[arg(
+                long,
+                help = "The address and port to listen on.",
+                default_value = "127.0.0.1:8080"
+            )]
+            address: String,
+        },
+        #[command(about = "List the algorithms and the texture each leaves behind.")]
+        ListAlgorithms,
+        #[command(about = "List the grids and the shape of their cells.")]
+        ListGrids,
+    }
+
+    // Generate : Everything that goes into carving a new maze and writing it out.
+    #[derive(clap::Args, Debug)]
+    pub struct Generate {
+        #[command(flatten)]
+        pub maze: MazeArgs,
+        #[command(flatten)]
+        pub solving: SolveArgs,
+        #[command(flatten)]
+        pub reports: ReportArgs,
+        #[command(flatten)]
+        pub outputs: OutputArgs,
+        #[command(flatten)]
+        pub style: StyleArgs,
+    }
+
+    // Args : All of the options a maze is made and written out with, whichever subcommand
+    // they were given to.
+    #[derive(Debug)]
+    pub struct Args {
+        pub maze: MazeArgs,
+        pub solving: SolveArgs,
+        pub reports: ReportArgs,
+        pub outputs: OutputArgs,
+        pub style: StyleArgs,
+        // a maze saved with --save, used in place of carving a new one.
+        pub load: Option<String>,
+    }
+
+    impl From<Generate> for Args {
+        fn from(generate: Generate) -> Self {
+            Self {
+                maze: generate.maze,
+                solving: generate.solving,
+                reports: generate.reports,
+                outputs: generate.outputs,
+                style: generate.style,
+                load: None,
+            }
+        }
+    }
+
+    // the values a group of options takes when none of them are given, for the groups a
+    // subcommand doesn't offer.
+    pub fn defaults<T: clap::Args + clap::FromArgMatches>() -> T {
+        let mut command = T::augment_args(clap::Command::new("defaults"));
+
+        // --heatmap requires --show-distances from the style options, which has to be stood
+        // in for when they aren't there.
+        if command
+            .get_arguments()
+            .all(|arg| arg.get_id() != "show_distances")
+        {
+            command = command.arg(
+                clap::Arg::new("show_distances")
+                    .long("show-distances")
+                    .action(clap::ArgAction::SetTrue),
+            );
+        }
 
-fn get_algorithm(name: &str) -> Algorithm {
-    match name.to_lowercase().as_str() {
-        "binarytree" => Algorithm::BinaryTree,
-        "sidewinder" => Algorithm::Sidewinder,
-        "aldousbroder" => Algorithm::AldousBroder,
-        "wilsons" => Algorithm::Wilsons,
-        "huntandkill" => Algorithm::HuntAndKill,
-        "recursivebacktracker" => Algorithm::RecursiveBacktracker,
-        "none" => Algorithm::None,
-        _ => panic!("Algorithm not found"),
+        return T::from_arg_matches(&command.get_matches_from(["defaults"])).unwrap();
     }
 }
 
+use std::io::Write;
+
+use prelude::*;
+
 fn main() {
-    let args = Args::parse();
-    generate_maze(args);
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
 }
 
-fn generate_maze(args: Args) {
-    let mut algorithm = get_algorithm(args.algorithm.unwrap().as_str());
+fn run(cli: Cli) -> Result<(), MazeError> {
+    if cli.verbose {
+        let start = std::time::Instant::now();
 
-    let mut mask = match args.mask {
-        Some(mask) => match Mask::from_txt(&mask) {
-            Ok(mask) => mask,
-            Err(e) => panic!("Error: {}", e),
+        // RUST_LOG can still narrow or widen what's logged, as with any env_logger.
+        env_logger::Builder::new()
+            .filter_module("rusty_mazes", log::LevelFilter::Debug)
+            .parse_default_env()
+            .format(move |out, record| {
+                writeln!(
+                    out,
+                    "[{:>8.3}s] {}",
+                    start.elapsed().as_secs_f64(),
+                    record.args()
+                )
+            })
+            .init();
+    }
+
+    let args = match cli.command {
+        None => Args::from(cli.generate),
+        Some(Command::Generate(generate)) => Args::from(*generate),
+        Some(Command::Solve {
+            maze,
+            solution_out,
+            outputs,
+            style,
+        }) => Args {
+            maze: defaults(),
+            solving: SolveArgs {
+                solve: true,
+                solution_out,
+            },
+            reports: defaults(),
+            outputs,
+            style,
+            load: Some(maze),
+        },
+        Some(Command::Render {
+            maze,
+            outputs,
+            style,
+        }) => Args {
+            maze: defaults(),
+            solving: defaults(),
+            reports: defaults(),
+            outputs,
+            style,
+            load: Some(maze),
+        },
+        Some(Command::Analyze { maze, reports }) => Args {
+            maze,
+            solving: defaults(),
+            reports,
+            outputs: defaults(),
+            style: defaults(),
+            load: None,
         },
-        None => Mask::new(GRID_WIDTH, GRID_HEIGHT),
+        Some(Command::Mask { mask }) => return check_mask(&mask),
+        Some(Command::Play {
+            maze,
+            window,
+            fog,
+            options,
+            style,
+        }) => {
+            let args = Args {
+                maze: options,
+                solving: defaults(),
+                reports: defaults(),
+                outputs: defaults(),
+                style,
+                load: maze,
+            };
+
+            return play(args, window, fog);
+        }
+        Some(Command::Serve { address }) => {
+            println!("Serving mazes on http://{}/maze", address);
+            return Ok(server::serve(&address)?);
+        }
+        Some(Command::ListAlgorithms) => {
+            for name in Algorithm::NAMES {
+                let algorithm = Algorithm::from_name(name, DirectionWeights::default()).unwrap();
+                println!("{:<22}{}", name, algorithm.texture());
+            }
+
+            return Ok(());
+        }
+        Some(Command::ListGrids) => {
+            for grid in <GridType as clap::ValueEnum>::value_variants() {
+                let name = clap::ValueEnum::to_possible_value(grid).unwrap();
+                println!("{:<14}{}", name.get_name(), grid.description());
+            }
+
+            return Ok(());
+        }
     };
 
-    mask = match args.mask_image {
-        Some(mask_image) => match Mask::from_png(&mask_image) {
-            Ok(mask) => mask,
-            Err(e) => panic!("Error: {}", e),
-        },
-        None => mask,
+    return generate_maze(args);
+}
+
+// the mask the maze is carved in, read from whichever of --mask, --mask-image and --mask-svg
+// was given, made as the --shape, with a noise shape grown from `seed`, or every cell of a
+// --width by --height grid, then resized, combined, inverted and repaired as asked for and
+// written to --save-mask.
+fn read_masks(maze: &MazeArgs, seed: u64) -> Result<Mask, MazeError> {
+    let width = maze.width.unwrap_or(GRID_WIDTH);
+    let mut builder = MaskBuilder::new()
+        .width(width)
+        .seed(seed)
+        .noise_fill(maze.noise_fill)
+        .points(maze.points);
+
+    if let Some(height) = maze.height {
+        builder = builder.size(width, height);
+    }
+
+    if let Some(mask) = &maze.mask {
+        builder = builder.text(mask);
+    } else if let Some(mask_image) = &maze.mask_image {
+        builder = builder.image(mask_image);
+    } else if let Some(mask_svg) = &maze.mask_svg {
+        builder = builder.svg(mask_svg);
+    } else if let Some(shape) = maze.shape {
+        builder = builder.shape(shape);
+    }
+
+    if let Some(inner) = maze.inner {
+        builder = builder.inner(inner);
+    }
+
+    if let Some((width, height)) = maze.mask_scale {
+        builder = builder.scale(width, height);
+    }
+
+    if let Some(threshold) = maze.mask_threshold {
+        builder = builder.threshold(threshold);
+    }
+
+    if maze.mask_dither {
+        builder = builder.dither();
+    }
+
+    for path in maze.mask_union.iter() {
+        builder = builder.union(path);
+    }
+
+    for path in maze.mask_intersect.iter() {
+        builder = builder.intersect(path);
+    }
+
+    for path in maze.mask_subtract.iter() {
+        builder = builder.subtract(path);
+    }
+
+    if maze.invert_mask {
+        builder = builder.invert();
+    }
+
+    if let Some(repair) = maze.repair_mask {
+        builder = builder.repair(repair);
+    }
+
+    let mask = builder.build()?;
+
+    if let Some(path) = &maze.save_mask {
+        // written to stdout, the mask is shown as text.
+        let text = path == "-" || Path::new(path).extension().is_some_and(|e| e == "txt");
+        let bytes = if text {
+            mask.to_text().into_bytes()
+        } else {
+            png(DynamicImage::from(mask.to_image()))
+        };
+
+        write_output(path, bytes)?;
+    }
+
+    return Ok(mask);
+}
+
+// whether the algorithm makes a perfect maze on the grid. binary tree and sidewinder only
+// link cells to the north and east, which aren't neighbors on every kind of grid.
+fn carves(algorithm: &Algorithm, grid: GridType) -> bool {
+    return !matches!(
+        (algorithm, grid),
+        (Algorithm::BinaryTree, GridType::Triangle)
+            | (
+                Algorithm::BinaryTree | Algorithm::Sidewinder,
+                GridType::ThreeD | GridType::Sphere | GridType::Penrose
+            )
+    );
+}
+
+// the maze --grid and --algorithm ask for, carved over `mask` from `seed` and shaped by the
+// options that shape rectangular mazes.
+fn maze_builder(
+    maze: &MazeArgs,
+    mask: Mask,
+    algorithm: Algorithm,
+    seed: u64,
+) -> Result<MazeBuilder, MazeError> {
+    if !carves(&algorithm, maze.grid) {
+        let grid = clap::ValueEnum::to_possible_value(&maze.grid).unwrap();
+        return Err(MazeError::UnsupportedGrid {
+            algorithm: algorithm.name().to_string(),
+            grid: grid.get_name().to_string(),
+        });
+    }
+
+    let mut builder = MazeBuilder::new()
+        .mask(mask)
+        .algorithm(algorithm)
+        .seed(seed)
+        .grid(maze.grid)
+        .depth(maze.depth);
+
+    if maze.grid == GridType::Graph {
+        builder = builder.graph(GraphGrid::from_txt(maze.graph.as_deref().unwrap())?);
+    }
+
+    if let Some(symmetry) = maze.symmetry {
+        builder = builder.symmetry(symmetry);
+    }
+
+    if let Some(count) = maze.stitch {
+        builder = builder.stitch(count, maze.connectors);
+    }
+
+    if let Some(size) = maze.nest {
+        builder = builder.nest(size, maze.nest_chance);
+    }
+
+    for &room in maze.room.iter() {
+        builder = builder.room(room);
+    }
+
+    if let Some((count, min, max)) = maze.rooms {
+        builder = builder.random_rooms(count, min, max);
+    }
+
+    if let Some(p) = maze.braid {
+        builder = builder.braid(p);
+    }
+
+    if let Some(count) = maze.loops {
+        builder = builder.loops(count);
+    }
+
+    if let Some(count) = maze.lava {
+        builder = builder.lava(count, maze.lava_cost);
+    }
+
+    for &(a, b) in maze.portal.iter() {
+        builder = builder.portal(a, b);
+    }
+
+    if let Some(count) = maze.portals {
+        builder = builder.random_portals(count);
+    }
+
+    if let Some(entrances) = maze.entrances {
+        builder = builder.entrances(entrances);
+    }
+
+    if let Some(p) = maze.one_way {
+        builder = builder.one_way(p);
+    }
+
+    if let Some(count) = maze.locks {
+        builder = builder.locks(count);
+    }
+
+    if let Some(rating) = maze.difficulty {
+        builder = builder.difficulty(rating);
+    }
+
+    return Ok(builder);
+}
+
+fn print_regions(regions: &[Vec<Point>]) {
+    for (i, region) in regions.iter().enumerate() {
+        let start = region[0];
+        eprintln!(
+            "  region {}: {} cells, starting at ({}, {})",
+            i + 1,
+            region.len(),
+            start.x,
+            start.y
+        );
+    }
+}
+
+fn check_mask(path: &str) -> Result<(), MazeError> {
+    // masks from stdin are read as text.
+    let mask = if path != "-" && !path.to_lowercase().ends_with(".txt") {
+        MaskBuilder::new().image(path).build()?
+    } else {
+        MaskBuilder::new().text(path).build()?
+    };
+    let regions = mask.regions();
+    let cells = mask.mask.iter().filter(|&&cell| cell).count();
+
+    println!(
+        "{} by {} mask with {} cells in {} {}.",
+        mask.width,
+        mask.height,
+        cells,
+        regions.len(),
+        if regions.len() == 1 {
+            "region"
+        } else {
+            "regions"
+        }
+    );
+
+    if regions.len() > 1 {
+        print_regions(&regions);
+        return Err(MazeError::DisconnectedMask {
+            regions: regions.len(),
+        });
+    }
+
+    return Ok(());
+}
+
+// carves a maze, or loads a saved one, and lets it be walked through in the terminal or in
+// a window until the exit is reached or the walk is given up with q or escape.
+fn play(args: Args, window: bool, fog: Option<usize>) -> Result<(), MazeError> {
+    let options = &args.maze;
+    let mut grid = match &args.load {
+        Some(path) => load_grid(path)?,
+        None => {
+            let seed = seed_for(options);
+            log::info!("seeded with {}", seed);
+
+            let mask = read_masks(options, seed)?;
+            let algorithm = Algorithm::from_name(
+                options.algorithm.as_deref().unwrap(),
+                options.weights.unwrap_or_default(),
+            )?;
+
+            maze_builder(options, mask, algorithm, seed)?.build()
+        }
+    };
+
+    if grid.entrances.is_empty() {
+        grid.open_longest_entrances();
+    }
+
+    let mut walker = match Walker::new(grid) {
+        Some(walker) => walker,
+        None => {
+            eprintln!("The maze has no cells to walk through.");
+            return Ok(());
+        }
+    };
+
+    let start = std::time::Instant::now();
+
+    if window {
+        walk_window(&mut walker, &style(&args), fog)?;
+    } else {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::terminal::EnterAlternateScreen,
+            crossterm::cursor::Hide
+        )?;
+
+        let walked = walk(&mut walker, start);
+
+        // the terminal is put back however the walk ended.
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::cursor::Show,
+            crossterm::terminal::LeaveAlternateScreen
+        )?;
+        crossterm::terminal::disable_raw_mode()?;
+        walked?;
+    }
+
+    let seconds = start.elapsed().as_secs_f64();
+
+    if walker.finished() {
+        println!(
+            "Solved in {} moves and {:.1} seconds, the shortest path takes {}.",
+            walker.moves,
+            seconds,
+            walker.shortest()
+        );
+    } else {
+        println!(
+            "Gave up after {} moves and {:.1} seconds.",
+            walker.moves, seconds
+        );
+    }
+
+    return Ok(());
+}
+
+// redraws the maze after every key, and every second so the clock keeps running.
+fn walk(walker: &mut Walker, start: std::time::Instant) -> std::io::Result<()> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+
+    loop {
+        draw_walk(walker, start)?;
+
+        // the finished maze stays up until a key is pressed.
+        if walker.finished() {
+            while !matches!(event::read()?, Event::Key(key) if key.kind == KeyEventKind::Press) {}
+            return Ok(());
+        }
+
+        if !event::poll(std::time::Duration::from_secs(1))? {
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            _ => continue,
+        };
+
+        let direction = match key.code {
+            KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('k') => Direction::North,
+            KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('l') => Direction::East,
+            KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('j') => Direction::South,
+            KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('h') => Direction::West,
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            _ => continue,
+        };
+
+        walker.step(direction);
+    }
+}
+
+// opens a window showing the maze as it's walked, which stays open once the exit is reached
+// until it's closed.
+fn walk_window(walker: &mut Walker, style: &Style, fog: Option<usize>) -> std::io::Result<()> {
+    use minifb::{Key, KeyRepeat, Window, WindowOptions};
+
+    let (width, height) = walker.grid.image_size(style);
+    let mut window = Window::new(
+        "Rusty Mazes",
+        width as usize,
+        height as usize,
+        WindowOptions::default(),
+    )
+    .map_err(|e| std::io::Error::other(e.to_string()))?;
+    window.set_target_fps(60);
+
+    while window.is_open() && !window.is_key_down(Key::Escape) && !window.is_key_down(Key::Q) {
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            let direction = match key {
+                Key::Up | Key::W | Key::K => Direction::North,
+                Key::Right | Key::D | Key::L => Direction::East,
+                Key::Down | Key::S | Key::J => Direction::South,
+                Key::Left | Key::A | Key::H => Direction::West,
+                _ => continue,
+            };
+
+            walker.step(direction);
+        }
+
+        // the window takes each pixel as 0RGB.
+        let pixels = walker
+            .to_image(style, fog)
+            .pixels()
+            .map(|&Rgb([r, g, b])| (r as u32) << 16 | (g as u32) << 8 | b as u32)
+            .collect::<Vec<u32>>();
+
+        window
+            .update_with_buffer(&pixels, width as usize, height as usize)
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+    }
+
+    return Ok(());
+}
+
+// the part of the maze around the player that fits in the terminal, above a line of moves
+// and time taken.
+fn draw_walk(walker: &Walker, start: std::time::Instant) -> std::io::Result<()> {
+    use crossterm::{cursor, queue, terminal};
+
+    let (columns, rows) = terminal::size()?;
+    let (columns, rows) = (columns as usize, (rows as usize).saturating_sub(1));
+    let lines = walker
+        .to_unicode()
+        .lines()
+        .map(|line| line.chars().collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+
+    // the view is kept centered on the player, as far as the edges of the maze allow.
+    let center = |at: usize, size: usize, view: usize| {
+        return at.saturating_sub(view / 2).min(size.saturating_sub(view));
+    };
+    let width = lines.first().map_or(0, |line| line.len());
+    let top = center(walker.position.y as usize * 2 + 1, lines.len(), rows);
+    let left = center(walker.position.x as usize * 4 + 2, width, columns);
+
+    let mut stdout = std::io::stdout();
+    queue!(
+        stdout,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+
+    for line in lines.iter().skip(top).take(rows) {
+        let line = line.iter().skip(left).take(columns).collect::<String>();
+        write!(stdout, "{}\r\n", line)?;
+    }
+
+    let status = if walker.finished() {
+        "Solved! Press any key."
+    } else {
+        "Arrow keys to move, q to quit."
+    };
+    write!(
+        stdout,
+        "moves {}  time {}s  {}",
+        walker.moves,
+        start.elapsed().as_secs(),
+        status
+    )?;
+
+    return stdout.flush();
+}
+
+fn generate_maze(mut args: Args) -> Result<(), MazeError> {
+    let seed = seed_for(&args.maze);
+    random::seed(seed);
+    log::info!("seeded with {}", seed);
+    // kept so output paths can be named after it.
+    args.maze.seed = Some(seed);
+
+    if let Some(parent) = Path::new(&output_path(&args, "", "png")).parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", parent.display(), e)))?;
+    }
+
+    if args.maze.huge {
+        if let Some(option) = stream_conflict(&args) {
+            return Err(MazeError::Unstreamable(String::from(option)));
+        }
+
+        if !args.outputs.to_png {
+            return Err(MazeError::InvalidOutput(String::from(
+                "a --huge maze is only written as a --to-png image",
+            )));
+        }
+    }
+
+    if streamed(&args) {
+        return stream_maze(&args);
+    }
+
+    let mut algorithm = Algorithm::from_name(
+        args.maze.algorithm.as_deref().unwrap(),
+        args.maze.weights.unwrap_or_default(),
+    )?;
+
+    if args.maze.progress {
+        show_progress();
+    }
+
+    warn_ignored(&args);
+
+    let mask = read_masks(&args.maze, seed)?;
+
+    if args.maze.symmetry == Some(Symmetry::FourFold) && mask.width != mask.height {
+        eprintln!("Four-fold symmetry is only available for square mazes.");
+    }
+
+    let regions = mask.regions();
+    log::info!("regions in the mask: {}", regions.len());
+
+    if regions.len() > 1 && !args.maze.allow_disconnected {
+        print_regions(&regions);
+        eprintln!(
+            "Use --allow-disconnected to carve each region on its own, or --repair-mask to join them."
+        );
+        return Err(MazeError::DisconnectedMask {
+            regions: regions.len(),
+        });
+    }
+
+    let builder = maze_builder(&args.maze, mask.clone(), algorithm, seed)?;
+
+    if let Some(runs) = args.reports.sample {
+        println!("{}", builder.sample(runs));
+        return Ok(());
+    }
+
+    let grid = match &args.load {
+        Some(path) => {
+            let mut grid = load_grid(path)?;
+
+            for problem in builder.shape(&mut grid) {
+                eprintln!("{}", problem);
+            }
+
+            AnyGrid::Rectangular(grid)
+        }
+        None => {
+            let carved = builder.carve();
+
+            if let (AnyGrid::Rectangular(grid), Some(found)) = (&carved.grid, carved.seed) {
+                if found != seed {
+                    eprintln!(
+                        "The {} maze has seed {}.",
+                        Difficulty::of(grid).rating(),
+                        found
+                    );
+                }
+            }
+
+            for problem in carved.problems {
+                eprintln!("{}", problem);
+            }
+
+            // kept so output paths are named after the seed the maze was carved from.
+            args.maze.seed = carved.seed;
+            carved.grid
+        }
+    };
+
+    match grid {
+        AnyGrid::Rectangular(grid) => write_rectangular(grid, seed, &args)?,
+        mut grid => {
+            if args.style.show_distances {
+                grid.measure_distances();
+            }
+
+            print_braille(grid.drawable(), &args);
+
+            if let AnyGrid::ThreeD(grid) = &grid {
+                print_levels(grid, &args);
+                save_levels(grid, &args)?;
+            }
+
+            print_reports(grid.grid(), &args);
+            save_csv(grid.grid(), &args)?;
+            save_dot(grid.grid(), &[], &args)?;
+            save_png(grid.drawable(), &args)?;
+            save_svg(grid.drawable(), &output_path(&args, "", "svg"), &args)?;
+        }
+    }
+
+    if args.outputs.to_polar_png {
+        let mut grid = match args.maze.symmetry {
+            Some(symmetry) => PolarGrid::symmetric(&mask, symmetry, &mut algorithm),
+            None => {
+                let mut grid = PolarGrid::from_mask(&mask);
+                algorithm.on(&mut grid);
+                grid
+            }
+        };
+
+        if args.style.show_distances {
+            grid.distances = grid.distances.measure(&grid);
+        }
+
+        save_image(&grid, &output_path(&args, "_polar", "png"), &args)?;
+
+        save_svg(&grid, &output_path(&args, "_polar", "svg"), &args)?;
+    }
+
+    return Ok(());
+}
+
+// warns about each option asked for that the grid can't honor, before anything is carved
+// or written, so nothing is made without it unannounced.
+fn warn_ignored(args: &Args) {
+    if args.outputs.output
+        && args.outputs.charset != Charset::Braille
+        && !matches!(args.maze.grid, GridType::Rectangular | GridType::ThreeD)
+    {
+        eprintln!("Text output is only available for rectangular and 3d grids.");
+    }
+
+    if (args.outputs.level.is_some() || args.outputs.level_images)
+        && args.maze.grid != GridType::ThreeD
+    {
+        eprintln!("Levels are only available for 3d grids.");
+    }
+
+    if args.maze.nest.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Nesting is only available for rectangular grids.");
+    }
+
+    if args.maze.symmetry.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Symmetry is only available for rectangular grids.");
+    }
+
+    if args.maze.stitch.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Stitching is only available for rectangular grids.");
+    }
+
+    if args.maze.lava.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Lava is only available for rectangular grids.");
+    }
+
+    if args.maze.entrances.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Entrances are only available for rectangular grids.");
+    }
+
+    if args.solving.solve && args.maze.grid != GridType::Rectangular {
+        eprintln!("Solving is only available for rectangular grids.");
+    }
+
+    if (args.outputs.save.is_some() || args.load.is_some())
+        && args.maze.grid != GridType::Rectangular
+    {
+        eprintln!("Saving and loading are only available for rectangular grids.");
+    }
+
+    if args.outputs.to_pdf && args.maze.grid != GridType::Rectangular {
+        eprintln!("PDF output is only available for rectangular grids.");
+    }
+
+    if args.outputs.to_dxf && args.maze.grid != GridType::Rectangular {
+        eprintln!("DXF output is only available for rectangular grids.");
+    }
+
+    if args.outputs.to_nbt && args.maze.grid != GridType::Rectangular {
+        eprintln!("Minecraft output is only available for rectangular grids.");
+    }
+
+    if args.outputs.to_tmx && args.maze.grid != GridType::Rectangular {
+        eprintln!("Tiled map output is only available for rectangular grids.");
+    }
+
+    if args.outputs.tileset.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Tileset rendering is only available for rectangular grids.");
+    }
+
+    if (args.outputs.to_stl || args.outputs.to_obj || args.outputs.to_gltf)
+        && args.maze.grid != GridType::Rectangular
+    {
+        eprintln!("3D model output is only available for rectangular grids.");
+    }
+
+    if args.outputs.to_first_person && args.maze.grid != GridType::Rectangular {
+        eprintln!("First person views are only available for rectangular grids.");
+    }
+
+    if args.outputs.animate && args.maze.grid != GridType::Rectangular {
+        eprintln!("Animation is only available for rectangular grids.");
+    }
+
+    if (args.maze.rooms.is_some() || !args.maze.room.is_empty())
+        && args.maze.grid != GridType::Rectangular
+    {
+        eprintln!("Rooms are only available for rectangular grids.");
+    }
+
+    if (args.maze.portals.is_some() || !args.maze.portal.is_empty())
+        && args.maze.grid != GridType::Rectangular
+    {
+        eprintln!("Portals are only available for rectangular grids.");
+    }
+
+    if args.maze.locks.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Locked doors are only available for rectangular grids.");
+    }
+
+    if args.maze.one_way.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("One-way passages are only available for rectangular grids.");
+    }
+
+    if args.maze.braid.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Braiding is only available for rectangular grids.");
+    }
+
+    if args.maze.difficulty.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Difficulty is only available for rectangular grids.");
+    }
+
+    if args.maze.difficulty.is_some() && args.load.is_some() {
+        eprintln!("Difficulty only picks between mazes that are carved, not loaded.");
+    }
+
+    if args.maze.loops.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Loops are only available for rectangular grids.");
+    }
+
+    if args.outputs.crop.is_some() && args.maze.grid != GridType::Rectangular {
+        eprintln!("Cropping is only available for rectangular grids.");
+    }
+}
+
+// solves, measures, crops and saves a rectangular maze as asked for, then writes every
+// output asked for of it.
+fn write_rectangular(mut grid: RectangularGrid, seed: u64, args: &Args) -> Result<(), MazeError> {
+    let seed = args.maze.seed.unwrap_or(seed);
+
+    if args.solving.solve {
+        grid.solve();
+        save_solution(&grid, seed, args)?;
+    }
+
+    match args.style.distances_from {
+        DistanceRoots::Start => {}
+        DistanceRoots::Boundary => {
+            grid.distances = Distances::new_multi(grid.boundary());
+        }
+        DistanceRoots::Entrances if !grid.entrances.is_empty() => {
+            let cells = grid.entrances.iter().map(|e| e.cell).collect();
+            grid.distances = Distances::new_multi(cells);
+        }
+        DistanceRoots::Entrances => {
+            eprintln!("The maze has no entrances, measuring distances from the start.");
+        }
+    }
+
+    if args.style.show_distances {
+        grid.distances = grid.distances.measure(&grid);
+    }
+
+    if let Some(rect) = args.outputs.crop {
+        grid = grid.crop(rect);
+    }
+
+    if let Some(path) = &args.outputs.save {
+        let bytes = if path.ends_with(".maze") {
+            let mut bytes = Vec::new();
+            grid.save(&mut bytes, false)?;
+            bytes
+        } else {
+            grid.to_json().into_bytes()
+        };
+
+        write_output(path, bytes)?;
+    }
+
+    if args.outputs.output {
+        let heat = style(args);
+        let heat = if args.outputs.heatmap {
+            Some(&heat)
+        } else {
+            None
+        };
+
+        match args.outputs.charset {
+            Charset::Ascii => println!("{}", grid.to_ascii(heat)),
+            Charset::Unicode => println!("{}", grid.to_unicode(heat)),
+            Charset::Braille => print!("{}", grid.to_braille()),
+        }
+    }
+
+    print_reports(&grid, args);
+    save_csv(&grid, args)?;
+    save_dot(&grid, &grid.path, args)?;
+    save_png(&grid, args)?;
+    save_svg(&grid, &output_path(args, "", "svg"), args)?;
+
+    if args.outputs.animate {
+        let path = output_path(args, "", "gif");
+
+        let mut gif = Vec::new();
+        write_gif(solver_frames(&grid, &style(args)), &mut gif)?;
+        write_output(&path, gif)?;
+    }
+
+    if args.outputs.to_pdf {
+        let pdf = grid.to_pdf(
+            args.outputs.page_size,
+            args.outputs.margin,
+            &style(args),
+            args.outputs.pdf_solution,
+        );
+
+        write_output(&output_path(args, "", "pdf"), pdf)?;
+    }
+
+    if args.outputs.to_stl || args.outputs.to_obj || args.outputs.to_gltf {
+        save_models(&grid, args)?;
+    }
+
+    if let Some(camera) = grid.start_camera().filter(|_| args.outputs.to_first_person) {
+        let path = output_path(args, "_first_person", "png");
+        let view = grid.to_first_person(
+            &camera,
+            args.outputs.view_width.max(1),
+            args.outputs.view_height.max(1),
+            &style(args),
+        );
+
+        write_output(&path, png(view.into()))?;
+    }
+
+    if let Some(tileset) = &args.outputs.tileset {
+        save_tiled(&grid, tileset, args)?;
+    }
+
+    if args.outputs.to_tmx {
+        save_tmx(&grid, args)?;
+    }
+
+    if args.outputs.to_nbt {
+        save_structure(&grid, args)?;
+    }
+
+    if args.outputs.to_dxf {
+        let dxf = grid.to_dxf(args.outputs.dxf_cell_size);
+        write_output(&output_path(args, "", "dxf"), dxf)?;
+    }
+
+    return Ok(());
+}
+
+// the most cells a maze can have before it's carved and drawn a row at a time.
+const HUGE_CELLS: usize = 4_000_000;
+
+// whether the maze is carved and drawn a row at a time, as it is with --huge. A maze too
+// big to hold is only streamed on its own when nothing it's asked for would be left out.
+fn streamed(args: &Args) -> bool {
+    let maze = &args.maze;
+    let cells = maze.width.unwrap_or(GRID_WIDTH) * maze.height.unwrap_or(GRID_HEIGHT);
+
+    return args.load.is_none()
+        && args.outputs.to_png
+        && stream_conflict(args).is_none()
+        && (maze.huge || cells > HUGE_CELLS);
+}
+
+// the first option given that a streamed maze would leave out, since it's only a plain
+// rectangular maze carved with Eller's algorithm and written as a PNG.
+fn stream_conflict(args: &Args) -> Option<&'static str> {
+    let (maze, solving, reports, outputs, style) = (
+        &args.maze,
+        &args.solving,
+        &args.reports,
+        &args.outputs,
+        &args.style,
+    );
+
+    let conflicts = [
+        (maze.grid != GridType::Rectangular, "--grid"),
+        (maze.mask.is_some(), "--mask"),
+        (maze.mask_image.is_some(), "--mask-image"),
+        (maze.mask_svg.is_some(), "--mask-svg"),
+        (maze.shape.is_some(), "--shape"),
+        (!maze.mask_union.is_empty(), "--mask-union"),
+        (!maze.mask_intersect.is_empty(), "--mask-intersect"),
+        (!maze.mask_subtract.is_empty(), "--mask-subtract"),
+        (maze.invert_mask, "--invert-mask"),
+        (maze.repair_mask.is_some(), "--repair-mask"),
+        (maze.save_mask.is_some(), "--save-mask"),
+        (
+            maze.algorithm.as_deref().map(str::to_lowercase) != defaults::<MazeArgs>().algorithm,
+            "--algorithm",
+        ),
+        (maze.weights.is_some(), "--weights"),
+        (maze.stitch.is_some(), "--stitch"),
+        (maze.nest.is_some(), "--nest"),
+        (maze.symmetry.is_some(), "--symmetry"),
+        (maze.rooms.is_some(), "--rooms"),
+        (!maze.room.is_empty(), "--room"),
+        (maze.portals.is_some(), "--portals"),
+        (!maze.portal.is_empty(), "--portal"),
+        (maze.one_way.is_some(), "--one-way"),
+        (maze.locks.is_some(), "--locks"),
+        (maze.entrances.is_some(), "--entrances"),
+        (maze.lava.is_some(), "--lava"),
+        (maze.braid.is_some(), "--braid"),
+        (maze.loops.is_some(), "--loops"),
+        (maze.difficulty.is_some(), "--difficulty"),
+        (solving.solve, "--solve"),
+        (solving.solution_out.is_some(), "--solution-out"),
+        (reports.stats, "--stats"),
+        (reports.analyze.is_some(), "--analyze"),
+        (reports.histogram.is_some(), "--histogram"),
+        (reports.sample.is_some(), "--sample"),
+        (reports.verify, "--verify"),
+        (outputs.output, "--output"),
+        (outputs.crop.is_some(), "--crop"),
+        (outputs.to_csv.is_some(), "--to-csv"),
+        (outputs.to_dot, "--to-dot"),
+        (outputs.save.is_some(), "--save"),
+        (outputs.to_polar_png, "--to-polar-png"),
+        (outputs.to_svg, "--to-svg"),
+        (outputs.animate, "--animate"),
+        (outputs.to_pdf, "--to-pdf"),
+        (outputs.to_first_person, "--to-first-person"),
+        (outputs.to_stl, "--to-stl"),
+        (outputs.to_obj, "--to-obj"),
+        (outputs.to_gltf, "--to-gltf"),
+        (outputs.tileset.is_some(), "--tileset"),
+        (outputs.to_tmx, "--to-tmx"),
+        (outputs.to_nbt, "--to-nbt"),
+        (outputs.to_dxf, "--to-dxf"),
+        (outputs.level_images, "--level-images"),
+        (style.show_distances, "--show-distances"),
+        (style.transparent, "--transparent"),
+        (style.pages.is_some(), "--pages"),
+    ];
+
+    return conflicts
+        .into_iter()
+        .find(|&(given, _)| given)
+        .map(|(_, option)| option);
+}
+
+// carves a maze with Eller's algorithm and writes it to a PNG as it goes.
+fn stream_maze(args: &Args) -> Result<(), MazeError> {
+    let (width, height) = (
+        args.maze.width.unwrap_or(GRID_WIDTH).max(1),
+        args.maze.height.unwrap_or(GRID_HEIGHT).max(1),
+    );
+    let path = output_path(args, "", "png");
+    log::info!(
+        "streaming a {}x{} maze carved with Eller's algorithm to {}",
+        width,
+        height,
+        path
+    );
+
+    if path == "-" {
+        let stdout = std::io::stdout().lock();
+        return RectangularGrid::stream_png(width, height, &style(args), stdout);
+    }
+
+    let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+
+    return RectangularGrid::stream_png(width, height, &style(args), file);
+}
+
+// the seed to carve from, the one given with --seed or the day's with --daily, and otherwise
+// a fresh one. The day's seed is reported so the maze can be made again later.
+fn seed_for(maze: &MazeArgs) -> u64 {
+    if let Some(namespace) = &maze.daily {
+        let date = random::today();
+        let seed = random::daily_seed(&date, namespace.as_deref().unwrap_or_default());
+        eprintln!("The maze of the day for {} has seed {}.", date, seed);
+
+        return seed;
+    }
+
+    return maze.seed.unwrap_or_else(random::random_seed);
+}
+
+// draws a bar of the cells carved so far on stderr, redrawn each time another percent of
+// the maze is carved.
+fn show_progress() {
+    const WIDTH: usize = 30;
+    let mut shown = None;
+
+    progress::watch(move |carved, total| {
+        let percent = carved * 100 / total.max(1);
+
+        if shown == Some(percent) {
+            return;
+        }
+
+        shown = Some(percent);
+        let filled = percent * WIDTH / 100;
+
+        eprint!(
+            "\r[{}{}] {:>3}% {}/{} cells",
+            "#".repeat(filled),
+            " ".repeat(WIDTH - filled),
+            percent,
+            carved,
+            total
+        );
+
+        if carved == total {
+            eprintln!();
+        }
+    });
+}
+
+fn print_braille(grid: &dyn Drawable, args: &Args) {
+    if args.outputs.output && args.outputs.charset == Charset::Braille {
+        print!("{}", grid.to_braille());
+    }
+}
+
+// prints the levels of a 3d maze one after another from the bottom up, or only --level.
+fn print_levels(grid: &Grid3D, args: &Args) {
+    if !args.outputs.output || args.outputs.charset == Charset::Braille {
+        return;
+    }
+
+    let levels = match args.outputs.level {
+        Some(level) if level == 0 || level > grid.depth => {
+            eprintln!("The maze only has levels 1 to {}.", grid.depth);
+            return;
+        }
+        Some(level) => level - 1..level,
+        None => 0..grid.depth,
+    };
+
+    for z in levels {
+        println!("Level {}:", z + 1);
+        println!(
+            "{}",
+            grid.level_to_text(z, args.outputs.charset == Charset::Unicode)
+        );
+    }
+}
+
+fn save_levels(grid: &Grid3D, args: &Args) -> Result<(), MazeError> {
+    if !args.outputs.level_images {
+        return Ok(());
+    }
+
+    for z in 0..grid.depth {
+        let path = output_path(args, &format!("_level_{}", z + 1), "png");
+        let image = grid.level_image(z, &style(args));
+
+        write_output(&path, png(image.into()))?;
+    }
+
+    return Ok(());
+}
+
+fn save_csv(grid: &dyn Grid, args: &Args) -> Result<(), MazeError> {
+    let (suffix, csv) = match args.outputs.to_csv {
+        Some(CsvFormat::Edges) => ("_edges", Connectivity::of(grid).to_edges_csv()),
+        Some(CsvFormat::Adjacency) => ("_adjacency", Connectivity::of(grid).to_adjacency_csv()),
+        None => return Ok(()),
     };
 
-    let mut grid = RectangularGrid::from_mask(&mask);
-    algorithm.on(&mut grid);
+    return write_output(&output_path(args, suffix, "csv"), csv);
+}
+
+fn save_dot(grid: &dyn Grid, path: &[Point], args: &Args) -> Result<(), MazeError> {
+    if !args.outputs.to_dot {
+        return Ok(());
+    }
+
+    let file = output_path(args, "", "dot");
 
-    if args.show_distances {
-        grid.distances.compute(grid.clone());
+    return write_output(&file, Connectivity::of(grid).to_dot(path));
+}
+
+fn print_reports(grid: &dyn Grid, args: &Args) {
+    if args.reports.stats {
+        println!("{}", Stats::of(grid));
+    }
+
+    if args.reports.verify {
+        let violations = verify(grid);
+
+        if violations.is_empty() {
+            println!("The maze is perfect.");
+        }
+
+        for violation in violations {
+            println!("{}", violation);
+        }
+    }
+
+    match args.reports.analyze {
+        Some(ReportFormat::Text) => println!("{}", Analysis::of(grid)),
+        Some(ReportFormat::Json) => println!("{}", Analysis::of(grid).to_json()),
+        None => {}
     }
 
-    if args.output {
-        println!("{}", grid);
+    match args.reports.histogram {
+        Some(ReportFormat::Text) => println!("{}", Branching::of(grid)),
+        Some(ReportFormat::Json) => println!("{}", Branching::of(grid).to_json()),
+        None => {}
+    }
+}
+
+fn load_grid(path: &str) -> Result<RectangularGrid, MazeError> {
+    let bytes = read_input(path)?;
+
+    // a .maze file is told apart by what it starts with, so one read from stdin works too.
+    if bytes.starts_with(b"MAZE") {
+        return RectangularGrid::load(bytes.as_slice());
+    }
+
+    return RectangularGrid::from_json(&String::from_utf8_lossy(&bytes));
+}
+
+// writes `contents` to the file at `path`, or to stdout when `path` is -.
+fn write_output(path: &str, contents: impl AsRef<[u8]>) -> Result<(), MazeError> {
+    log::info!("writing {} bytes to {}", contents.as_ref().len(), path);
+
+    if path == "-" {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(contents.as_ref())?;
+        stdout.flush()?;
+
+        return Ok(());
     }
 
-    if args.to_png {
-        let path = Path::new("maze.png");
-        grid.to_grid_image(args.resolution.unwrap())
-            .save(path)
-            .unwrap();
+    // the error names the file, like those of `read_input`.
+    std::fs::write(path, contents)
+        .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", path, e)))?;
+
+    return Ok(());
+}
+
+fn png(image: DynamicImage) -> Vec<u8> {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageOutputFormat::Png).unwrap();
+
+    return bytes.into_inner();
+}
+
+fn save_solution(grid: &RectangularGrid, seed: u64, args: &Args) -> Result<(), MazeError> {
+    if let Some(path) = &args.solving.solution_out {
+        write_output(path, grid.solution_json(seed))?;
     }
 
-    if args.to_polar_png {
-        let mut grid = PolarGrid::from_mask(&mask);
-        algorithm.on(&mut grid);
+    return Ok(());
+}
 
-        let path = Path::new("maze_polar.png");
-        grid.to_grid_image(args.resolution.unwrap())
-            .save(path)
-            .unwrap();
+// where an output is written, `--out` with its placeholders filled in and its extension
+// swapped for `extension`. `suffix` tells apart outputs that share an extension.
+fn output_path(args: &Args, suffix: &str, extension: &str) -> String {
+    // every output goes to stdout, so only one should be asked for.
+    if args.outputs.out == "-" {
+        return String::from("-");
     }
+
+    let grid = clap::ValueEnum::to_possible_value(&args.maze.grid).unwrap();
+    let pattern = args
+        .outputs
+        .out
+        .replace("{seed}", &args.maze.seed.unwrap_or_default().to_string())
+        .replace(
+            "{algorithm}",
+            &args.maze.algorithm.as_deref().unwrap().to_lowercase(),
+        )
+        .replace("{grid}", grid.get_name());
+
+    let path = Path::new(&pattern);
+    let stem = path
+        .file_stem()
+        .unwrap_or("maze".as_ref())
+        .to_string_lossy();
+    let name = format!("{}{}.{}", stem, suffix, extension);
+
+    return path.with_file_name(name).to_string_lossy().into_owned();
+}
+
+fn save_png(grid: &dyn Drawable, args: &Args) -> Result<(), MazeError> {
+    if args.outputs.to_png {
+        save_image(grid, &output_path(args, "", "png"), args)?;
+    }
+
+    if let Some((columns, rows)) = args.style.pages {
+        let style = style(args);
+        let pages = Pages {
+            columns,
+            rows,
+            overlap: args
+                .style
+                .page_overlap
+                .unwrap_or(style.cell_width.max(style.cell_height) as u32),
+        };
+
+        for (r, row) in pages.render(grid, &style).into_iter().enumerate() {
+            for (c, page) in row.into_iter().enumerate() {
+                let path = output_path(args, &format!("_page_{}_{}", r + 1, c + 1), "png");
+
+                write_output(&path, png(page.into()))?;
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+fn save_image(grid: &dyn Drawable, path: &str, args: &Args) -> Result<(), MazeError> {
+    log::info!("drawing {}", path);
+
+    let image = if args.style.transparent {
+        DynamicImage::from(grid.to_transparent_image(&style(args)))
+    } else {
+        DynamicImage::from(grid.to_grid_image(&style(args)))
+    };
+
+    return write_output(path, png(image));
+}
+
+fn save_svg(grid: &dyn Drawable, path: &str, args: &Args) -> Result<(), MazeError> {
+    if !args.outputs.to_svg {
+        return Ok(());
+    }
+
+    match grid.to_svg(&style(args)) {
+        Some(svg) => write_output(path, svg)?,
+        None => eprintln!("SVG output is only available for rectangular and polar grids."),
+    }
+
+    return Ok(());
+}
+
+fn save_tiled(grid: &RectangularGrid, tileset: &str, args: &Args) -> Result<(), MazeError> {
+    let tiled = image::open(tileset)
+        .map_err(|e| e.to_string())
+        .and_then(|tileset| grid.to_tiled_image(&tileset.to_rgb8()))
+        .map_err(|e| {
+            MazeError::InvalidOutput(format!("could not draw the maze with {}: {}", tileset, e))
+        })?;
+
+    return write_output(&output_path(args, "_tiles", "png"), png(tiled.into()));
+}
+
+fn save_tmx(grid: &RectangularGrid, args: &Args) -> Result<(), MazeError> {
+    let (source, size) = match &args.outputs.tileset {
+        // the map is read from wherever it's saved, so it's given the whole path of the tileset.
+        Some(tileset) => {
+            let size = image::image_dimensions(tileset)?;
+            let source = std::fs::canonicalize(tileset)
+                .map_or(tileset.clone(), |path| path.to_string_lossy().into_owned());
+
+            (source, size)
+        }
+        None => {
+            // Tiled's tiles are square.
+            let mut style = style(args);
+            style.cell_height = style.cell_width;
+
+            let path = output_path(args, "_tileset", "png");
+            let image = RectangularGrid::tileset(&style);
+            let size = image.dimensions();
+
+            write_output(&path, png(image.into()))?;
+
+            let source = Path::new(&path).file_name().unwrap().to_string_lossy();
+            (source.into_owned(), size)
+        }
+    };
+
+    let path = output_path(args, "", "tmx");
+    let tmx = grid
+        .to_tmx(&source, size)
+        .map_err(|e| MazeError::InvalidOutput(format!("could not write {}: {}", path, e)))?;
+
+    return write_output(&path, tmx);
+}
+
+fn save_structure(grid: &RectangularGrid, args: &Args) -> Result<(), MazeError> {
+    let options = StructureOptions {
+        wall: args.outputs.mc_wall.clone(),
+        floor: Some(args.outputs.mc_floor.clone()).filter(|floor| floor != "none"),
+        height: args.outputs.mc_height.max(1),
+    };
+    return write_output(&output_path(args, "", "nbt"), grid.to_structure(&options));
+}
+
+fn save_models(grid: &RectangularGrid, args: &Args) -> Result<(), MazeError> {
+    let options = MeshOptions {
+        cell_size: args.outputs.mesh_cell_size,
+        wall_thickness: args.outputs.mesh_wall_thickness,
+        wall_height: args.outputs.mesh_wall_height,
+        base: args.outputs.mesh_base.max(0.0),
+    };
+
+    if options.wall_thickness <= 0.0 || options.wall_thickness >= options.cell_size {
+        return Err(MazeError::InvalidOutput(String::from(
+            "the walls of the model must be thinner than its cells",
+        )));
+    }
+
+    if options.wall_height <= 0.0 {
+        return Err(MazeError::InvalidOutput(String::from(
+            "the walls of the model must have a height",
+        )));
+    }
+
+    let mesh = grid.to_mesh(&options);
+    let mut files = Vec::new();
+
+    if args.outputs.to_stl {
+        files.push((output_path(args, "", "stl"), mesh.to_stl()));
+    }
+
+    if args.outputs.to_obj {
+        // the OBJ file finds its materials beside it.
+        let mtl = output_path(args, "", "mtl");
+        let mtl_name = Path::new(&mtl).file_name().unwrap().to_string_lossy();
+
+        files.push((
+            output_path(args, "", "obj"),
+            mesh.to_obj(&mtl_name).into_bytes(),
+        ));
+        files.push((mtl, mesh.to_mtl().into_bytes()));
+    }
+
+    if args.outputs.to_gltf {
+        files.push((output_path(args, "", "gltf"), mesh.to_gltf().into_bytes()));
+    }
+
+    for (path, contents) in files {
+        write_output(&path, contents)?;
+    }
+
+    return Ok(());
+}
+
+fn style(args: &Args) -> Style {
+    let resolution = args.style.resolution.unwrap();
+    let mut style = Style::new(
+        args.style.cell_width.unwrap_or(resolution),
+        args.style.cell_height.unwrap_or(resolution),
+    )
+    .with_theme(args.style.theme);
+    style.wall_thickness = args.style.wall_thickness.max(1);
+
+    if let Some(wall_color) = args.style.wall_color {
+        style.wall_color = wall_color;
+    }
+
+    if let Some(bg_color) = args.style.bg_color {
+        style.background = bg_color;
+    }
+
+    if let Some(gradient) = args.style.gradient {
+        style.gradient = gradient;
+    }
+
+    return style;
 }
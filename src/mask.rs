@@ -1,13 +1,22 @@
 use std::fs;
 
+use rand::{rngs::StdRng, SeedableRng};
+
 use crate::prelude::*;
+use crate::svg::Outline;
+
+// how many times the cells of a noise mask are smoothed towards their neighbors.
+const NOISE_ROUNDS: usize = 4;
 
+/// Mask : Which cells of a `width` by `height` grid a maze may use, row by row.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Mask {
     pub mask: Vec<bool>,
     pub width: usize,
     pub height: usize,
 }
 
+/// Maskable : A grid that can be built from a mask, leaving out the cells it excludes.
 pub trait Maskable {
     fn mask(&mut self, mask: &Mask);
     fn from_mask(mask: &Mask) -> Self;
@@ -26,31 +35,429 @@ impl Mask {
         self.mask[point.x as usize + point.y as usize * self.width] = value;
     }
 
-    pub fn from_txt(file_path: &str) -> Result<Mask, std::io::Error> {
+    pub fn get(&self, point: Point) -> bool {
+        let inside =
+            (0..self.width as i32).contains(&point.x) && (0..self.height as i32).contains(&point.y);
+
+        return inside && self.mask[point.x as usize + point.y as usize * self.width];
+    }
+
+    // the groups of enabled cells joined up through their north, south, east and west
+    // sides, largest first. A maze can only reach every cell of a mask with one region.
+    pub fn regions(&self) -> Vec<Vec<Point>> {
+        let mut seen = vec![false; self.mask.len()];
+        let mut regions = Vec::new();
+
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let start = Point::new(x, y);
+                let index = x as usize + y as usize * self.width;
+
+                if !self.mask[index] || seen[index] {
+                    continue;
+                }
+
+                seen[index] = true;
+                let mut region = vec![start];
+                let mut next = 0;
+
+                while next < region.len() {
+                    let point = region[next];
+                    next += 1;
+
+                    for neighbor in [point.north(), point.south(), point.east(), point.west()] {
+                        if !self.get(neighbor) {
+                            continue;
+                        }
+
+                        let index = neighbor.x as usize + neighbor.y as usize * self.width;
+
+                        if !seen[index] {
+                            seen[index] = true;
+                            region.push(neighbor);
+                        }
+                    }
+                }
+
+                regions.push(region);
+            }
+        }
+
+        regions.sort_by_key(|region| std::cmp::Reverse(region.len()));
+
+        return regions;
+    }
+
+    // the mask with only its largest region left, so stray cells cut off from the rest, as
+    // specks in an image often are, don't become fragments of maze no one can reach.
+    pub fn remove_orphans(&self) -> Mask {
+        let mut mask = Mask::new(self.width, self.height).invert();
+
+        for &point in self.regions().first().into_iter().flatten() {
+            mask.set(point, true);
+        }
+
+        return mask;
+    }
+
+    // the mask with every region joined to the largest one, by enabling the fewest cells
+    // between them along the way. Regions are joined one at a time, the nearest first.
+    pub fn bridge_regions(&self) -> Mask {
+        let mut mask = self.clone();
+        let index = |point: Point| point.x as usize + point.y as usize * self.width;
+
+        loop {
+            let regions = mask.regions();
+
+            if regions.len() < 2 {
+                return mask;
+            }
+
+            // a search out from the largest region in which stepping over a left out cell
+            // costs one and stepping over an enabled one costs nothing, so the first enabled
+            // cell of another region it reaches is behind the fewest left out cells.
+            let mut cost = vec![usize::MAX; mask.mask.len()];
+            let mut previous = vec![None; mask.mask.len()];
+            let mut queue = std::collections::VecDeque::new();
+
+            for &point in regions[0].iter() {
+                cost[index(point)] = 0;
+                queue.push_back(point);
+            }
+
+            let mut reached = None;
+
+            while let Some(point) = queue.pop_front() {
+                if mask.get(point) && cost[index(point)] > 0 {
+                    reached = Some(point);
+                    break;
+                }
+
+                for neighbor in [point.north(), point.south(), point.east(), point.west()] {
+                    let inside = (0..self.width as i32).contains(&neighbor.x)
+                        && (0..self.height as i32).contains(&neighbor.y);
+
+                    if !inside {
+                        continue;
+                    }
+
+                    let step = if mask.get(neighbor) { 0 } else { 1 };
+                    let next = cost[index(point)] + step;
+
+                    if next < cost[index(neighbor)] {
+                        cost[index(neighbor)] = next;
+                        previous[index(neighbor)] = Some(point);
+
+                        if step == 0 {
+                            queue.push_front(neighbor);
+                        } else {
+                            queue.push_back(neighbor);
+                        }
+                    }
+                }
+            }
+
+            let mut point = match reached {
+                Some(point) => point,
+                None => return mask,
+            };
+
+            while let Some(back) = previous[index(point)] {
+                mask.set(back, true);
+                point = back;
+            }
+        }
+    }
+
+    // the same mask with every cell flipped, so the maze takes up the cells it left out.
+    pub fn invert(&self) -> Mask {
+        return Mask {
+            mask: self.mask.iter().map(|&cell| !cell).collect(),
+            width: self.width,
+            height: self.height,
+        };
+    }
+
+    // the cells enabled in this mask or in `other`. Masks are combined cell by cell from
+    // their top left corners, keeping this mask's size, and cells outside `other` count as
+    // left out of it.
+    pub fn union(&self, other: &Mask) -> Mask {
+        return self.combine(other, |a, b| a || b);
+    }
+
+    // the cells enabled in both this mask and `other`.
+    pub fn intersect(&self, other: &Mask) -> Mask {
+        return self.combine(other, |a, b| a && b);
+    }
+
+    // the cells enabled in this mask but not in `other`.
+    pub fn subtract(&self, other: &Mask) -> Mask {
+        return self.combine(other, |a, b| a && !b);
+    }
+
+    fn combine(&self, other: &Mask, keep: impl Fn(bool, bool) -> bool) -> Mask {
+        let mut mask = self.clone();
+
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let point = Point::new(x, y);
+                mask.set(point, keep(self.get(point), other.get(point)));
+            }
+        }
+
+        return mask;
+    }
+
+    // a disc `diameter` cells across.
+    pub fn circle(diameter: usize) -> Mask {
+        return Mask::from_fn(diameter, |x, y| x * x + y * y <= 1.0);
+    }
+
+    // a disc `outer` cells across with a hole `inner` cells across in its middle.
+    pub fn ring(outer: usize, inner: usize) -> Mask {
+        let hole = inner as f64 / outer.max(1) as f64;
+
+        return Mask::from_fn(outer, |x, y| {
+            let distance = x * x + y * y;
+            distance <= 1.0 && distance > hole * hole
+        });
+    }
+
+    // a star with `points` points, `size` cells across, pointing up.
+    pub fn star(size: usize, points: usize) -> Mask {
+        let points = points.max(2);
+        let corners = (0..points * 2)
+            .map(|i| {
+                let angle = std::f64::consts::PI * i as f64 / points as f64;
+                // the inner corners sit halfway out, which keeps the points wide enough to walk.
+                let radius = if i % 2 == 0 { 1.0 } else { 0.5 };
+
+                (radius * angle.sin(), -radius * angle.cos())
+            })
+            .collect::<Vec<(f64, f64)>>();
+
+        let star = Mask::from_fn(size, |x, y| {
+            let mut inside = false;
+
+            for (i, &(x0, y0)) in corners.iter().enumerate() {
+                let (x1, y1) = corners[(i + 1) % corners.len()];
+
+                if (y0 <= y) != (y1 <= y) && x < x0 + (y - y0) / (y1 - y0) * (x1 - x0) {
+                    inside = !inside;
+                }
+            }
+
+            inside
+        });
+
+        // the tips of the points can be cut off from the rest by a cell or two, and are left
+        // out rather than left stranded.
+        return star.remove_orphans();
+    }
+
+    // a heart `size` cells across.
+    pub fn heart(size: usize) -> Mask {
+        return Mask::from_fn(size, |x, y| {
+            // the curve (x² + y² - 1)³ = x²y³, which runs from about -1.14 to 1.14 across
+            // and -1 to 1.25 up, scaled to fit and turned the right way up.
+            let (x, y) = (x * 1.2, 0.1 - y * 1.2);
+            let r = x * x + y * y - 1.0;
+
+            r * r * r <= x * x * y * y * y
+        });
+    }
+
+    // a cave-like `width` by `height` mask of rounded blobs. About `fill` of the cells start
+    // out enabled at random, then each is smoothed over a few rounds to match most of its
+    // eight neighbors, cells beyond the edge counting as left out. Only the largest blob is
+    // kept. The same seed always gives the same mask.
+    pub fn noise(width: usize, height: usize, seed: u64, fill: f64) -> Mask {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut mask = Mask::new(width, height);
+
+        for cell in mask.mask.iter_mut() {
+            *cell = rng.gen_bool(fill.clamp(0.0, 1.0));
+        }
+
+        for _ in 0..NOISE_ROUNDS {
+            let before = mask.clone();
+
+            for y in 0..height as i32 {
+                for x in 0..width as i32 {
+                    let enabled = (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| Point::new(x + dx, y + dy)))
+                        .filter(|&point| point != Point::new(x, y) && before.get(point))
+                        .count();
+
+                    if enabled > 4 {
+                        mask.set(Point::new(x, y), true);
+                    } else if enabled < 4 {
+                        mask.set(Point::new(x, y), false);
+                    }
+                }
+            }
+        }
+
+        return mask.remove_orphans();
+    }
+
+    // a `size` by `size` mask with the cells enabled that `inside` holds for, given the
+    // middle of each cell scaled so the mask runs from -1 to 1 each way.
+    fn from_fn(size: usize, inside: impl Fn(f64, f64) -> bool) -> Mask {
+        let mut mask = Mask::new(size, size);
+
+        for y in 0..size {
+            for x in 0..size {
+                let scale = |i: usize| (i as f64 + 0.5) / size as f64 * 2.0 - 1.0;
+                mask.set(Point::new(x as i32, y as i32), inside(scale(x), scale(y)));
+            }
+        }
+
+        return mask;
+    }
+
+    // the mask stretched or shrunk to `width` by `height` cells. Each new cell covers a patch
+    // of the old ones, and is enabled when at least half of that patch is. Growing a mask
+    // just repeats each cell.
+    pub fn resize(&self, width: usize, height: usize) -> Mask {
+        let mut mask = Mask::new(width, height);
+        // the old cells a new cell at `i` of `count` covers, out of `total`.
+        let span = |i: usize, count: usize, total: usize| {
+            let start = i * total / count;
+            start..((i + 1) * total).div_ceil(count).max(start + 1)
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let (xs, ys) = (span(x, width, self.width), span(y, height, self.height));
+                let area = xs.len() * ys.len();
+                let enabled = ys
+                    .flat_map(|sy| xs.clone().map(move |sx| Point::new(sx as i32, sy as i32)))
+                    .filter(|&point| self.get(point))
+                    .count();
+
+                mask.set(Point::new(x as i32, y as i32), enabled * 2 >= area);
+            }
+        }
+
+        log::debug!("resized a mask to {}", mask.summary());
+
+        return mask;
+    }
+
+    // the mask's size and how many of its cells are enabled, for logging.
+    fn summary(&self) -> String {
+        return format!(
+            "{} by {} with {} cells enabled",
+            self.width,
+            self.height,
+            self.mask.iter().filter(|&&cell| cell).count()
+        );
+    }
+
+    pub fn from_txt(file_path: &str) -> Result<Mask, MazeError> {
         let data = fs::read_to_string(file_path)?;
+
+        return Mask::from_text(&data);
+    }
+
+    // a mask written out as text, its width and height on the first line and then a line
+    // of . for each cell the maze may use and x for each it can't.
+    pub fn from_text(data: &str) -> Result<Mask, MazeError> {
+        let invalid = |line: usize, message: String| MazeError::InvalidMask { line, message };
         let mut lines = data.lines();
 
-        let mut coords = lines.next().unwrap().split_whitespace();
-        let width = coords.next().unwrap().parse::<usize>().unwrap();
-        let height = coords.next().unwrap().parse::<usize>().unwrap();
+        let mut coords = lines.next().unwrap_or_default().split_whitespace();
+        let mut size = |name: &str| {
+            coords
+                .next()
+                .and_then(|value| value.parse::<usize>().ok())
+                .ok_or_else(|| invalid(1, format!("expected the mask's {} first", name)))
+        };
+        let width = size("width")?;
+        let height = size("height")?;
 
         let mut mask = Mask::new(width, height);
 
         for (y, line) in lines.enumerate() {
             for (x, c) in line.chars().enumerate() {
+                if x >= width || y >= height {
+                    return Err(invalid(
+                        y + 2,
+                        format!("the mask is only {} by {} cells", width, height),
+                    ));
+                }
+
                 match c {
                     '.' => mask.set(Point::new(x as i32, y as i32), true),
                     'x' => mask.set(Point::new(x as i32, y as i32), false),
-                    _ => panic!("Invalid character in mask file"),
+                    _ => return Err(invalid(y + 2, format!("'{}' isn't . or x", c))),
                 }
             }
         }
 
+        log::debug!("read a text mask, {}", mask.summary());
+
         return Ok(mask);
     }
 
-    pub fn from_png(file_path: &str) -> Result<Mask, ImageError> {
-        let img = open(file_path)?;
+    // a mask of the shapes filled in an SVG drawing, `width` cells across and, unless it's
+    // given, as many down as keeps the drawing's proportions. A cell is enabled when its
+    // middle falls inside a shape.
+    pub fn from_svg(svg: &str, width: usize, height: Option<usize>) -> Result<Mask, MazeError> {
+        let outline = Outline::parse(svg).map_err(MazeError::InvalidSvg)?;
+        let (left, top, view_width, view_height) = outline.view;
+        let height = height
+            .unwrap_or_else(|| ((width as f64 * view_height / view_width).round() as usize).max(1));
+
+        let mut mask = Mask::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let inside = outline.contains(
+                    left + (x as f64 + 0.5) * view_width / width as f64,
+                    top + (y as f64 + 0.5) * view_height / height as f64,
+                );
+
+                mask.set(Point::new(x as i32, y as i32), inside);
+            }
+        }
+
+        log::debug!("read an SVG mask, {}", mask.summary());
+
+        return Ok(mask);
+    }
+
+    // the mask written out in the form `from_text` reads.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{} {}\n", self.width, self.height);
+
+        for row in self.mask.chunks(self.width.max(1)) {
+            text.extend(row.iter().map(|&cell| if cell { '.' } else { 'x' }));
+            text.push('\n');
+        }
+
+        return text;
+    }
+
+    // the mask as an image with a pixel for each cell, white where the maze may go and black
+    // where it can't, in the form `from_image` reads.
+    pub fn to_image(&self) -> RgbImage {
+        return RgbImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            if self.get(Point::new(x as i32, y as i32)) {
+                WHITE
+            } else {
+                BLACK
+            }
+        });
+    }
+
+    pub fn from_png(file_path: &str) -> Result<Mask, MazeError> {
+        return Ok(Mask::from_image(&open(file_path)?));
+    }
+
+    // a mask that leaves out the cells of black pixels.
+    pub fn from_image(img: &DynamicImage) -> Mask {
         let rgb_img = img.to_rgb8();
         let (width, height) = rgb_img.dimensions();
 
@@ -64,7 +471,178 @@ impl Mask {
             }
         }
 
-        return Ok(mask);
+        log::debug!("read an image mask, {}", mask.summary());
+
+        return mask;
     }
+
+    // a mask that leaves out the cells of pixels darker than `threshold`, by luminance, so
+    // photos and anti-aliased drawings don't have to be pure black and white. With `dither`,
+    // each pixel's distance from black or white is spread over its neighbours, Floyd-Steinberg
+    // style, so shades of gray come out as a scattering of cells rather than a hard edge.
+    pub fn from_luminance(img: &DynamicImage, threshold: u8, dither: bool) -> Mask {
+        let luma = img.to_luma8();
+        let (width, height) = (luma.width() as usize, luma.height() as usize);
+        let mut levels = luma.pixels().map(|p| p[0] as f64).collect::<Vec<f64>>();
+        let mut mask = Mask::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let level = levels[y * width + x];
+                let light = level >= threshold as f64;
+                mask.set(Point::new(x as i32, y as i32), light);
+
+                if !dither {
+                    continue;
+                }
+
+                let error = level - if light { 255.0 } else { 0.0 };
+                let spread = [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)];
+
+                for (dx, dy, share) in spread {
+                    let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+
+                    if nx >= 0 && (nx as usize) < width && (ny as usize) < height {
+                        levels[ny as usize * width + nx as usize] += error * share / 16.0;
+                    }
+                }
+            }
+        }
+
+        log::debug!(
+            "read an image mask at threshold {}{}, {}",
+            threshold,
+            if dither { ", dithered" } else { "" },
+            mask.summary()
+        );
+
+        return mask;
+    }
+}
+
+// reads a mask size given as width x height, like 60x40, or as just a width.
+pub fn parse_mask_scale(value: &str) -> Result<(usize, Option<usize>), String> {
+    let (width, height) = match value.split_once(['x', 'X']) {
+        Some((width, height)) => (width, Some(height)),
+        None => (value, None),
+    };
+    let count = |text: &str| {
+        text.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|&count| count > 0)
+            .ok_or(format!("'{}' is not a number of cells", text))
+    };
+
+    return Ok((count(width)?, height.map(count).transpose()?));
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(mask: &str) -> Mask {
+        return Mask::from_text(mask).unwrap();
+    }
+
+    #[test]
+    fn reads_the_shapes_of_an_svg() {
+        let svg = r#"<svg viewBox="0 0 10 10"><rect x="0" y="0" width="5" height="10"/></svg>"#;
+
+        assert_eq!(
+            Mask::from_svg(svg, 4, None).unwrap().to_text(),
+            "4 4\n..xx\n..xx\n..xx\n..xx\n"
+        );
+
+        let svg = r#"<svg viewBox="0 0 10 10"><circle cx="5" cy="5" r="4"/></svg>"#;
+        let mask = Mask::from_svg(svg, 5, Some(5)).unwrap();
+
+        assert!(mask.get(Point::new(2, 2)));
+        assert!(!mask.get(Point::new(0, 0)));
+    }
+
+    #[test]
+    fn rejects_bad_svgs() {
+        for svg in [
+            "",
+            "not a drawing",
+            r#"<svg viewBox="0 0 10 10"></svg>"#,
+            r#"<svg viewBox="0 0 10 10"><rect width="4" height="4" fill="none"/></svg>"#,
+            r#"<svg viewBox="0 0 10 10"><path d="0 0 4 4"/></svg>"#,
+            r#"<svg viewBox="0 0 10 10"><path d="M 0 0 L 4"/></svg>"#,
+            r#"<svg viewBox="0 0 10 10"><path d="M 0 0 ? 4 4"/></svg>"#,
+        ] {
+            assert!(
+                matches!(Mask::from_svg(svg, 4, None), Err(MazeError::InvalidSvg(_))),
+                "{}",
+                svg
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_text_masks() {
+        for (text, line) in [
+            ("", 1),
+            ("3", 1),
+            ("2 1\n.y\n", 2),
+            ("2 1\n...\n", 2),
+            ("1 1\n.\nx\n", 3),
+        ] {
+            assert!(matches!(
+                Mask::from_text(text),
+                Err(MazeError::InvalidMask { line: found, .. }) if found == line
+            ));
+        }
+    }
+
+    #[test]
+    fn inverts_every_cell() {
+        let mask = text("3 2\n.x.\nxx.\n");
+
+        assert_eq!(mask.invert(), text("3 2\nx.x\n..x\n"));
+        assert_eq!(mask.invert().invert(), mask);
+    }
+
+    #[test]
+    fn combines_masks_cell_by_cell() {
+        let (a, b) = (text("4 1\n..xx\n"), text("4 1\n.x.x\n"));
+
+        assert_eq!(a.union(&b), text("4 1\n...x\n"));
+        assert_eq!(a.intersect(&b), text("4 1\n.xxx\n"));
+        assert_eq!(a.subtract(&b), text("4 1\nx.xx\n"));
+    }
+
+    // cells beyond the other mask count as left out of it, and the first mask's size is kept.
+    #[test]
+    fn combines_masks_of_other_sizes() {
+        let (a, b) = (text("3 2\n...\n...\n"), text("2 1\n..\n"));
+
+        assert_eq!(a.union(&b), a);
+        assert_eq!(a.intersect(&b), text("3 2\n..x\nxxx\n"));
+        assert_eq!(a.subtract(&b), text("3 2\nxx.\n...\n"));
+        assert_eq!(b.union(&a), b);
+    }
+
+    #[test]
+    fn resizes_by_the_share_of_each_patch_enabled() {
+        let mask = text("4 4\n..xx\n..xx\n..x.\n..xx\n");
+
+        assert_eq!(mask.resize(2, 2), text("2 2\n.x\n.x\n"));
+        // a patch half enabled stays enabled.
+        assert_eq!(text("2 1\n.x\n").resize(1, 1), text("1 1\n.\n"));
+    }
+
+    #[test]
+    fn grows_by_repeating_cells() {
+        assert_eq!(text("2 1\n.x\n").resize(4, 2), text("4 2\n..xx\n..xx\n"));
+    }
+
+    #[test]
+    fn parses_mask_scales() {
+        assert_eq!(parse_mask_scale("60x40"), Ok((60, Some(40))));
+        assert_eq!(parse_mask_scale("60"), Ok((60, None)));
+        assert!(parse_mask_scale("0").is_err());
+        assert!(parse_mask_scale("ax3").is_err());
+    }
+}
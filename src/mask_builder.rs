@@ -0,0 +1,315 @@
+use crate::prelude::*;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaskShape {
+    Circle,
+    // a circle with a hole in the middle, `inner` cells across.
+    Ring,
+    // a star with `points` points.
+    Star,
+    Heart,
+    // cave-like blobs, `width` by `height`, made from the mask's seed.
+    Noise,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaskRepair {
+    // leave out every region but the largest.
+    Remove,
+    // enable the fewest cells that join every region to the largest.
+    Bridge,
+}
+
+// MaskSource : Where the cells of a mask come from before it's resized and combined.
+#[derive(Debug, Clone, PartialEq)]
+enum MaskSource {
+    Size,
+    Text(String),
+    Image(String),
+    Svg(String),
+    Shape(MaskShape),
+}
+
+/// MaskBuilder : The mask a maze is carved in, read from a file or made as a shape, then
+/// resized, combined with other masks, inverted and repaired in that order by `build`.
+/// Files are read from stdin when their path is -.
+///
+/// ```
+/// use rusty_mazes::prelude::{MaskBuilder, MaskShape};
+///
+/// let mask = MaskBuilder::new()
+///     .shape(MaskShape::Ring)
+///     .size(20, 20)
+///     .inner(8)
+///     .invert()
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!((mask.width, mask.height), (20, 20));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskBuilder {
+    source: MaskSource,
+    width: usize,
+    height: Option<usize>,
+    seed: u64,
+    noise_fill: f64,
+    inner: Option<usize>,
+    points: usize,
+    scale: Option<(usize, Option<usize>)>,
+    threshold: Option<u8>,
+    dither: bool,
+    unions: Vec<String>,
+    intersections: Vec<String>,
+    subtractions: Vec<String>,
+    invert: bool,
+    repair: Option<MaskRepair>,
+}
+
+impl Default for MaskBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MaskBuilder {
+    // every cell of a GRID_WIDTH by GRID_HEIGHT grid.
+    pub fn new() -> Self {
+        Self {
+            source: MaskSource::Size,
+            width: GRID_WIDTH,
+            height: None,
+            seed: 0,
+            noise_fill: 0.55,
+            inner: None,
+            points: 5,
+            scale: None,
+            threshold: None,
+            dither: false,
+            unions: Vec::new(),
+            intersections: Vec::new(),
+            subtractions: Vec::new(),
+            invert: false,
+            repair: None,
+        }
+    }
+
+    // how big a shape or a mask of every cell is, and how many cells an SVG drawing is
+    // traced onto.
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = Some(height);
+        return self;
+    }
+
+    // only the width, leaving an SVG drawing as tall as its proportions make it and any
+    // other mask GRID_HEIGHT tall.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        return self;
+    }
+
+    // a text mask of . and x characters.
+    pub fn text(mut self, path: &str) -> Self {
+        self.source = MaskSource::Text(path.to_string());
+        return self;
+    }
+
+    // an image, leaving out the cells of its black pixels unless `threshold` or `dither`
+    // says otherwise.
+    pub fn image(mut self, path: &str) -> Self {
+        self.source = MaskSource::Image(path.to_string());
+        return self;
+    }
+
+    // an SVG drawing, using the cells inside its shapes.
+    pub fn svg(mut self, path: &str) -> Self {
+        self.source = MaskSource::Svg(path.to_string());
+        return self;
+    }
+
+    pub fn shape(mut self, shape: MaskShape) -> Self {
+        self.source = MaskSource::Shape(shape);
+        return self;
+    }
+
+    // the seed a noise shape grows from.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        return self;
+    }
+
+    // the share of a noise shape's cells that start out used.
+    pub fn noise_fill(mut self, fill: f64) -> Self {
+        self.noise_fill = fill;
+        return self;
+    }
+
+    // how many cells across the hole of a ring is, half of it by default.
+    pub fn inner(mut self, inner: usize) -> Self {
+        self.inner = Some(inner);
+        return self;
+    }
+
+    pub fn points(mut self, points: usize) -> Self {
+        self.points = points;
+        return self;
+    }
+
+    // resizes the mask once it's read, keeping its proportions when `height` isn't given.
+    pub fn scale(mut self, width: usize, height: Option<usize>) -> Self {
+        self.scale = Some((width, height));
+        return self;
+    }
+
+    // leaves out the cells of image masks darker than this luminance.
+    pub fn threshold(mut self, threshold: u8) -> Self {
+        self.threshold = Some(threshold);
+        return self;
+    }
+
+    // dithers image masks by their luminance rather than cutting them off at the threshold.
+    pub fn dither(mut self) -> Self {
+        self.dither = true;
+        return self;
+    }
+
+    // also uses the cells of the mask at `path`. Masks to combine are read as text, SVG or
+    // an image by their extension and brought to the size of the mask they're combined with.
+    pub fn union(mut self, path: &str) -> Self {
+        self.unions.push(path.to_string());
+        return self;
+    }
+
+    // only keeps the cells also in the mask at `path`.
+    pub fn intersect(mut self, path: &str) -> Self {
+        self.intersections.push(path.to_string());
+        return self;
+    }
+
+    // leaves out the cells of the mask at `path`.
+    pub fn subtract(mut self, path: &str) -> Self {
+        self.subtractions.push(path.to_string());
+        return self;
+    }
+
+    // swaps the cells used for those left out, once the masks are combined.
+    pub fn invert(mut self) -> Self {
+        self.invert = true;
+        return self;
+    }
+
+    // joins up the regions of the finished mask.
+    pub fn repair(mut self, repair: MaskRepair) -> Self {
+        self.repair = Some(repair);
+        return self;
+    }
+
+    pub fn build(&self) -> Result<Mask, MazeError> {
+        let width = self.width.max(1);
+        let height = self.height.unwrap_or(GRID_HEIGHT).max(1);
+
+        let mut mask = match &self.source {
+            MaskSource::Size => Mask::new(width, height),
+            MaskSource::Text(path) => read_mask(path, false)?,
+            MaskSource::Image(path) => self.read_image(path)?,
+            MaskSource::Svg(path) => {
+                let svg = read_input(path)?;
+
+                Mask::from_svg(&String::from_utf8_lossy(&svg), width, self.height)?
+            }
+            MaskSource::Shape(shape) => match shape {
+                MaskShape::Circle => Mask::circle(width),
+                MaskShape::Ring => Mask::ring(width, self.inner.unwrap_or(width / 2)),
+                MaskShape::Star => Mask::star(width, self.points),
+                MaskShape::Heart => Mask::heart(width),
+                MaskShape::Noise => Mask::noise(width, height, self.seed, self.noise_fill),
+            },
+        };
+
+        if let Some((width, height)) = self.scale {
+            let height = height.unwrap_or_else(|| {
+                let height = width as f64 * mask.height as f64 / mask.width.max(1) as f64;
+                (height.round() as usize).max(1)
+            });
+
+            mask = mask.resize(width, height);
+        }
+
+        for path in self.unions.iter() {
+            mask = mask.union(&self.read_other(path, &mask)?);
+        }
+
+        for path in self.intersections.iter() {
+            mask = mask.intersect(&self.read_other(path, &mask)?);
+        }
+
+        for path in self.subtractions.iter() {
+            mask = mask.subtract(&self.read_other(path, &mask)?);
+        }
+
+        if self.invert {
+            mask = mask.invert();
+        }
+
+        mask = match self.repair {
+            Some(MaskRepair::Remove) => mask.remove_orphans(),
+            Some(MaskRepair::Bridge) => mask.bridge_regions(),
+            None => mask,
+        };
+
+        return Ok(mask);
+    }
+
+    // an image mask, read by luminance when a threshold or dithering is asked for.
+    fn read_image(&self, path: &str) -> Result<Mask, MazeError> {
+        if self.threshold.is_none() && !self.dither {
+            return read_mask(path, true);
+        }
+
+        let image = image::load_from_memory(&read_input(path)?)?;
+        let threshold = self.threshold.unwrap_or(128);
+
+        return Ok(Mask::from_luminance(&image, threshold, self.dither));
+    }
+
+    // a mask to combine with `base`, read as text, SVG or an image by the file's extension
+    // and brought to the size of `base` so the two line up.
+    fn read_other(&self, path: &str, base: &Mask) -> Result<Mask, MazeError> {
+        let extension = Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+
+        let mask = match extension.as_deref() {
+            Some("txt") => read_mask(path, false)?,
+            Some("svg") => {
+                let svg = read_input(path)?;
+
+                return Mask::from_svg(
+                    &String::from_utf8_lossy(&svg),
+                    base.width,
+                    Some(base.height),
+                );
+            }
+            _ => self.read_image(path)?,
+        };
+
+        if (mask.width, mask.height) != (base.width, base.height) {
+            return Ok(mask.resize(base.width, base.height));
+        }
+
+        return Ok(mask);
+    }
+}
+
+// a text mask, or a mask image when `image` is set, read from the file at `path` or from
+// stdin when `path` is -.
+fn read_mask(path: &str, image: bool) -> Result<Mask, MazeError> {
+    let bytes = read_input(path)?;
+
+    if image {
+        return Ok(Mask::from_image(&image::load_from_memory(&bytes)?));
+    }
+
+    return Mask::from_text(&String::from_utf8_lossy(&bytes));
+}
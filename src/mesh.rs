@@ -0,0 +1,374 @@
+// Material : The part of the maze a face belongs to, so each part can be given its own look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Material {
+    Wall,
+    Floor,
+}
+
+impl Material {
+    pub const ALL: [Material; 2] = [Material::Wall, Material::Floor];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Material::Wall => "wall",
+            Material::Floor => "floor",
+        }
+    }
+
+    // the diffuse color, each channel between 0 and 1.
+    pub fn color(&self) -> [f32; 3] {
+        match self {
+            Material::Wall => [0.25, 0.25, 0.28],
+            Material::Floor => [0.9, 0.9, 0.85],
+        }
+    }
+}
+
+// Quad : A flat four sided face of a mesh, its corners in counter clockwise order seen
+// from the side its normal points to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quad {
+    pub corners: [[f32; 3]; 4],
+    pub normal: [f32; 3],
+    pub material: Material,
+}
+
+impl Quad {
+    // texture coordinates for the corners, projected onto the plane of the quad so a texture
+    // repeats every `tile` millimeters.
+    pub fn uvs(&self, tile: f32) -> [[f32; 2]; 4] {
+        // the axis the quad faces is dropped.
+        let (u, v) = match self.normal.map(f32::abs) {
+            [x, _, _] if x > 0.5 => (1, 2),
+            [_, y, _] if y > 0.5 => (0, 2),
+            _ => (0, 1),
+        };
+
+        return self
+            .corners
+            .map(|corner| [corner[u] / tile, corner[v] / tile]);
+    }
+}
+
+// Mesh : A 3D model of a maze made of quads, measured in millimeters with z pointing up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    pub quads: Vec<Quad>,
+    // the size, in millimeters, a texture is stretched over.
+    pub tile: f32,
+}
+
+// MeshOptions : The sizes, in millimeters, a maze is built to when made into a model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshOptions {
+    pub cell_size: f32,
+    pub wall_thickness: f32,
+    pub wall_height: f32,
+    pub base: f32,
+}
+
+// MeshBuffers : The triangles of a mesh that share a material, as the vertex and index
+// buffers a game engine or GPU takes. Measured in meters with y pointing up, the
+// conventions of glTF and of engines like Bevy, so each buffer can be handed over as is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshBuffers {
+    pub material: Material,
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    // three to a triangle, counter clockwise seen from the front.
+    pub indices: Vec<u32>,
+}
+
+// OBJ and glTF files expect y to point up, which keeps the model's winding.
+fn y_up(point: [f32; 3]) -> [f32; 3] {
+    return [point[0], point[2], -point[1]];
+}
+
+impl Mesh {
+    pub fn new(tile: f32) -> Self {
+        Self {
+            quads: Vec::new(),
+            tile,
+        }
+    }
+
+    pub fn add_quad(&mut self, corners: [[f32; 3]; 4], normal: [f32; 3], material: Material) {
+        self.quads.push(Quad {
+            corners,
+            normal,
+            material,
+        });
+    }
+
+    // every quad split into two triangles, keeping their winding.
+    pub fn triangles(&self) -> Vec<([[f32; 3]; 3], [f32; 3])> {
+        return self
+            .quads
+            .iter()
+            .flat_map(|quad| {
+                let [a, b, c, d] = quad.corners;
+                [([a, b, c], quad.normal), ([a, c, d], quad.normal)]
+            })
+            .collect();
+    }
+
+    // the mesh as a binary STL file, the format most slicers for 3D printers read.
+    pub fn to_stl(&self) -> Vec<u8> {
+        let triangles = self.triangles();
+        let mut output = Vec::with_capacity(84 + triangles.len() * 50);
+
+        let mut header = b"rusty_mazes".to_vec();
+        header.resize(80, 0);
+        output.extend(header);
+        output.extend((triangles.len() as u32).to_le_bytes());
+
+        for (corners, normal) in triangles {
+            for value in normal.iter().chain(corners.iter().flatten()) {
+                output.extend(value.to_le_bytes());
+            }
+
+            // the attribute byte count, which nothing uses.
+            output.extend(0u16.to_le_bytes());
+        }
+
+        return output;
+    }
+
+    // the mesh as a Wavefront OBJ file, its materials read from the file named `mtl`.
+    pub fn to_obj(&self, mtl: &str) -> String {
+        let mut output = format!("# rusty_mazes\nmtllib {}\n", mtl);
+        let mut faces = String::new();
+        let mut written = 0;
+
+        for material in Material::ALL {
+            faces.push_str(&format!("usemtl {}\n", material.name()));
+
+            for quad in self.quads.iter().filter(|quad| quad.material == material) {
+                for (corner, uv) in quad.corners.iter().zip(quad.uvs(self.tile)) {
+                    let [x, y, z] = y_up(*corner);
+                    output.push_str(&format!("v {} {} {}\nvt {} {}\n", x, y, z, uv[0], uv[1]));
+                }
+
+                let [x, y, z] = y_up(quad.normal);
+                output.push_str(&format!("vn {} {} {}\n", x, y, z));
+
+                // OBJ counts from 1, and every quad adds four vertices and a normal.
+                written += 1;
+                let corners = (4 * written - 3..=4 * written)
+                    .map(|i| format!("{}/{}/{}", i, i, written))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                faces.push_str(&format!("f {}\n", corners));
+            }
+        }
+
+        return output + &faces;
+    }
+
+    // the materials an OBJ file from `to_obj` uses.
+    pub fn to_mtl(&self) -> String {
+        return Material::ALL
+            .iter()
+            .map(|material| {
+                let [r, g, b] = material.color();
+                format!("newmtl {}\nKd {} {} {}\n", material.name(), r, g, b)
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+
+    // the quads of each material that has any, split into triangles sharing their corners.
+    pub fn buffers(&self) -> Vec<MeshBuffers> {
+        let mut buffers = Vec::new();
+
+        for material in Material::ALL {
+            let quads = self
+                .quads
+                .iter()
+                .filter(|quad| quad.material == material)
+                .collect::<Vec<&Quad>>();
+
+            if quads.is_empty() {
+                continue;
+            }
+
+            buffers.push(MeshBuffers {
+                material,
+                positions: quads
+                    .iter()
+                    .flat_map(|quad| quad.corners.map(|corner| y_up(corner).map(|c| c / 1000.0)))
+                    .collect(),
+                normals: quads
+                    .iter()
+                    .flat_map(|quad| [y_up(quad.normal); 4])
+                    .collect(),
+                uvs: quads.iter().flat_map(|quad| quad.uvs(self.tile)).collect(),
+                indices: (0..quads.len() as u32)
+                    .flat_map(|quad| [0, 1, 2, 0, 2, 3].map(|corner| 4 * quad + corner))
+                    .collect(),
+            });
+        }
+
+        return buffers;
+    }
+
+    // the mesh as a glTF file with its data embedded, one primitive for each material.
+    // glTF measures in meters.
+    pub fn to_gltf(&self) -> String {
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut views = Vec::new();
+        let mut accessors = Vec::new();
+        let mut primitives = Vec::new();
+
+        for buffers in self.buffers() {
+            let MeshBuffers {
+                material,
+                positions,
+                normals,
+                uvs,
+                indices,
+            } = buffers;
+            let index = Material::ALL.iter().position(|m| *m == material).unwrap();
+
+            let mut min = [f32::MAX; 3];
+            let mut max = [f32::MIN; 3];
+
+            for position in positions.iter() {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(position[axis]);
+                    max[axis] = max[axis].max(position[axis]);
+                }
+            }
+
+            let first = accessors.len();
+            let mut add_view = |bytes: Vec<u8>, target: u32, accessor: String| {
+                views.push(format!(
+                    "{{\"buffer\": 0, \"byteOffset\": {}, \"byteLength\": {}, \"target\": {}}}",
+                    buffer.len(),
+                    bytes.len(),
+                    target
+                ));
+                accessors.push(format!(
+                    "{{\"bufferView\": {}, {}}}",
+                    views.len() - 1,
+                    accessor
+                ));
+                buffer.extend(bytes);
+            };
+
+            add_view(
+                positions
+                    .iter()
+                    .flatten()
+                    .flat_map(|c| c.to_le_bytes())
+                    .collect(),
+                34962,
+                format!(
+                    "\"componentType\": 5126, \"count\": {}, \"type\": \"VEC3\", \
+                     \"min\": [{}, {}, {}], \"max\": [{}, {}, {}]",
+                    positions.len(),
+                    min[0],
+                    min[1],
+                    min[2],
+                    max[0],
+                    max[1],
+                    max[2]
+                ),
+            );
+            add_view(
+                normals
+                    .iter()
+                    .flatten()
+                    .flat_map(|c| c.to_le_bytes())
+                    .collect(),
+                34962,
+                format!(
+                    "\"componentType\": 5126, \"count\": {}, \"type\": \"VEC3\"",
+                    normals.len()
+                ),
+            );
+            add_view(
+                uvs.iter().flatten().flat_map(|c| c.to_le_bytes()).collect(),
+                34962,
+                format!(
+                    "\"componentType\": 5126, \"count\": {}, \"type\": \"VEC2\"",
+                    uvs.len()
+                ),
+            );
+            add_view(
+                indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+                34963,
+                format!(
+                    "\"componentType\": 5125, \"count\": {}, \"type\": \"SCALAR\"",
+                    indices.len()
+                ),
+            );
+
+            primitives.push(format!(
+                "{{\"attributes\": {{\"POSITION\": {}, \"NORMAL\": {}, \"TEXCOORD_0\": {}}}, \
+                 \"indices\": {}, \"material\": {}}}",
+                first,
+                first + 1,
+                first + 2,
+                first + 3,
+                index
+            ));
+        }
+
+        let materials = Material::ALL
+            .iter()
+            .map(|material| {
+                let [r, g, b] = material.color();
+                format!(
+                    "{{\"name\": \"{}\", \"pbrMetallicRoughness\": {{\"baseColorFactor\": \
+                     [{}, {}, {}, 1], \"metallicFactor\": 0, \"roughnessFactor\": 1}}}}",
+                    material.name(),
+                    r,
+                    g,
+                    b
+                )
+            })
+            .collect::<Vec<String>>();
+
+        return format!(
+            "{{\n  \"asset\": {{\"version\": \"2.0\", \"generator\": \"rusty_mazes\"}},\n  \
+             \"scene\": 0,\n  \"scenes\": [{{\"nodes\": [0]}}],\n  \
+             \"nodes\": [{{\"name\": \"maze\", \"mesh\": 0}}],\n  \
+             \"meshes\": [{{\"primitives\": [\n    {}\n  ]}}],\n  \
+             \"materials\": [\n    {}\n  ],\n  \
+             \"accessors\": [\n    {}\n  ],\n  \
+             \"bufferViews\": [\n    {}\n  ],\n  \
+             \"buffers\": [{{\"byteLength\": {}, \
+             \"uri\": \"data:application/octet-stream;base64,{}\"}}]\n}}\n",
+            primitives.join(",\n    "),
+            materials.join(",\n    "),
+            accessors.join(",\n    "),
+            views.join(",\n    "),
+            buffer.len(),
+            base64(&buffer)
+        );
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(group >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    return output;
+}
@@ -0,0 +1,66 @@
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression};
+
+// Nbt : A tag of Minecraft's Named Binary Tag format, just the kinds structure files use.
+// Compounds keep their fields in the order they're given.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Nbt {
+    Int(i32),
+    String(String),
+    List(Vec<Nbt>),
+    Compound(Vec<(String, Nbt)>),
+}
+
+impl Nbt {
+    fn id(&self) -> u8 {
+        match self {
+            Nbt::Int(_) => 3,
+            Nbt::String(_) => 8,
+            Nbt::List(_) => 9,
+            Nbt::Compound(_) => 10,
+        }
+    }
+
+    fn write_string(output: &mut Vec<u8>, text: &str) {
+        output.extend((text.len() as u16).to_be_bytes());
+        output.extend(text.as_bytes());
+    }
+
+    fn write_payload(&self, output: &mut Vec<u8>) {
+        match self {
+            Nbt::Int(value) => output.extend(value.to_be_bytes()),
+            Nbt::String(text) => Nbt::write_string(output, text),
+            Nbt::List(items) => {
+                // an empty list is given the type of the end tag.
+                output.push(items.first().map_or(0, Nbt::id));
+                output.extend((items.len() as i32).to_be_bytes());
+
+                for item in items {
+                    item.write_payload(output);
+                }
+            }
+            Nbt::Compound(fields) => {
+                for (name, value) in fields {
+                    output.push(value.id());
+                    Nbt::write_string(output, name);
+                    value.write_payload(output);
+                }
+
+                output.push(0);
+            }
+        }
+    }
+
+    // the tag as the unnamed root of a gzipped NBT file.
+    pub fn to_file(&self) -> Vec<u8> {
+        let mut output = vec![self.id()];
+        Nbt::write_string(&mut output, "");
+        self.write_payload(&mut output);
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&output).unwrap();
+
+        return encoder.finish().unwrap();
+    }
+}
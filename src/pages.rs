@@ -0,0 +1,120 @@
+use crate::prelude::*;
+
+// the dashes marking overlaps are this many pixels long, with gaps as long between them.
+const DASH: u32 = 4;
+
+// Pages : How a maze too large for one sheet is split into `columns` by `rows` images to
+// print. Neighboring pages share `overlap` pixels, with the edge of the neighbor marked on
+// each, so the printed sheets can be lined up and taped together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pages {
+    pub columns: u32,
+    pub rows: u32,
+    pub overlap: u32,
+}
+
+// reads pages given as columns x rows, like 3x2.
+pub fn parse_pages(value: &str) -> Result<(u32, u32), String> {
+    let (columns, rows) = value.split_once(['x', 'X']).ok_or(format!(
+        "'{}' is not given as columns x rows, like 3x2",
+        value
+    ))?;
+    let count = |text: &str| {
+        text.trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|&count| count > 0)
+            .ok_or(format!("'{}' is not a number of pages", text))
+    };
+
+    return Ok((count(columns)?, count(rows)?));
+}
+
+// the start and length of each of `count` stretches covering `total` pixels, each sharing
+// `overlap` pixels with the next.
+fn spans(total: u32, count: u32, overlap: u32) -> Vec<(u32, u32)> {
+    let count = count.clamp(1, total.max(1));
+    let size = (total + (count - 1) * overlap).div_ceil(count);
+    let overlap = overlap.min(size.saturating_sub(1));
+
+    return (0..count)
+        .map(|i| i * (size - overlap))
+        .take_while(|&start| start < total)
+        .map(|start| (start, size.min(total - start)))
+        .collect();
+}
+
+impl Pages {
+    // the windows of a `width` by `height` image each page shows, as x, y, width and height,
+    // row by row.
+    pub fn windows(&self, width: u32, height: u32) -> Vec<Vec<(u32, u32, u32, u32)>> {
+        let columns = spans(width, self.columns, self.overlap);
+
+        return spans(height, self.rows, self.overlap)
+            .into_iter()
+            .map(|(y, page_height)| {
+                columns
+                    .iter()
+                    .map(|&(x, page_width)| (x, y, page_width, page_height))
+                    .collect()
+            })
+            .collect();
+    }
+
+    // each page of `grid` drawn in `style`, row by row.
+    pub fn render(&self, grid: &dyn Drawable, style: &Style) -> Vec<Vec<RgbImage>> {
+        let (width, height) = grid.image_size(style);
+        let windows = self.windows(width, height);
+        let mut pages = Vec::new();
+
+        for (r, row) in windows.iter().enumerate() {
+            let mut row_pages = Vec::new();
+
+            for (c, &(x, y, page_width, page_height)) in row.iter().enumerate() {
+                let mut page = grid.to_window_image(style, x, y, page_width, page_height);
+
+                // the last column the page before shares, and the first the page after does.
+                // Pages that don't overlap leave these outside of the page, and unmarked.
+                if c > 0 {
+                    let (before_x, _, before_width, _) = row[c - 1];
+                    mark(
+                        &mut page,
+                        (before_x + before_width).checked_sub(x + 1),
+                        None,
+                    );
+                }
+
+                if let Some(&(after_x, _, _, _)) = row.get(c + 1) {
+                    mark(&mut page, Some(after_x - x), None);
+                }
+
+                if r > 0 {
+                    let (_, above_y, _, above_height) = windows[r - 1][c];
+                    mark(&mut page, None, (above_y + above_height).checked_sub(y + 1));
+                }
+
+                if let Some(&(_, below_y, _, _)) = windows.get(r + 1).map(|below| &below[c]) {
+                    mark(&mut page, None, Some(below_y - y));
+                }
+
+                row_pages.push(page);
+            }
+
+            pages.push(row_pages);
+        }
+
+        return pages;
+    }
+}
+
+// dashes down `column` or across `row` of the page.
+fn mark(page: &mut RgbImage, column: Option<u32>, row: Option<u32>) {
+    for (x, y, pixel) in page.enumerate_pixels_mut() {
+        let on_column = column == Some(x) && (y / DASH).is_multiple_of(2);
+        let on_row = row == Some(y) && (x / DASH).is_multiple_of(2);
+
+        if on_column || on_row {
+            *pixel = MARK;
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::{collections::HashMap, hash::Hash};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// writes a map as a list of its keys and values in the order of its keys, as formats such as
+// JSON only take strings as keys and points aren't. Used with `#[serde(with = "crate::pairs")]`.
+pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize + Ord,
+    V: Serialize,
+    S: Serializer,
+{
+    let mut pairs = map.iter().collect::<Vec<(&K, &V)>>();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+
+    return pairs.serialize(serializer);
+}
+
+pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let pairs = Vec::<(K, V)>::deserialize(deserializer)?;
+
+    return Ok(pairs.into_iter().collect());
+}
@@ -0,0 +1,118 @@
+use crate::prelude::*;
+
+// millimeters to PDF points, which are 1/72 of an inch.
+pub const POINTS_PER_MM: f64 = 72.0 / 25.4;
+
+impl PageSize {
+    // the width and height of the page in points, portrait.
+    pub fn dimensions(&self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (595.28, 841.89),
+            PageSize::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+// Pdf : A bare PDF document of pages drawn with vector operators, just enough to print
+// mazes without a PDF library. Page contents use PDF's own coordinates, in points from the
+// bottom left corner of the page.
+pub struct Pdf {
+    pub width: f64,
+    pub height: f64,
+    pages: Vec<String>,
+}
+
+impl Pdf {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            pages: Vec::new(),
+        }
+    }
+
+    pub fn add_page(&mut self, content: String) {
+        self.pages.push(content);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // the catalog and page tree come first, then each page followed by its contents.
+        let kids = (0..self.pages.len())
+            .map(|i| format!("{} 0 R", 3 + 2 * i))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let mut objects = vec![
+            String::from("<< /Type /Catalog /Pages 2 0 R >>"),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {:.2} {:.2}] >>",
+                kids,
+                self.pages.len(),
+                self.width,
+                self.height
+            ),
+        ];
+
+        for (i, content) in self.pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /Contents {} 0 R >>",
+                4 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                content.len(),
+                content
+            ));
+        }
+
+        let mut output = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(output.len());
+            output.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+
+        let xref = output.len();
+        output.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+
+        for offset in offsets {
+            output.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+
+        output.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+
+        return output.into_bytes();
+    }
+}
+
+// the PDF operators stroking each of `lines` in `color`, `width` points wide.
+pub fn pdf_stroke(lines: &[Vec<(f64, f64)>], color: Rgb<u8>, width: f64) -> String {
+    let Rgb([r, g, b]) = color;
+    let mut content = format!(
+        "{:.3} {:.3} {:.3} RG {:.2} w 1 J 1 j\n",
+        r as f64 / 255.0,
+        g as f64 / 255.0,
+        b as f64 / 255.0,
+        width
+    );
+
+    for line in lines.iter().filter(|line| line.len() >= 2) {
+        content.push_str(&format!("{:.2} {:.2} m\n", line[0].0, line[0].1));
+
+        for &(x, y) in line.iter().skip(1) {
+            content.push_str(&format!("{:.2} {:.2} l\n", x, y));
+        }
+    }
+
+    content.push_str("S\n");
+
+    return content;
+}
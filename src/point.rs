@@ -1,5 +1,7 @@
-use std::ops::{Add, Sub};
-use crate::prelude::*;
+use std::{
+    ops::{Add, Sub},
+    str::FromStr,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
@@ -9,15 +11,24 @@ pub enum Direction {
     West,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+// Point : The position of a cell. Most grids are flat and leave z at 0, grids that stack
+// cells on top of each other use it as the layer a cell lives on.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
 pub struct Point {
     pub x: i32,
     pub y: i32,
+    pub z: i32,
 }
 
 impl Point {
     pub fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
+        Self { x, y, z: 0 }
+    }
+
+    pub fn new_3d(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
     }
 
     pub fn zero() -> Self {
@@ -25,27 +36,37 @@ impl Point {
     }
 
     pub fn north(&self) -> Self {
-        Self::new(self.x, self.y - 1)
+        Self::new_3d(self.x, self.y - 1, self.z)
     }
 
     pub fn south(&self) -> Self {
-        Self::new(self.x, self.y + 1)
+        Self::new_3d(self.x, self.y + 1, self.z)
     }
 
     pub fn east(&self) -> Self {
-        Self::new(self.x + 1, self.y)
+        Self::new_3d(self.x + 1, self.y, self.z)
     }
 
     pub fn west(&self) -> Self {
-        Self::new(self.x - 1, self.y)
+        Self::new_3d(self.x - 1, self.y, self.z)
     }
 
     pub fn in_direction(direction: Direction) -> Self {
         match direction {
-            Direction::North => Point::new(0, 1),
-            Direction::South => Point::new(0, -1),
+            Direction::North => Point::new(0, -1),
+            Direction::South => Point::new(0, 1),
             Direction::East => Point::new(1, 0),
-            Direction::West => Point::new(-1, 0), 
+            Direction::West => Point::new(-1, 0),
+        }
+    }
+
+    pub fn direction_to(&self, other: Point) -> Option<Direction> {
+        match (other.x - self.x, other.y - self.y, other.z - self.z) {
+            (0, -1, 0) => Some(Direction::North),
+            (1, 0, 0) => Some(Direction::East),
+            (0, 1, 0) => Some(Direction::South),
+            (-1, 0, 0) => Some(Direction::West),
+            _ => None,
         }
     }
 }
@@ -54,7 +75,7 @@ impl Add for Point {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self::new(self.x + other.x, self.y + other.y)
+        Self::new_3d(self.x + other.x, self.y + other.y, self.z + other.z)
     }
 }
 
@@ -62,6 +83,69 @@ impl Sub for Point {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self::new(self.x - other.x, self.y - other.y)
+        Self::new_3d(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+// Rect : A rectangular region of a grid, `width` cells across and `height` cells down from
+// the cell at (x, y).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn origin(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        return point.x >= self.x
+            && point.y >= self.y
+            && point.x < self.x + self.width as i32
+            && point.y < self.y + self.height as i32;
+    }
+}
+
+impl FromStr for Rect {
+    type Err = String;
+
+    // parses a region such as "4,2,10,8", given as x,y,width,height.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s.split(',').map(|v| v.trim()).collect::<Vec<&str>>();
+
+        if values.len() != 4 {
+            return Err(format!(
+                "Invalid region '{}', expected <x>,<y>,<width>,<height>",
+                s
+            ));
+        }
+
+        let x = values[0]
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid x '{}'", values[0]))?;
+        let y = values[1]
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid y '{}'", values[1]))?;
+        let width = values[2]
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid width '{}'", values[2]))?;
+        let height = values[3]
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid height '{}'", values[3]))?;
+
+        return Ok(Rect::new(x, y, width, height));
     }
 }
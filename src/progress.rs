@@ -0,0 +1,28 @@
+use std::cell::RefCell;
+
+// a callback given the cells carved so far and the cells there are to carve.
+type Watcher = Box<dyn FnMut(usize, usize)>;
+
+thread_local! {
+    static WATCHER: RefCell<Option<Watcher>> = RefCell::new(None);
+}
+
+// calls `watcher` as the slow algorithms, Aldous-Broder and Wilson's, carve each cell of
+// a maze on this thread, with the cells carved so far and the total. It replaces any
+// watcher there was before.
+pub fn watch(watcher: impl FnMut(usize, usize) + 'static) {
+    WATCHER.with(|current| *current.borrow_mut() = Some(Box::new(watcher)));
+}
+
+// stops reporting progress to the watcher given to `watch`.
+pub fn stop() {
+    WATCHER.with(|current| *current.borrow_mut() = None);
+}
+
+pub(crate) fn report(carved: usize, total: usize) {
+    WATCHER.with(|current| {
+        if let Some(watcher) = current.borrow_mut().as_mut() {
+            watcher(carved, total);
+        }
+    });
+}
@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+thread_local! {
+    static SEEDED: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+// SeededRng : A handle to the thread's random number generator, which is seeded once so
+// the same seed always carves the same maze. Used anywhere `rand::thread_rng` would be.
+#[derive(Debug, Clone, Copy)]
+pub struct SeededRng;
+
+// restarts the generator from `seed`, every maze made after this point follows from it.
+pub fn seed(seed: u64) {
+    SEEDED.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+// a fresh seed for runs that weren't given one, so they can still be reported and replayed.
+pub fn random_seed() -> u64 {
+    return rand::thread_rng().next_u64();
+}
+
+pub fn rng() -> SeededRng {
+    return SeededRng;
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        return SEEDED.with(|rng| rng.borrow_mut().next_u32());
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        return SEEDED.with(|rng| rng.borrow_mut().next_u64());
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        SEEDED.with(|rng| rng.borrow_mut().fill_bytes(dest));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        return SEEDED.with(|rng| rng.borrow_mut().try_fill_bytes(dest));
+    }
+}
+
+// today's date in UTC, as YYYY-MM-DD.
+pub fn today() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    // the days since 1970-01-01 turned into a date, counting in 400 year eras that start
+    // on the 1st of March so leap days fall at the end of each year.
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return format!("{:04}-{:02}-{:02}", year, month, day);
+}
+
+// the seed shared by everyone making a maze on `date` within `namespace`, so a puzzle of
+// the day is the same wherever it's made. The date and namespace are hashed with FNV-1a,
+// which unlike the standard library's hasher never changes between versions.
+pub fn daily_seed(date: &str, namespace: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for byte in date.bytes().chain([0]).chain(namespace.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    return hash;
+}
@@ -0,0 +1,260 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::prelude::*;
+
+// the largest maze, in cells across or down, that will be carved for a request.
+pub const MAX_SERVED_SIZE: usize = 200;
+
+// Response : What a request is answered with, its status code, the type of its body and any
+// headers beyond those every response has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: u16, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            content_type,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        return Response::new(
+            status,
+            "text/plain; charset=utf-8",
+            format!("{}\n", message),
+        );
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len()
+        );
+
+        for (name, value) in self.headers.iter() {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        head.push_str("\r\n");
+
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+
+        return bytes;
+    }
+}
+
+// answers HTTP requests on `address`, such as 127.0.0.1:8080, until the process is stopped.
+// Each connection is answered on a thread of its own.
+pub fn serve(address: &str) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    log::info!("serving mazes on http://{}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+
+        std::thread::spawn(move || {
+            // a client that hangs up early has nothing left to be sent to.
+            let _ = answer(stream);
+        });
+    }
+
+    return Ok(());
+}
+
+fn answer(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    reader.read_line(&mut request_line)?;
+
+    // the headers aren't needed, but are read so the client isn't cut off mid request.
+    let mut header = String::new();
+
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => respond(target),
+        (Some(_), Some(_)) => Response::error(405, "Only GET requests are answered."),
+        _ => Response::error(400, "The request couldn't be read."),
+    };
+
+    stream.write_all(&response.to_bytes())?;
+
+    return stream.flush();
+}
+
+// the response to a GET of `target`, a path and its query such as
+// /maze?w=40&h=30&algorithm=wilsons&format=svg.
+//
+// /maze carves a new rectangular maze, taking w and h, algorithm, seed, braid, solve,
+// resolution, theme and a format of svg, png, txt or json. The seed it was carved from is
+// sent back in the X-Maze-Seed header. /algorithms lists the algorithm names.
+pub fn respond(target: &str) -> Response {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = parse_query(query);
+
+    match path {
+        "/maze" => return maze(&query).unwrap_or_else(|message| Response::error(400, &message)),
+        "/algorithms" => {
+            let names = Algorithm::NAMES.join("\n") + "\n";
+            return Response::new(200, "text/plain; charset=utf-8", names);
+        }
+        _ => return Response::error(404, "Try /maze or /algorithms."),
+    }
+}
+
+fn maze(query: &HashMap<String, String>) -> Result<Response, String> {
+    let number = |key: &str, default: usize| match query.get(key) {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| format!("{} must be a whole number, not '{}'", key, value)),
+        None => Ok(default),
+    };
+
+    let (width, height) = (number("w", GRID_WIDTH)?, number("h", GRID_HEIGHT)?);
+
+    if !(1..=MAX_SERVED_SIZE).contains(&width) || !(1..=MAX_SERVED_SIZE).contains(&height) {
+        return Err(format!("w and h must be between 1 and {}", MAX_SERVED_SIZE));
+    }
+
+    let algorithm = query
+        .get("algorithm")
+        .map_or("recursivebacktracker", String::as_str);
+    let algorithm =
+        Algorithm::from_name(algorithm, DirectionWeights::default()).map_err(|e| e.to_string())?;
+
+    let seed = match query.get("seed") {
+        Some(seed) => seed
+            .parse::<u64>()
+            .map_err(|_| format!("seed must be a whole number, not '{}'", seed))?,
+        None => random::random_seed(),
+    };
+
+    let mut builder = MazeBuilder::new()
+        .size(width, height)
+        .algorithm(algorithm)
+        .seed(seed);
+
+    if let Some(braid) = query.get("braid") {
+        let p = braid
+            .parse::<f64>()
+            .map_err(|_| format!("braid must be a probability, not '{}'", braid))?;
+        builder = builder.braid(p);
+    }
+
+    if query.get("solve").is_some_and(|solve| solve != "false") {
+        builder = builder.solve();
+    }
+
+    let resolution = number("resolution", 16)?.clamp(2, 64);
+    let mut style = Style::new(resolution, resolution);
+
+    if let Some(theme) = query.get("theme") {
+        let theme = <Theme as clap::ValueEnum>::from_str(theme, true)
+            .map_err(|_| format!("unknown theme '{}'", theme))?;
+        style = style.with_theme(theme);
+    }
+
+    let grid = builder.build();
+    let format = query.get("format").map_or("svg", String::as_str);
+
+    let mut response = match format {
+        "svg" => Response::new(
+            200,
+            "image/svg+xml",
+            grid.to_svg(&style).unwrap_or_default(),
+        ),
+        "png" => {
+            let mut bytes = std::io::Cursor::new(Vec::new());
+            DynamicImage::from(grid.to_grid_image(&style))
+                .write_to(&mut bytes, ImageOutputFormat::Png)
+                .map_err(|e| e.to_string())?;
+
+            Response::new(200, "image/png", bytes.into_inner())
+        }
+        "txt" => Response::new(200, "text/plain; charset=utf-8", grid.to_ascii(None)),
+        "json" => Response::new(200, "application/json", grid.to_json()),
+        _ => {
+            return Err(format!(
+                "format must be svg, png, txt or json, not '{}'",
+                format
+            ))
+        }
+    };
+
+    response
+        .headers
+        .push((String::from("X-Maze-Seed"), seed.to_string()));
+
+    return Ok(response);
+}
+
+// the keys and values of a query string, with + and %XX escapes decoded.
+fn parse_query(query: &str) -> HashMap<String, String> {
+    return query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect();
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => decoded.push(b' '),
+            (byte, _) => decoded.push(byte),
+        }
+
+        i += 1;
+    }
+
+    return String::from_utf8_lossy(&decoded).into_owned();
+}
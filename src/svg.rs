@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+
+// how many straight segments each curve, and each quarter of a circle, is flattened into.
+const CURVE_SEGMENTS: usize = 16;
+
+// Shape : The outline of one filled element, as closed polygons, and whether a point inside
+// it is found by the nonzero winding rule or by the even-odd rule.
+#[derive(Debug, Clone, PartialEq)]
+struct Shape {
+    polygons: Vec<Vec<(f64, f64)>>,
+    even_odd: bool,
+}
+
+impl Shape {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        let mut winding = 0;
+
+        for polygon in self.polygons.iter() {
+            for (i, &(x0, y0)) in polygon.iter().enumerate() {
+                let (x1, y1) = polygon[(i + 1) % polygon.len()];
+
+                if (y0 <= y) == (y1 <= y) {
+                    continue;
+                }
+
+                let crossing = x0 + (y - y0) / (y1 - y0) * (x1 - x0);
+
+                if crossing > x {
+                    winding += if y1 > y0 { 1 } else { -1 };
+                }
+            }
+        }
+
+        if self.even_odd {
+            return winding % 2 != 0;
+        }
+
+        return winding != 0;
+    }
+}
+
+// Outline : The filled shapes of an SVG drawing and the area of the drawing they're laid out
+// in, its view box. Only as much of SVG as silhouettes need is read, paths, rectangles,
+// circles, ellipses and polygons, without transforms, strokes or styles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outline {
+    shapes: Vec<Shape>,
+    // the left, top, width and height of the drawing.
+    pub view: (f64, f64, f64, f64),
+}
+
+impl Outline {
+    pub fn parse(text: &str) -> Result<Outline, String> {
+        let mut shapes = Vec::new();
+        let mut view = None;
+        // shapes inside definitions, clip paths and the like aren't drawn as they are.
+        let mut hidden = 0;
+
+        for (name, attributes, closing, empty) in elements(text) {
+            if matches!(
+                name.as_str(),
+                "defs" | "clipPath" | "mask" | "symbol" | "pattern"
+            ) {
+                if closing {
+                    hidden -= 1;
+                } else if !empty {
+                    hidden += 1;
+                }
+
+                continue;
+            }
+
+            if closing || hidden > 0 {
+                continue;
+            }
+
+            let number = |key: &str| {
+                attributes
+                    .get(key)
+                    .and_then(|value| leading_number(value))
+                    .unwrap_or(0.0)
+            };
+
+            let polygons = match name.as_str() {
+                "svg" => {
+                    let view_box = attributes.get("viewBox").map(|value| numbers(value));
+
+                    view = match view_box.as_deref() {
+                        Some(&[x, y, width, height]) => Some((x, y, width, height)),
+                        _ if number("width") > 0.0 && number("height") > 0.0 => {
+                            Some((0.0, 0.0, number("width"), number("height")))
+                        }
+                        _ => None,
+                    };
+
+                    continue;
+                }
+                "path" => path(attributes.get("d").map_or("", String::as_str))?,
+                "rect" => {
+                    let (x, y) = (number("x"), number("y"));
+                    let (width, height) = (number("width"), number("height"));
+
+                    vec![vec![
+                        (x, y),
+                        (x + width, y),
+                        (x + width, y + height),
+                        (x, y + height),
+                    ]]
+                }
+                "circle" => vec![ellipse(
+                    number("cx"),
+                    number("cy"),
+                    number("r"),
+                    number("r"),
+                )],
+                "ellipse" => vec![ellipse(
+                    number("cx"),
+                    number("cy"),
+                    number("rx"),
+                    number("ry"),
+                )],
+                "polygon" => {
+                    let points = numbers(attributes.get("points").map_or("", String::as_str));
+                    vec![points.chunks_exact(2).map(|p| (p[0], p[1])).collect()]
+                }
+                _ => continue,
+            };
+
+            if attributes.get("fill").is_some_and(|fill| fill == "none") {
+                continue;
+            }
+
+            shapes.push(Shape {
+                polygons: polygons.into_iter().filter(|p| p.len() > 2).collect(),
+                even_odd: attributes
+                    .get("fill-rule")
+                    .is_some_and(|rule| rule == "evenodd"),
+            });
+        }
+
+        if shapes.is_empty() {
+            return Err(String::from("the drawing has no filled shapes"));
+        }
+
+        // without a size of its own, the drawing is as large as its shapes.
+        let view = view.unwrap_or_else(|| {
+            let points = shapes
+                .iter()
+                .flat_map(|shape| shape.polygons.iter().flatten());
+            let (mut left, mut top) = (f64::MAX, f64::MAX);
+            let (mut right, mut bottom) = (f64::MIN, f64::MIN);
+
+            for &(x, y) in points {
+                (left, top) = (left.min(x), top.min(y));
+                (right, bottom) = (right.max(x), bottom.max(y));
+            }
+
+            (left, top, right - left, bottom - top)
+        });
+
+        return Ok(Outline { shapes, view });
+    }
+
+    // whether the point (x, y), in the drawing's units, is inside any of its shapes.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        return self.shapes.iter().any(|shape| shape.contains(x, y));
+    }
+}
+
+// the elements of an XML document in order, each with its name, its attributes, whether it
+// closes an element and whether it is empty, closing itself.
+fn elements(text: &str) -> Vec<(String, HashMap<String, String>, bool, bool)> {
+    let mut elements = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+
+        // comments, declarations and processing instructions hold no shapes.
+        if rest.starts_with("!--") {
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+
+        let closing = tag.starts_with('/');
+        let empty = tag.ends_with('/');
+        let tag = tag.trim_start_matches('/').trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let mut attributes = HashMap::new();
+        let mut attribute_text = &tag[name_end..];
+
+        while let Some(equals) = attribute_text.find('=') {
+            let key = attribute_text[..equals].trim().to_string();
+            let value_text = attribute_text[equals + 1..].trim_start();
+            let quote = match value_text.chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => break,
+            };
+            let value_end = value_text[1..]
+                .find(quote)
+                .map_or(value_text.len(), |end| end + 1);
+
+            attributes.insert(key, value_text[1..value_end].to_string());
+            attribute_text = value_text.get(value_end + 1..).unwrap_or("");
+        }
+
+        elements.push((tag[..name_end].to_string(), attributes, closing, empty));
+    }
+
+    return elements;
+}
+
+// the number a length such as "120px" starts with.
+fn leading_number(value: &str) -> Option<f64> {
+    let mut reader = Reader::new(value);
+    return reader.number();
+}
+
+// every number in a list such as "0 0 100 80" or "1,2 3,4".
+fn numbers(value: &str) -> Vec<f64> {
+    let mut reader = Reader::new(value);
+    let mut numbers = Vec::new();
+
+    while let Some(number) = reader.number() {
+        numbers.push(number);
+    }
+
+    return numbers;
+}
+
+fn ellipse(cx: f64, cy: f64, rx: f64, ry: f64) -> Vec<(f64, f64)> {
+    let steps = CURVE_SEGMENTS * 4;
+
+    return (0..steps)
+        .map(|i| {
+            let angle = i as f64 / steps as f64 * std::f64::consts::TAU;
+            (cx + rx * angle.cos(), cy + ry * angle.sin())
+        })
+        .collect();
+}
+
+// the subpaths of path data, each flattened into a polygon.
+fn path(d: &str) -> Result<Vec<Vec<(f64, f64)>>, String> {
+    let mut reader = Reader::new(d);
+    let mut polygons: Vec<Vec<(f64, f64)>> = Vec::new();
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    // the control point a smooth curve reflects, from the curve before it.
+    let mut control: Option<(f64, f64)> = None;
+    let mut command = None;
+
+    loop {
+        command = match reader.command() {
+            Some(next) => Some(next),
+            // numbers following a command repeat it, a move repeating as a line.
+            None if reader.at_number() => match command {
+                Some('M') => Some('L'),
+                Some('m') => Some('l'),
+                repeated => repeated,
+            },
+            None if reader.done() => break,
+            None => return Err(format!("unexpected path data at '{}'", reader.rest())),
+        };
+
+        let c = command.ok_or("path data must start with a command")?;
+        let relative = c.is_ascii_lowercase();
+        let (ox, oy) = if relative { current } else { (0.0, 0.0) };
+        let mut number = || {
+            reader
+                .number()
+                .ok_or(format!("'{}' is missing a number", c))
+        };
+        let mut points = Vec::new();
+        let mut next_control = None;
+
+        match c.to_ascii_uppercase() {
+            'M' => {
+                current = (ox + number()?, oy + number()?);
+                start = current;
+                polygons.push(vec![current]);
+            }
+            'L' => points.push((ox + number()?, oy + number()?)),
+            'H' => points.push((ox + number()?, current.1)),
+            'V' => points.push((current.0, oy + number()?)),
+            'C' | 'S' => {
+                let first = if c.eq_ignore_ascii_case(&'C') {
+                    (ox + number()?, oy + number()?)
+                } else {
+                    reflect(control, current)
+                };
+                let second = (ox + number()?, oy + number()?);
+                let end = (ox + number()?, oy + number()?);
+
+                points = bezier(&[current, first, second, end]);
+                next_control = Some(second);
+            }
+            'Q' | 'T' => {
+                let handle = if c.eq_ignore_ascii_case(&'Q') {
+                    (ox + number()?, oy + number()?)
+                } else {
+                    reflect(control, current)
+                };
+                let end = (ox + number()?, oy + number()?);
+
+                points = bezier(&[current, handle, end]);
+                next_control = Some(handle);
+            }
+            'A' => {
+                let (rx, ry, rotation) = (number()?, number()?, number()?);
+                let large = reader.flag().ok_or("an arc is missing its flags")?;
+                let sweep = reader.flag().ok_or("an arc is missing its flags")?;
+                let end = (
+                    ox + reader.number().ok_or("an arc is missing its end")?,
+                    oy + reader.number().ok_or("an arc is missing its end")?,
+                );
+
+                points = arc(current, (rx, ry), rotation, large, sweep, end);
+            }
+            'Z' => {
+                current = start;
+                command = None;
+            }
+            _ => return Err(format!("'{}' isn't a path command", c)),
+        }
+
+        if !points.is_empty() {
+            if polygons.is_empty() {
+                polygons.push(vec![current]);
+            }
+
+            current = *points.last().unwrap();
+            polygons.last_mut().unwrap().extend(points);
+        }
+
+        // only curves of the same kind can reflect the last curve's control point.
+        control = next_control;
+    }
+
+    return Ok(polygons);
+}
+
+fn reflect(control: Option<(f64, f64)>, current: (f64, f64)) -> (f64, f64) {
+    return control.map_or(current, |(x, y)| (2.0 * current.0 - x, 2.0 * current.1 - y));
+}
+
+// the points along a Bézier curve through `points`, after the first.
+fn bezier(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    return (1..=CURVE_SEGMENTS)
+        .map(|i| {
+            let t = i as f64 / CURVE_SEGMENTS as f64;
+            let mut points = points.to_vec();
+
+            while points.len() > 1 {
+                points = points
+                    .windows(2)
+                    .map(|pair| {
+                        (
+                            pair[0].0 + (pair[1].0 - pair[0].0) * t,
+                            pair[0].1 + (pair[1].1 - pair[0].1) * t,
+                        )
+                    })
+                    .collect();
+            }
+
+            points[0]
+        })
+        .collect();
+}
+
+// the points along an elliptical arc from `from` to `to`, after the first, found by
+// converting SVG's endpoints and flags to the arc's center and angles.
+fn arc(
+    from: (f64, f64),
+    (rx, ry): (f64, f64),
+    rotation: f64,
+    large: bool,
+    sweep: bool,
+    to: (f64, f64),
+) -> Vec<(f64, f64)> {
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+
+    if rx == 0.0 || ry == 0.0 || from == to {
+        return vec![to];
+    }
+
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let (dx, dy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+
+    // radii too small to reach are scaled up until they just do.
+    let scale = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+
+    if scale > 1.0 {
+        (rx, ry) = (rx * scale.sqrt(), ry * scale.sqrt());
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut root = (numerator / denominator).max(0.0).sqrt();
+
+    if large == sweep {
+        root = -root;
+    }
+
+    let (cx1, cy1) = (root * rx * y1 / ry, -root * ry * x1 / rx);
+    let (cx, cy) = (
+        cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+        sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+    );
+
+    let angle = |ux: f64, uy: f64| uy.atan2(ux);
+    let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut span = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - start;
+
+    if sweep && span < 0.0 {
+        span += std::f64::consts::TAU;
+    } else if !sweep && span > 0.0 {
+        span -= std::f64::consts::TAU;
+    }
+
+    return (1..=CURVE_SEGMENTS)
+        .map(|i| {
+            let theta = start + span * i as f64 / CURVE_SEGMENTS as f64;
+            let (x, y) = (rx * theta.cos(), ry * theta.sin());
+            (cos * x - sin * y + cx, sin * x + cos * y + cy)
+        })
+        .collect();
+}
+
+// Reader : Reads the commands, numbers and flags of path data and number lists, skipping the
+// spaces and commas between them.
+struct Reader {
+    chars: Vec<char>,
+    at: usize,
+}
+
+impl Reader {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            at: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .chars
+            .get(self.at)
+            .is_some_and(|c| c.is_whitespace() || *c == ',')
+        {
+            self.at += 1;
+        }
+    }
+
+    fn done(&mut self) -> bool {
+        self.skip_separators();
+        return self.at >= self.chars.len();
+    }
+
+    fn rest(&self) -> String {
+        return self.chars[self.at..].iter().take(12).collect();
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        return self
+            .chars
+            .get(self.at)
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'));
+    }
+
+    fn command(&mut self) -> Option<char> {
+        self.skip_separators();
+        let c = *self.chars.get(self.at)?;
+
+        if c.is_ascii_alphabetic() && !matches!(c, 'e' | 'E') {
+            self.at += 1;
+            return Some(c);
+        }
+
+        return None;
+    }
+
+    // a single 0 or 1, which arcs write without separators, as in "a5 5 0 011 1".
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.chars.get(self.at)? {
+            '0' => false,
+            '1' => true,
+            _ => return None,
+        };
+        self.at += 1;
+
+        return Some(flag);
+    }
+
+    // a number such as -1.5e3. A second decimal point or a sign starts the next number, so
+    // "0.5.5" is two numbers and "1-2" is too.
+    fn number(&mut self) -> Option<f64> {
+        self.skip_separators();
+        let start = self.at;
+        let mut seen_point = false;
+        let mut seen_exponent = false;
+
+        if matches!(self.chars.get(self.at), Some('-' | '+')) {
+            self.at += 1;
+        }
+
+        while let Some(&c) = self.chars.get(self.at) {
+            if c.is_ascii_digit() {
+                self.at += 1;
+            } else if c == '.' && !seen_point && !seen_exponent {
+                seen_point = true;
+                self.at += 1;
+            } else if matches!(c, 'e' | 'E') && !seen_exponent && self.at > start {
+                seen_exponent = true;
+                self.at += 1;
+
+                if matches!(self.chars.get(self.at), Some('-' | '+')) {
+                    self.at += 1;
+                }
+            } else {
+                break;
+            }
+        }
+
+        let text = self.chars[start..self.at].iter().collect::<String>();
+        let number = text.parse::<f64>().ok();
+
+        if number.is_none() {
+            self.at = start;
+        }
+
+        return number;
+    }
+}